@@ -0,0 +1,262 @@
+//! This module contains the different chromatic adaptation transforms that Scarlet supports, for use
+//! with [`XYZColor::color_adapt_with`](../color/struct.XYZColor.html#method.color_adapt_with).
+//! Every one of these works the same way, following von Kries: convert XYZ into some space of cone
+//! responses with a matrix, scale each response by the ratio of the new white point to the old one,
+//! and convert back. They differ only in which matrix they use. Older transforms use something close
+//! to physiological cone responses, while newer ones "sharpen" the responses to better fit
+//! experimental data on corresponding colors.
+//!
+//! The degree of adaptation, D, says how much an observer adapts to the new lighting: 1 is
+//! complete adaptation, as when viewing a print in a room lit by the new light, and 0 is none at
+//! all. Partial adaptation is common when only part of the visual field is lit differently, such as
+//! when viewing a screen in a room. Scarlet leaves choosing D to the caller: CIECAM02 and CAM16 give
+//! a formula for it based on the viewing conditions.
+
+use rulinalg::matrix::decomposition::PartialPivLu;
+use rulinalg::matrix::Matrix;
+use rulinalg::vector::Vector;
+
+use color::XYZColor;
+use consts::BRADFORD_TRANSFORM as BRADFORD;
+use consts::BRADFORD_TRANSFORM_LU as BRADFORD_LU;
+use consts::CAT02_TRANSFORM as CAT02;
+use consts::CAT02_TRANSFORM_LU as CAT02_LU;
+use consts::CAT16_TRANSFORM as CAT16;
+use consts::CAT16_TRANSFORM_LU as CAT16_LU;
+use consts::VON_KRIES_TRANSFORM as VON_KRIES;
+use consts::VON_KRIES_TRANSFORM_LU as VON_KRIES_LU;
+use illuminants::Illuminant;
+
+/// A chromatic adaptation transform: a way of predicting what color, under one light, looks the same
+/// as a given color under another. The [default](#impl-Default) is Bradford, which is what
+/// [`XYZColor::color_adapt`](../color/struct.XYZColor.html#method.color_adapt) uses and what ICC
+/// color management specifies.
+///
+/// # Example
+/// Different transforms agree on white, but disagree on saturated colors.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::adaptation::ChromaticAdaptation;
+/// # use scarlet::color::XYZColor;
+/// let white = XYZColor::white_point(Illuminant::D65);
+/// let blue = XYZColor{x: 0.2, y: 0.1, z: 0.9, illuminant: Illuminant::D65};
+/// for &method in &[ChromaticAdaptation::XYZScaling, ChromaticAdaptation::CAT16] {
+///     let adapted = white.color_adapt_with(Illuminant::D50, method, 1.0);
+///     assert!(adapted.approx_equal(&XYZColor::white_point(Illuminant::D50)));
+/// }
+/// let bradford = blue.color_adapt_with(Illuminant::D50, ChromaticAdaptation::Bradford, 1.0);
+/// let scaling = blue.color_adapt_with(Illuminant::D50, ChromaticAdaptation::XYZScaling, 1.0);
+/// assert!(bradford.distance(&scaling) > 1.0);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ChromaticAdaptation {
+    /// Scales X, Y, and Z directly, without converting to cone responses at all. This is the
+    /// simplest transform, and the least accurate: it's mostly useful for comparing against other
+    /// software that uses it.
+    XYZScaling,
+    /// The original von Kries transform, using the Hunt-Pointer-Estévez cone fundamentals normalized
+    /// to D65.
+    VonKries,
+    /// The Bradford transform, from Lam (1985), as used by ICC profiles. This is Scarlet's default.
+    Bradford,
+    /// The transform from CIECAM02.
+    CAT02,
+    /// The transform from CAM16, which fixes some problems CAT02 has with highly saturated colors.
+    CAT16,
+    /// A custom transform, given as the rows of the matrix that converts XYZ to cone responses. The
+    /// matrix must be invertible.
+    Custom([[f64; 3]; 3]),
+}
+
+impl Default for ChromaticAdaptation {
+    /// The Bradford transform.
+    fn default() -> ChromaticAdaptation {
+        ChromaticAdaptation::Bradford
+    }
+}
+
+impl ChromaticAdaptation {
+    /// Adapts the given color from its illuminant to the given one, with degree of adaptation
+    /// `degree`.
+    pub(crate) fn adapt(&self, xyz: &XYZColor, illuminant: Illuminant, degree: f64) -> XYZColor {
+        let xyz_vec = vector![xyz.x, xyz.y, xyz.z];
+        let w = Vector::from(xyz.illuminant.white_point().to_vec());
+        let wr = Vector::from(illuminant.white_point().to_vec());
+        // &* needed because lazy_static uses a different type which implements Deref
+        let adapted = match *self {
+            ChromaticAdaptation::XYZScaling => von_kries(xyz_vec, &w, &wr, degree),
+            ChromaticAdaptation::VonKries => {
+                cone_adapt(&VON_KRIES, &VON_KRIES_LU, xyz_vec, w, wr, degree)
+            }
+            ChromaticAdaptation::Bradford => {
+                cone_adapt(&BRADFORD, &BRADFORD_LU, xyz_vec, w, wr, degree)
+            }
+            ChromaticAdaptation::CAT02 => cone_adapt(&CAT02, &CAT02_LU, xyz_vec, w, wr, degree),
+            ChromaticAdaptation::CAT16 => cone_adapt(&CAT16, &CAT16_LU, xyz_vec, w, wr, degree),
+            ChromaticAdaptation::Custom(rows) => {
+                let matrix = Matrix::new(
+                    3,
+                    3,
+                    rows.iter().flat_map(|r| r.to_vec()).collect::<Vec<f64>>(),
+                );
+                let lu = PartialPivLu::decompose(matrix.clone()).expect("Matrix is invertible.");
+                cone_adapt(&matrix, &lu, xyz_vec, w, wr, degree)
+            }
+        };
+        XYZColor {
+            x: adapted[0],
+            y: adapted[1],
+            z: adapted[2],
+            illuminant,
+        }
+    }
+}
+
+/// Converts to cone responses using the given matrix, adapts them, and converts back using the LU
+/// decomposition of the same matrix for accuracy.
+fn cone_adapt(
+    matrix: &Matrix<f64>,
+    lu: &PartialPivLu<f64>,
+    xyz: Vector<f64>,
+    white: Vector<f64>,
+    white_ref: Vector<f64>,
+    degree: f64,
+) -> Vector<f64> {
+    let lms = von_kries(
+        matrix * xyz,
+        &(matrix * white),
+        &(matrix * white_ref),
+        degree,
+    );
+    lu.solve(lms).expect("Matrix is invertible.")
+}
+
+/// Scales each response by the ratio of the white points, blending with no change at all according
+/// to the degree of adaptation. Because each white point has already been normalized to Y = 1,
+/// there's no need for a factor for luminance.
+fn von_kries(
+    lms: Vector<f64>,
+    white: &Vector<f64>,
+    white_ref: &Vector<f64>,
+    degree: f64,
+) -> Vector<f64> {
+    let adapt = |i: usize| degree * (lms[i] * white_ref[i] / white[i]) + (1.0 - degree) * lms[i];
+    vector![adapt(0), adapt(1), adapt(2)]
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    const METHODS: [ChromaticAdaptation; 5] = [
+        ChromaticAdaptation::XYZScaling,
+        ChromaticAdaptation::VonKries,
+        ChromaticAdaptation::Bradford,
+        ChromaticAdaptation::CAT02,
+        ChromaticAdaptation::CAT16,
+    ];
+
+    #[test]
+    fn test_white_point_preserved() {
+        let lights = [
+            Illuminant::D50,
+            Illuminant::D55,
+            Illuminant::D65,
+            Illuminant::D75,
+        ];
+        for &method in METHODS.iter() {
+            for &from in lights.iter() {
+                for &to in lights.iter() {
+                    let white = XYZColor::white_point(from).color_adapt_with(to, method, 1.0);
+                    assert!(white.approx_equal(&XYZColor::white_point(to)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bradford_matches_color_adapt() {
+        let color = XYZColor {
+            x: 0.3,
+            y: 0.5,
+            z: 0.2,
+            illuminant: Illuminant::D65,
+        };
+        let default = color.color_adapt_with(Illuminant::D50, ChromaticAdaptation::default(), 1.0);
+        assert_eq!(default, color.color_adapt(Illuminant::D50));
+        // Bruce Lindbloom's Bradford matrix from D65 to D50, applied to the color
+        let expected = [
+            1.0478112 * 0.3 + 0.0228866 * 0.5 - 0.0501270 * 0.2,
+            0.0295424 * 0.3 + 0.9904844 * 0.5 - 0.0170491 * 0.2,
+            -0.0092345 * 0.3 + 0.0150436 * 0.5 + 0.7521316 * 0.2,
+        ];
+        assert!((default.x - expected[0]).abs() <= 1e-4);
+        assert!((default.y - expected[1]).abs() <= 1e-4);
+        assert!((default.z - expected[2]).abs() <= 1e-4);
+    }
+
+    #[test]
+    fn test_partial_adaptation() {
+        let color = XYZColor {
+            x: 0.2,
+            y: 0.1,
+            z: 0.9,
+            illuminant: Illuminant::D65,
+        };
+        for &method in METHODS.iter() {
+            let none = color.color_adapt_with(Illuminant::D50, method, 0.0);
+            assert!((none.x - color.x).abs() <= 1e-10);
+            assert!((none.y - color.y).abs() <= 1e-10);
+            assert!((none.z - color.z).abs() <= 1e-10);
+            assert_eq!(none.illuminant, Illuminant::D50);
+            let full = color.color_adapt_with(Illuminant::D50, method, 1.0);
+            let half = color.color_adapt_with(Illuminant::D50, method, 0.5);
+            // the responses are linear in D, so halfway is exactly the midpoint
+            assert!((half.z - (full.z + color.z) / 2.0).abs() <= 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let color = XYZColor {
+            x: 0.4,
+            y: 0.3,
+            z: 0.5,
+            illuminant: Illuminant::D50,
+        };
+        for &method in METHODS.iter() {
+            let there = color.color_adapt_with(Illuminant::D75, method, 1.0);
+            let back = there.color_adapt_with(Illuminant::D50, method, 1.0);
+            assert!(back.approx_equal(&color));
+        }
+    }
+
+    #[test]
+    fn test_custom_matrix() {
+        let identity =
+            ChromaticAdaptation::Custom([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+        let bradford = ChromaticAdaptation::Custom([
+            [0.8951, 0.2664, -0.1614],
+            [-0.7502, 1.7135, 0.0367],
+            [0.0389, -0.0685, 1.0296],
+        ]);
+        let color = XYZColor {
+            x: 0.6,
+            y: 0.2,
+            z: 0.1,
+            illuminant: Illuminant::D65,
+        };
+        assert!(color
+            .color_adapt_with(Illuminant::D55, identity, 1.0)
+            .approx_equal(&color.color_adapt_with(
+                Illuminant::D55,
+                ChromaticAdaptation::XYZScaling,
+                1.0
+            )));
+        assert!(color
+            .color_adapt_with(Illuminant::D55, bradford, 1.0)
+            .approx_equal(&color.color_adapt(Illuminant::D55)));
+    }
+}
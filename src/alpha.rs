@@ -0,0 +1,398 @@
+//! This module adds transparency to Scarlet through [`Alpha`](alpha::Alpha), a wrapper around any
+//! [`Color`] that adds an alpha channel. Alpha isn't a property of a color as such: it's a property
+//! of a color on a screen, describing how much it covers whatever is behind it. As such, no color
+//! space in Scarlet has a native notion of it, but wrapping a color in `Alpha` lets you carry it
+//! around, parse it from CSS strings like `rgba(...)` or `#rrggbbaa`, and format it back out again.
+//!
+//! There are two standard ways of storing alpha. *Straight* alpha, which [`Alpha`](alpha::Alpha)
+//! uses, stores the color as it would look if it were fully opaque. *Premultiplied* alpha, which
+//! [`PremultipliedAlpha`](alpha::PremultipliedAlpha) uses, stores each component already multiplied
+//! by the alpha value, which is how most compositing is actually done. Converting between them is
+//! lossless except for fully transparent colors, which have no color information when
+//! premultiplied.
+//!
+//! [`Color`]: ../color/trait.Color.html
+
+use std::fmt;
+use std::str::FromStr;
+
+use color::{Color, RGBColor, RGBParseError, XYZColor};
+use colorpoint::ColorPoint;
+use colors::hslcolor::HSLColor;
+use colors::hsvcolor::HSVColor;
+use coord::Coord;
+use csscolor::{parse_hsla_hsva_tuple, parse_rgba_str, CSSFormat, CSSParseError, ToCSS};
+use illuminants::Illuminant;
+
+/// A color with straight (not premultiplied) alpha, where an alpha of 1 is fully opaque and an
+/// alpha of 0 is fully transparent. This implements [`Color`] by simply passing conversions through
+/// to the wrapped color: converting to another color space discards the alpha, and converting from
+/// another color space gives a fully opaque color. To convert the color and keep the alpha, use
+/// [`convert_color`](#method.convert_color).
+/// # Example
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::alpha::Alpha;
+/// # use scarlet::colors::HSLColor;
+/// let translucent_red: Alpha<RGBColor> = "rgba(255, 0, 0, 0.5)".parse().unwrap();
+/// assert_eq!(translucent_red.alpha, 0.5);
+/// assert_eq!(translucent_red.color.to_string(), "#FF0000");
+/// // the color can be converted without losing the alpha
+/// let hsla: Alpha<HSLColor> = translucent_red.convert_color();
+/// assert_eq!(hsla.alpha, 0.5);
+/// // and formatted back to an 8-digit hex code
+/// assert_eq!(translucent_red.to_string(), "#FF000080");
+/// ```
+///
+/// [`Color`]: ../color/trait.Color.html
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alpha<C: Color> {
+    /// The color, as it would appear if it were fully opaque.
+    pub color: C,
+    /// The alpha value, ranging from 0 (fully transparent) to 1 (fully opaque).
+    pub alpha: f64,
+}
+
+impl<C: Color> Alpha<C> {
+    /// Creates a new color with the given alpha value.
+    pub fn new(color: C, alpha: f64) -> Alpha<C> {
+        Alpha { color, alpha }
+    }
+    /// Creates a fully opaque color, with an alpha of 1.
+    pub fn opaque(color: C) -> Alpha<C> {
+        Alpha { color, alpha: 1.0 }
+    }
+    /// Converts the wrapped color to a different color space, keeping the alpha value.
+    pub fn convert_color<T: Color>(&self) -> Alpha<T> {
+        Alpha {
+            color: self.color.convert(),
+            alpha: self.alpha,
+        }
+    }
+}
+
+impl<C: ColorPoint> Alpha<C> {
+    /// Converts to premultiplied alpha by multiplying each component of the color by the alpha
+    /// value. This only really makes sense for RGB spaces, where each component describes an amount
+    /// of light.
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::prelude::*;
+    /// # use scarlet::alpha::Alpha;
+    /// let color = Alpha::new(RGBColor{r: 1., g: 0.5, b: 0.}, 0.5);
+    /// let premul = color.premultiply();
+    /// assert_eq!(premul.color, RGBColor{r: 0.5, g: 0.25, b: 0.});
+    /// assert_eq!(premul.unpremultiply(), color);
+    /// ```
+    pub fn premultiply(&self) -> PremultipliedAlpha<C> {
+        let coord: Coord = self.color.into();
+        PremultipliedAlpha {
+            color: C::from(coord * self.alpha),
+            alpha: self.alpha,
+        }
+    }
+}
+
+impl<C: Color> Color for Alpha<C> {
+    /// Converts from XYZ to a fully opaque color.
+    fn from_xyz(xyz: XYZColor) -> Alpha<C> {
+        Alpha::opaque(C::from_xyz(xyz))
+    }
+    /// Converts the color to XYZ, ignoring the alpha.
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        self.color.to_xyz(illuminant)
+    }
+    // the default implementations of these convert back from XYZ, which would reset the alpha: pass
+    // them on to the color instead
+    fn set_hue(&mut self, new_hue: f64) {
+        self.color.set_hue(new_hue);
+    }
+    fn set_lightness(&mut self, new_lightness: f64) {
+        self.color.set_lightness(new_lightness);
+    }
+    fn set_chroma(&mut self, new_chroma: f64) {
+        self.color.set_chroma(new_chroma);
+    }
+    fn set_saturation(&mut self, new_sat: f64) {
+        self.color.set_saturation(new_sat);
+    }
+    fn grayscale(&self) -> Alpha<C> {
+        Alpha {
+            color: self.color.grayscale(),
+            alpha: self.alpha,
+        }
+    }
+}
+
+impl Alpha<RGBColor> {
+    /// Given a string that represents a hex code, returns the color it represents. Along with the
+    /// formats [`RGBColor::from_hex_code`] accepts, which are fully opaque, this accepts `"#rgba"`
+    /// as a shorthand for `"#rrggbbaa"` and `"#rrggbbaa"` itself, with the last component giving
+    /// the alpha. As before, the `#` is optional.
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::prelude::*;
+    /// # use scarlet::alpha::Alpha;
+    /// # fn try_main() -> Result<(), RGBParseError> {
+    /// let fuchsia = Alpha::<RGBColor>::from_hex_code("#ff00ff80")?;
+    /// let fuchsia2 = Alpha::<RGBColor>::from_hex_code("f0f8")?;
+    /// assert_eq!(fuchsia.color, fuchsia2.color);
+    /// assert_eq!(fuchsia.alpha, 128. / 255.);
+    /// assert_eq!(fuchsia2.alpha, 136. / 255.);
+    /// let opaque = Alpha::<RGBColor>::from_hex_code("#ff00ff")?;
+    /// assert_eq!(opaque.alpha, 1.);
+    /// # Ok(())
+    /// # }
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`RGBColor::from_hex_code`]: ../color/struct.RGBColor.html#method.from_hex_code
+    pub fn from_hex_code(hex: &str) -> Result<Alpha<RGBColor>, RGBParseError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(RGBParseError::InvalidHexSyntax);
+        }
+        // the color part is handled by the usual function: just split off the alpha
+        let (color, alpha) = match digits.len() {
+            3 | 6 => return Ok(Alpha::opaque(RGBColor::from_hex_code(digits)?)),
+            4 => (&digits[..3], digits[3..].repeat(2)),
+            8 => (&digits[..6], digits[6..].to_string()),
+            _ => return Err(RGBParseError::InvalidHexSyntax),
+        };
+        Ok(Alpha {
+            color: RGBColor::from_hex_code(color)?,
+            alpha: f64::from(u8::from_str_radix(&alpha, 16)?) / 255.0,
+        })
+    }
+    /// Gets the integer version of the alpha value, from 0 to 255, clamping if need be.
+    pub fn int_alpha(&self) -> u8 {
+        if self.alpha < 0.0 {
+            0_u8
+        } else if self.alpha > 1.0 {
+            255_u8
+        } else {
+            (self.alpha * 255.0).round() as u8
+        }
+    }
+}
+
+impl fmt::Display for Alpha<RGBColor> {
+    /// Formats the color as an 8-digit hex code, `#RRGGBBAA`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{:02X}", self.color.to_string(), self.int_alpha())
+    }
+}
+
+impl FromStr for Alpha<RGBColor> {
+    type Err = RGBParseError;
+
+    /// Parses a hex code with or without alpha, an X11 color name (including `"transparent"`), or
+    /// an `rgb()` or `rgba()` function. Colors without an alpha are fully opaque.
+    fn from_str(s: &str) -> Result<Alpha<RGBColor>, RGBParseError> {
+        if s.eq_ignore_ascii_case("transparent") {
+            return Ok(Alpha::new(RGBColor::from((0, 0, 0)), 0.0));
+        }
+        match Alpha::<RGBColor>::from_hex_code(s) {
+            Ok(rgba) => Ok(rgba),
+            Err(_e) => match RGBColor::from_color_name(s) {
+                Ok(rgb) => Ok(Alpha::opaque(rgb)),
+                Err(_e) => {
+                    let (r, g, b, alpha) = parse_rgba_str(s)?;
+                    Ok(Alpha::new(RGBColor::from((r, g, b)), alpha))
+                }
+            },
+        }
+    }
+}
+
+impl FromStr for Alpha<HSLColor> {
+    type Err = CSSParseError;
+
+    /// Parses an `hsl()` or `hsla()` function, with the alpha being optional in either.
+    fn from_str(s: &str) -> Result<Alpha<HSLColor>, CSSParseError> {
+        let tup = if s.starts_with("hsla(") {
+            &s[4..]
+        } else if s.starts_with("hsl(") {
+            &s[3..]
+        } else {
+            return Err(CSSParseError::InvalidColorSyntax);
+        };
+        let (h, s, l, alpha) = parse_hsla_hsva_tuple(tup)?;
+        Ok(Alpha::new(HSLColor { h, s, l }, alpha))
+    }
+}
+
+impl FromStr for Alpha<HSVColor> {
+    type Err = CSSParseError;
+
+    /// Parses an `hsv()` or `hsva()` function, with the alpha being optional in either.
+    fn from_str(s: &str) -> Result<Alpha<HSVColor>, CSSParseError> {
+        let tup = if s.starts_with("hsva(") {
+            &s[4..]
+        } else if s.starts_with("hsv(") {
+            &s[3..]
+        } else {
+            return Err(CSSParseError::InvalidColorSyntax);
+        };
+        let (h, s, v, alpha) = parse_hsla_hsva_tuple(tup)?;
+        Ok(Alpha::new(HSVColor { h, s, v }, alpha))
+    }
+}
+
+impl<C: ToCSS> ToCSS for Alpha<C> {
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        self.color.write_css(self.alpha * alpha, format)
+    }
+}
+
+/// A color with premultiplied alpha: each component of the color has already been multiplied by
+/// the alpha value. This is the representation most compositing math uses, because blending two
+/// premultiplied colors is a simple weighted sum. Converting this to XYZ or any other color space
+/// first divides the alpha back out.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PremultipliedAlpha<C: Color> {
+    /// The color, with each of its components multiplied by the alpha value.
+    pub color: C,
+    /// The alpha value, ranging from 0 (fully transparent) to 1 (fully opaque).
+    pub alpha: f64,
+}
+
+impl<C: ColorPoint> PremultipliedAlpha<C> {
+    /// Converts back to straight alpha by dividing each component of the color by the alpha value.
+    /// A fully transparent color has no color information when premultiplied, so this gives a
+    /// color with every component 0 in that case.
+    pub fn unpremultiply(&self) -> Alpha<C> {
+        let coord: Coord = self.color.into();
+        let color = if self.alpha == 0.0 {
+            C::from(Coord {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            })
+        } else {
+            C::from(coord / self.alpha)
+        };
+        Alpha {
+            color,
+            alpha: self.alpha,
+        }
+    }
+}
+
+impl<C: ColorPoint> Color for PremultipliedAlpha<C> {
+    /// Converts from XYZ to a fully opaque color, for which premultiplying changes nothing.
+    fn from_xyz(xyz: XYZColor) -> PremultipliedAlpha<C> {
+        PremultipliedAlpha {
+            color: C::from_xyz(xyz),
+            alpha: 1.0,
+        }
+    }
+    /// Converts the color to XYZ, dividing out the alpha first and then ignoring it.
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        self.unpremultiply().color.to_xyz(illuminant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_hex_parsing() {
+        let rgba = Alpha::<RGBColor>::from_hex_code("#12345678").unwrap();
+        assert_eq!(rgba.color.int_rgb_tup(), (0x12, 0x34, 0x56));
+        assert_eq!(rgba.int_alpha(), 0x78);
+        let rgba = Alpha::<RGBColor>::from_hex_code("1234").unwrap();
+        assert_eq!(rgba.color.int_rgb_tup(), (0x11, 0x22, 0x33));
+        assert_eq!(rgba.int_alpha(), 0x44);
+        let rgba = Alpha::<RGBColor>::from_hex_code("#123456").unwrap();
+        assert_eq!(rgba.color.int_rgb_tup(), (0x12, 0x34, 0x56));
+        assert_eq!(rgba.alpha, 1.);
+        for bad in ["#12345", "#1234567", "#1234567g", "#", ""].iter() {
+            assert_eq!(
+                Alpha::<RGBColor>::from_hex_code(bad),
+                Err(RGBParseError::InvalidHexSyntax)
+            );
+        }
+    }
+    #[test]
+    fn test_to_string() {
+        for hex in ["#00000000", "#ABCDEF12", "#1A2B3CFF", "#D00A1280"].iter() {
+            assert_eq!(
+                *hex,
+                Alpha::<RGBColor>::from_hex_code(hex).unwrap().to_string()
+            );
+        }
+    }
+    #[test]
+    fn test_string_parsing() {
+        let rgba: Alpha<RGBColor> = "rgba(18, 52, 86, 0.5)".parse().unwrap();
+        assert_eq!(rgba.to_string(), "#12345680");
+        let rgba: Alpha<RGBColor> = "rgb(18, 52, 86)".parse().unwrap();
+        assert_eq!(rgba.to_string(), "#123456FF");
+        let rgba: Alpha<RGBColor> = "black".parse().unwrap();
+        assert_eq!(rgba.to_string(), "#000000FF");
+        let rgba: Alpha<RGBColor> = "transparent".parse().unwrap();
+        assert_eq!(rgba.to_string(), "#00000000");
+        assert_eq!(
+            "rgba(1, 2)".parse::<Alpha<RGBColor>>(),
+            Err(RGBParseError::InvalidFuncSyntax)
+        );
+
+        let hsla: Alpha<HSLColor> = "hsla(120, 100%, 50%, 40%)".parse().unwrap();
+        assert_eq!(hsla.alpha, 0.4);
+        assert_eq!(hsla.color.convert::<RGBColor>().to_string(), "#00FF00");
+        let hsla: Alpha<HSLColor> = "hsl(120, 100%, 50%)".parse().unwrap();
+        assert_eq!(hsla.alpha, 1.);
+        assert!("hsba(120, 100%, 25%, 0.4)"
+            .parse::<Alpha<HSLColor>>()
+            .is_err());
+
+        let hsva: Alpha<HSVColor> = "hsva(120, 100%, 100%, 0.25)".parse().unwrap();
+        assert_eq!(hsva.alpha, 0.25);
+        assert_eq!(hsva.color.convert::<RGBColor>().to_string(), "#00FF00");
+    }
+    #[test]
+    fn test_conversion_keeps_alpha() {
+        let mut rgba = Alpha::new(RGBColor::from((40, 100, 200)), 0.3);
+        rgba.set_hue(20.0);
+        rgba.set_lightness(30.0);
+        assert_eq!(rgba.alpha, 0.3);
+        assert_eq!(rgba.grayscale().alpha, 0.3);
+        let hsla: Alpha<HSLColor> = rgba.convert_color();
+        assert_eq!(hsla.alpha, 0.3);
+        // plain conversion is fully opaque
+        let converted: Alpha<HSLColor> = rgba.convert();
+        assert_eq!(converted.alpha, 1.);
+        assert!(converted.color.visually_indistinguishable(&rgba.color));
+    }
+    #[test]
+    fn test_premultiplication() {
+        let rgba = Alpha::new(
+            RGBColor {
+                r: 0.8,
+                g: 0.4,
+                b: 0.2,
+            },
+            0.25,
+        );
+        let premul = rgba.premultiply();
+        assert!((premul.color.r - 0.2).abs() <= 1e-10);
+        assert!((premul.color.g - 0.1).abs() <= 1e-10);
+        assert!((premul.color.b - 0.05).abs() <= 1e-10);
+        let rgba2 = premul.unpremultiply();
+        assert!((rgba.color.r - rgba2.color.r).abs() <= 1e-10);
+        assert!((rgba.color.g - rgba2.color.g).abs() <= 1e-10);
+        assert!((rgba.color.b - rgba2.color.b).abs() <= 1e-10);
+        assert!(premul.distance(&rgba) <= 1e-10);
+        // fully transparent colors lose their color
+        let clear = Alpha::new(rgba.color, 0.0).premultiply().unpremultiply();
+        assert_eq!(clear.color, RGBColor::from((0, 0, 0)));
+    }
+}
@@ -1,10 +1,72 @@
 //! This module describes the [`Bound`](bound::Bound) trait, which allows for a description of what
 //! colors a color gamut supports. For example, the sRGB gamut only supports RGB values ranging from
 //! 0-1 that are scaled to 0-255, which is about 30% of the total visible range of human vision.
+//!
+//! Bringing a color into a gamut can be done in more than one way, and the simplest, clipping each
+//! component, can change the hue of saturated colors quite a bit. The
+//! [`GamutMap`](bound::GamutMap) enum describes the other options, with the default following
+//! [CSS Color Module Level 4](https://www.w3.org/TR/css-color-4/#gamut-mapping).
 
 use color::{Color, RGBColor};
 use colorpoint::ColorPoint;
+use colors::{CIELABColor, CIELCHColor, OklabColor, OklchColor};
 use coord::Coord;
+use delta_e::DeltaE;
+
+/// The largest difference in Oklab, ΔEOK, that is treated as imperceptible when mapping colors with
+/// [`GamutMap::CSS`](enum.GamutMap.html#variant.CSS). This is the value CSS uses.
+const OKLAB_JND: f64 = 0.02;
+/// How close the chroma needs to be before binary searches stop.
+const CHROMA_EPSILON: f64 = 0.0001;
+/// How far outside of the bounds a component can be and still count as in the gamut, to allow for
+/// floating-point error in conversions.
+const GAMUT_EPSILON: f64 = 1e-9;
+
+/// The different ways of bringing a color into a gamut, for use with
+/// [`Bound::gamut_map_with`](trait.Bound.html#method.gamut_map_with). Every strategy leaves colors
+/// that are already in the gamut alone.
+///
+/// # Example
+/// Clipping a saturated orange into sRGB turns it noticeably redder, which the default avoids.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::bound::GamutMap;
+/// # use scarlet::colors::OklchColor;
+/// let orange = OklchColor{l: 0.7, c: 0.25, h: 60.};
+/// let clipped: OklchColor = RGBColor::gamut_map_with(orange, GamutMap::Clip);
+/// let mapped: OklchColor = RGBColor::gamut_map(orange);
+/// assert!(RGBColor::in_gamut(&mapped));
+/// assert!((clipped.h - orange.h).abs() > 15.);
+/// assert!((mapped.h - orange.h).abs() < 6.);
+/// assert!((mapped.l - orange.l).abs() < (clipped.l - orange.l).abs());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamutMap {
+    /// Clamps each component of the color in the gamut's own space, like
+    /// [`Bound::clamp`](trait.Bound.html#method.clamp). Cheap, but this can shift hue and lightness.
+    Clip,
+    /// The algorithm from CSS Color Module Level 4: reduces chroma in Oklch, keeping lightness and
+    /// hue constant, until clipping the result changes it by less than a just-noticeable difference
+    /// (ΔEOK of 0.02). Colors lighter than white or darker than black become white or black.
+    CSS,
+    /// Starts from the `CSS` result and searches nearby for colors in the gamut that are closer to
+    /// the original according to CIEDE2000. This finds a local minimum, not necessarily the closest
+    /// color in the gamut, but it's never further from the original than `CSS`. It's the slowest
+    /// strategy, and it can change every attribute of the color a little.
+    Nearest,
+    /// Reduces chroma in CIELCH, keeping CIELAB lightness and hue constant, until the color is in the
+    /// gamut. Unlike `CSS`, this never trades any lightness for chroma, which is useful when
+    /// lightness has to be exact, such as for contrast.
+    PreserveLightness,
+}
+
+impl Default for GamutMap {
+    /// The CSS Color 4 algorithm.
+    fn default() -> GamutMap {
+        GamutMap::CSS
+    }
+}
 
 /// Describes a color space in which the total space of representable colors has explicit bounds
 /// besides those imposed by human vision. For example, an sRGB color can't have negative values for
@@ -64,6 +126,165 @@ pub trait Bound: Color + ColorPoint {
         let point: Coord = converted_color.into();
         Self::from(Self::clamp_coord(point)).convert()
     }
+    /// Returns `true` if the given color is within the bounds of this color space, allowing for a tiny
+    /// amount of floating-point error.
+    fn in_gamut<T: Color>(color: &T) -> bool {
+        let point: Coord = color.convert::<Self>().into();
+        let components = [point.x, point.y, point.z];
+        Self::bounds()
+            .iter()
+            .zip(components.iter())
+            .all(|(&(min, max), &c)| c >= min - GAMUT_EPSILON && c <= max + GAMUT_EPSILON)
+    }
+    /// Brings a color into the bounds of this color space using the default strategy, the CSS Color 4
+    /// algorithm that reduces chroma at constant lightness and hue. Unlike
+    /// [`clamp`](trait.Bound.html#method.clamp), this keeps hue stable. See
+    /// [`GamutMap`](enum.GamutMap.html) for example usage.
+    fn gamut_map<T: Color>(color: T) -> T {
+        Self::gamut_map_with(color, GamutMap::default())
+    }
+    /// Brings a color into the bounds of this color space using the given strategy. See
+    /// [`GamutMap`](enum.GamutMap.html) for example usage and a description of each strategy.
+    fn gamut_map_with<T: Color>(color: T, method: GamutMap) -> T {
+        if Self::in_gamut(&color) {
+            return color;
+        }
+        let mapped: Self = match method {
+            GamutMap::Clip => clip(color),
+            GamutMap::CSS => css_gamut_map(color),
+            GamutMap::Nearest => nearest_gamut_map(color),
+            GamutMap::PreserveLightness => lightness_gamut_map(color),
+        };
+        mapped.convert()
+    }
+}
+
+/// Clips each component of a color in the space of `B`.
+fn clip<B: Bound, T: Color>(color: T) -> B {
+    let point: Coord = color.convert::<B>().into();
+    B::from(B::clamp_coord(point))
+}
+
+/// The CSS Color 4 gamut mapping algorithm: https://www.w3.org/TR/css-color-4/#binsearch
+fn css_gamut_map<B: Bound, T: Color>(color: T) -> B {
+    let origin: OklchColor = color.convert();
+    if origin.l >= 1.0 {
+        return clip(OklchColor {
+            l: 1.0,
+            c: 0.0,
+            h: 0.0,
+        });
+    } else if origin.l <= 0.0 {
+        return clip(OklchColor {
+            l: 0.0,
+            c: 0.0,
+            h: 0.0,
+        });
+    }
+    // ΔEOK is just Euclidean distance in Oklab
+    let delta_e_ok = |clipped: B, current: OklchColor| {
+        let clipped_lab: OklabColor = clipped.convert();
+        let current_lab: OklabColor = current.convert();
+        clipped_lab.euclidean_distance(current_lab)
+    };
+
+    let mut current = origin;
+    let mut clipped: B = clip(current);
+    if delta_e_ok(clipped, current) < OKLAB_JND {
+        return clipped;
+    }
+    let mut min = 0.0;
+    let mut max = origin.c;
+    let mut min_in_gamut = true;
+    while max - min > CHROMA_EPSILON {
+        let chroma = (min + max) / 2.0;
+        current.c = chroma;
+        if min_in_gamut && B::in_gamut(&current) {
+            min = chroma;
+            continue;
+        }
+        clipped = clip(current);
+        let error = delta_e_ok(clipped, current);
+        if error < OKLAB_JND {
+            if OKLAB_JND - error < CHROMA_EPSILON {
+                return clipped;
+            }
+            min_in_gamut = false;
+            min = chroma;
+        } else {
+            max = chroma;
+        }
+    }
+    clipped
+}
+
+/// Binary searches for the largest CIELCH chroma that is in the gamut, clipping at the end in case
+/// even a gray of that lightness is out of the gamut.
+fn lightness_gamut_map<B: Bound, T: Color>(color: T) -> B {
+    let mut current: CIELCHColor = color.convert();
+    let mut min = 0.0;
+    let mut max = current.c;
+    while max - min > CHROMA_EPSILON {
+        current.c = (min + max) / 2.0;
+        if B::in_gamut(&current) {
+            min = current.c;
+        } else {
+            max = current.c;
+        }
+    }
+    current.c = min;
+    clip(current)
+}
+
+/// Minimizes the CIEDE2000 difference between the original color and a point in the gamut, using a
+/// pattern search in the coordinates of `B` that starts from the CSS result and shrinks its step
+/// size whenever no step helps.
+fn nearest_gamut_map<B: Bound, T: Color>(color: T) -> B {
+    let metric = DeltaE::default();
+    let bounds = B::bounds();
+    let ranges: Vec<f64> = bounds
+        .iter()
+        .map(|&(min, max)| {
+            if (max - min).is_finite() {
+                max - min
+            } else {
+                1.0
+            }
+        })
+        .collect();
+    let original: CIELABColor = color.convert();
+    let error = |point: Coord| metric.distance(&original, &B::from(point));
+
+    let mut best: Coord = B::clamp_coord(css_gamut_map::<B, T>(color).into());
+    let mut best_error = error(best);
+    // step size, as a fraction of each component's range
+    let mut step = 0.05;
+    while step > 1e-7 {
+        let mut improved = false;
+        for i in 0..3 {
+            for &sign in &[1.0, -1.0] {
+                let mut offset = [0.0; 3];
+                offset[i] = sign * step * ranges[i];
+                let candidate = B::clamp_coord(
+                    best + Coord {
+                        x: offset[0],
+                        y: offset[1],
+                        z: offset[2],
+                    },
+                );
+                let candidate_error = error(candidate);
+                if candidate_error < best_error {
+                    best = candidate;
+                    best_error = candidate_error;
+                    improved = true;
+                }
+            }
+        }
+        if !improved {
+            step /= 2.0;
+        }
+    }
+    B::from(best)
 }
 
 // implement Bound for the base colors in the color module, to avoid cluttering that more than it
@@ -76,11 +297,13 @@ impl Bound for RGBColor {
 
 #[cfg(test)]
 mod tests {
-    use super::Bound;
+    use super::{Bound, GamutMap};
     use color::Color;
     use color::RGBColor;
     use colors::hslcolor::HSLColor;
     use colors::hsvcolor::HSVColor;
+    use colors::{CIELABColor, CIELCHColor, OklchColor};
+    use delta_e::DeltaE;
 
     #[test]
     fn test_zero_one_bounds() {
@@ -131,4 +354,114 @@ mod tests {
             },)
         );
     }
+
+    #[test]
+    fn test_in_gamut() {
+        assert!(RGBColor::in_gamut(&RGBColor {
+            r: 0.,
+            g: 0.5,
+            b: 1.,
+        }));
+        assert!(RGBColor::in_gamut(
+            &RGBColor::from_hex_code("#123456").unwrap()
+        ));
+        assert!(!RGBColor::in_gamut(&CIELABColor {
+            l: 50.,
+            a: 100.,
+            b: 0.,
+        }));
+    }
+
+    #[test]
+    fn test_gamut_map_keeps_in_gamut_colors() {
+        let color = CIELABColor {
+            l: 40.,
+            a: 20.,
+            b: -30.,
+        };
+        for &method in &[
+            GamutMap::Clip,
+            GamutMap::CSS,
+            GamutMap::Nearest,
+            GamutMap::PreserveLightness,
+        ] {
+            let mapped = RGBColor::gamut_map_with(color, method);
+            assert_eq!(mapped.l, color.l);
+            assert_eq!(mapped.a, color.a);
+            assert_eq!(mapped.b, color.b);
+        }
+    }
+
+    #[test]
+    fn test_css_gamut_map() {
+        // Display P3's green, which sRGB can't show
+        let green = OklchColor {
+            l: 0.8664,
+            c: 0.2948,
+            h: 142.5,
+        };
+        let mapped: OklchColor = RGBColor::gamut_map(green);
+        assert!(RGBColor::in_gamut(&mapped));
+        assert!((mapped.l - green.l).abs() < 0.01);
+        assert!((mapped.h - green.h).abs() < 1.);
+        assert!(mapped.c < green.c);
+        // too light and too dark go to white and black
+        let white: RGBColor = RGBColor::gamut_map(OklchColor {
+            l: 1.2,
+            c: 0.1,
+            h: 30.,
+        })
+        .convert();
+        assert_eq!(white.to_string(), "#FFFFFF");
+        let black: RGBColor = RGBColor::gamut_map(OklchColor {
+            l: -0.1,
+            c: 0.1,
+            h: 30.,
+        })
+        .convert();
+        assert_eq!(black.to_string(), "#000000");
+    }
+
+    #[test]
+    fn test_preserve_lightness_gamut_map() {
+        let blue = CIELCHColor {
+            l: 70.,
+            c: 80.,
+            h: 280.,
+        };
+        let mapped = RGBColor::gamut_map_with(blue, GamutMap::PreserveLightness);
+        assert!(RGBColor::in_gamut(&mapped));
+        assert!((mapped.l - blue.l).abs() < 1e-6);
+        assert!((mapped.h - blue.h).abs() < 1e-6);
+        assert!(mapped.c < blue.c);
+    }
+
+    #[test]
+    fn test_nearest_gamut_map() {
+        let metric = DeltaE::default();
+        let colors = [
+            CIELABColor {
+                l: 60.,
+                a: -100.,
+                b: 60.,
+            },
+            CIELABColor {
+                l: 30.,
+                a: 70.,
+                b: -110.,
+            },
+            CIELABColor {
+                l: 90.,
+                a: 10.,
+                b: 100.,
+            },
+        ];
+        for color in colors.iter() {
+            let nearest = RGBColor::gamut_map_with(*color, GamutMap::Nearest);
+            assert!(RGBColor::in_gamut(&nearest));
+            // the search starts from the CSS result and only ever improves on it
+            let css = RGBColor::gamut_map_with(*color, GamutMap::CSS);
+            assert!(metric.distance(color, &nearest) <= metric.distance(color, &css) + 1e-9);
+        }
+    }
 }
@@ -0,0 +1,401 @@
+//! This module implements the CAM16 color appearance model, described in Li et al., ["Comprehensive
+//! color solutions: CAM16, CAT16, and CAM16-UCS"](https://doi.org/10.1002/col.22131) (2017). A color
+//! appearance model goes one step further than a color space like CIELAB: instead of assuming a
+//! single viewing environment, it takes the conditions a color is viewed in as input and predicts
+//! how the color will actually look. The same physical stimulus looks noticeably different on a
+//! bright office monitor than it does on a projector in a dark room, and CAM16 can quantify that.
+//!
+//! The model is split into two parts. The [`ViewingConditions`] struct describes the environment:
+//! the white point, how bright the adapting field is, how bright the background is, and the
+//! surround. The [`CAM16Color`] struct then holds the six appearance correlates CAM16 predicts for a
+//! color in those conditions.
+//!
+//! [`ViewingConditions`]: struct.ViewingConditions.html
+//! [`CAM16Color`]: struct.CAM16Color.html
+
+use std::f64;
+use std::f64::consts::PI;
+
+use color::{Color, XYZColor};
+use consts::CAT16_TRANSFORM as CAT16;
+use consts::CAT16_TRANSFORM_LU as CAT16_LU;
+use illuminants::Illuminant;
+
+/// The relative luminance of the area surrounding a stimulus and its background, as CAM16 defines
+/// it. This affects perceived contrast: the same image looks more contrasty in a dark room.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Surround {
+    /// The surround is about as bright as the scene, as with a reflective print viewed in normal
+    /// lighting or a monitor in a well-lit office.
+    Average,
+    /// The surround is darker than the scene, as with a television in a dim living room.
+    Dim,
+    /// The surround is essentially black, as with a projector in a dark room or a cinema.
+    Dark,
+}
+
+impl Surround {
+    /// Returns the three parameters CAM16 derives from the surround, `(F, c, N_c)`: the maximum
+    /// degree of adaptation, the impact of the surround, and the chromatic induction factor.
+    fn parameters(self) -> (f64, f64, f64) {
+        match self {
+            Surround::Average => (1.0, 0.69, 1.0),
+            Surround::Dim => (0.9, 0.59, 0.9),
+            Surround::Dark => (0.8, 0.525, 0.8),
+        }
+    }
+}
+
+/// The conditions under which a color is viewed, which CAM16 uses to predict its appearance.
+/// # Example
+/// The same gray looks lighter and more contrasty in a dark room than in a bright office.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::cam16::{CAM16Color, Surround, ViewingConditions};
+/// let gray = RGBColor{r: 0.4, g: 0.4, b: 0.4}.to_xyz(Illuminant::D65);
+/// let office = ViewingConditions {
+///     white_point: Illuminant::D65,
+///     adapting_luminance: 318.31,
+///     background_luminance: 20.,
+///     surround: Surround::Average,
+/// };
+/// let cinema = ViewingConditions {
+///     adapting_luminance: 31.83,
+///     surround: Surround::Dark,
+///     ..office
+/// };
+/// let office_gray = CAM16Color::from_xyz_with(gray, &office);
+/// let cinema_gray = CAM16Color::from_xyz_with(gray, &cinema);
+/// assert!(cinema_gray.j > office_gray.j);
+/// // but it's brighter in absolute terms in the office, because there's more light
+/// assert!(office_gray.q > cinema_gray.q);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ViewingConditions {
+    /// The white point of the adopted white in the scene. Colors are chromatically adapted to this
+    /// before the model is applied.
+    pub white_point: Illuminant,
+    /// The luminance of the adapting field, *L<sub>A</sub>*, in cd/m². This is usually taken to be
+    /// 20% of the luminance of white in the scene: for a 250 cd/m² monitor, it would be 50.
+    pub adapting_luminance: f64,
+    /// The relative luminance of the background, *Y<sub>b</sub>*, on a scale where white is
+    /// 100. A mid-gray background of 20 is the usual default.
+    pub background_luminance: f64,
+    /// The surround of the scene.
+    pub surround: Surround,
+}
+
+impl Default for ViewingConditions {
+    /// The reference viewing conditions for sRGB, as described in IEC 61966-2-1: a D65 display viewed
+    /// in 64 lux of ambient light, with a mid-gray background and an average surround.
+    fn default() -> ViewingConditions {
+        ViewingConditions {
+            white_point: Illuminant::D65,
+            adapting_luminance: 64.0 / PI * 0.2,
+            background_luminance: 20.0,
+            surround: Surround::Average,
+        }
+    }
+}
+
+/// The quantities CAM16 derives from the viewing conditions before looking at any color. These are
+/// named after the notation in the paper.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct DerivedConditions {
+    /// The surround impact factor.
+    c: f64,
+    /// The chromatic induction factor.
+    n_c: f64,
+    /// The degree of adaptation factors for each cone response.
+    d_rgb: [f64; 3],
+    /// The luminance level adaptation factor.
+    pub(crate) f_l: f64,
+    /// The background induction factor.
+    n: f64,
+    /// The base exponential nonlinearity.
+    z: f64,
+    /// The brightness and chromatic background induction factors, which are equal in CAM16.
+    n_bb: f64,
+    /// The achromatic response to white.
+    a_w: f64,
+}
+
+impl ViewingConditions {
+    /// Computes the parameters that only depend on the viewing conditions.
+    pub(crate) fn derived(&self) -> DerivedConditions {
+        let (f, c, n_c) = self.surround.parameters();
+        let l_a = self.adapting_luminance;
+        // white is scaled to Y = 100, which is the convention CAM16 uses
+        let wp = self.white_point.white_point();
+        let rgb_w = &*CAT16 * vector![wp[0] * 100.0, wp[1] * 100.0, wp[2] * 100.0];
+        // the degree of adaptation: this is clamped to the range 0-1
+        let d = (f * (1.0 - (1.0 / 3.6) * ((-l_a - 42.0) / 92.0).exp())).clamp(0.0, 1.0);
+        let d_rgb = [
+            d * 100.0 / rgb_w[0] + 1.0 - d,
+            d * 100.0 / rgb_w[1] + 1.0 - d,
+            d * 100.0 / rgb_w[2] + 1.0 - d,
+        ];
+        let k = 1.0 / (5.0 * l_a + 1.0);
+        let k4 = k.powi(4);
+        let f_l = 0.2 * k4 * (5.0 * l_a) + 0.1 * (1.0 - k4).powi(2) * (5.0 * l_a).cbrt();
+        let n = self.background_luminance / 100.0;
+        let z = 1.48 + n.sqrt();
+        let n_bb = 0.725 * n.powf(-0.2);
+        let rgb_aw: Vec<f64> = (0..3)
+            .map(|i| adapt_response(d_rgb[i] * rgb_w[i], f_l))
+            .collect();
+        let a_w = (2.0 * rgb_aw[0] + rgb_aw[1] + 0.05 * rgb_aw[2] - 0.305) * n_bb;
+        DerivedConditions {
+            c,
+            n_c,
+            d_rgb,
+            f_l,
+            n,
+            z,
+            n_bb,
+            a_w,
+        }
+    }
+}
+
+/// The post-adaptation nonlinear compression CAM16 applies to each cone response. Negative values
+/// are compressed symmetrically so that the inverse is well-defined.
+fn adapt_response(x: f64, f_l: f64) -> f64 {
+    let scaled = (f_l * x.abs() / 100.0).powf(0.42);
+    x.signum() * 400.0 * scaled / (scaled + 27.13) + 0.1
+}
+
+/// The inverse of `adapt_response`.
+fn unadapt_response(x: f64, f_l: f64) -> f64 {
+    let shifted = x - 0.1;
+    shifted.signum()
+        * (100.0 / f_l)
+        * ((27.13 * shifted.abs()) / (400.0 - shifted.abs())).powf(1.0 / 0.42)
+}
+
+/// The eccentricity factor, which adjusts chromatic response by hue.
+fn eccentricity(h: f64) -> f64 {
+    0.25 * ((h.to_radians() + 2.0).cos() + 3.8)
+}
+
+/// A color described by its appearance under a given set of viewing conditions, according to the
+/// CAM16 model. Unlike every other color type in Scarlet, the same `CAM16Color` can correspond to
+/// different XYZ values depending on the conditions it's viewed under, so conversions are done with
+/// [`from_xyz_with`] and [`to_xyz_with`]. `CAM16Color` also implements [`Color`] using the default
+/// [`ViewingConditions`], so it can be used anywhere else in Scarlet.
+///
+/// Only three of the six correlates are independent: conversion back to XYZ uses lightness, chroma,
+/// and hue, and the others are ignored. If you modify a color, make sure to change those.
+/// # Example
+/// How would a saturated red look on a dim projector compared to a bright monitor?
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::cam16::{CAM16Color, Surround, ViewingConditions};
+/// let red = RGBColor{r: 0.8, g: 0.1, b: 0.1}.to_xyz(Illuminant::D65);
+/// let monitor = ViewingConditions {
+///     white_point: Illuminant::D65,
+///     adapting_luminance: 60.,
+///     background_luminance: 20.,
+///     surround: Surround::Average,
+/// };
+/// let projector = ViewingConditions {
+///     adapting_luminance: 10.,
+///     surround: Surround::Dim,
+///     ..monitor
+/// };
+/// let on_monitor = CAM16Color::from_xyz_with(red, &monitor);
+/// let on_projector = CAM16Color::from_xyz_with(red, &projector);
+/// // the hue stays the same, but the colorfulness drops in the dimmer setting
+/// assert!((on_monitor.h - on_projector.h).abs() < 1.);
+/// assert!(on_monitor.m > on_projector.m);
+/// // to make it look the same on the projector as it does on the monitor, we can go backwards
+/// let corrected = on_monitor.to_xyz_with(&projector, Illuminant::D65);
+/// let corrected_rgb: RGBColor = corrected.convert();
+/// println!("{}", corrected_rgb.to_string());
+/// ```
+///
+/// [`from_xyz_with`]: #method.from_xyz_with
+/// [`to_xyz_with`]: #method.to_xyz_with
+/// [`Color`]: ../color/trait.Color.html
+/// [`ViewingConditions`]: struct.ViewingConditions.html
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct CAM16Color {
+    /// The lightness, *J*: brightness relative to the brightness of white. Ranges from 0 to 100 for
+    /// colors no brighter than white.
+    pub j: f64,
+    /// The chroma, *C*: colorfulness relative to the brightness of white.
+    pub c: f64,
+    /// The hue angle, *h*, in degrees from 0 to 360.
+    pub h: f64,
+    /// The colorfulness, *M*: the absolute perceived amount of hue, which increases as the scene
+    /// gets brighter.
+    pub m: f64,
+    /// The saturation, *s*: colorfulness relative to the color's own brightness.
+    pub s: f64,
+    /// The brightness, *Q*: the absolute perceived amount of light, which increases as the scene
+    /// gets brighter.
+    pub q: f64,
+}
+
+impl CAM16Color {
+    /// Predicts the appearance of the given XYZ color under the given viewing conditions. The color
+    /// is first chromatically adapted to the white point of the viewing conditions if necessary.
+    pub fn from_xyz_with(xyz: XYZColor, conditions: &ViewingConditions) -> CAM16Color {
+        let vc = conditions.derived();
+        let xyz_c = xyz.color_adapt(conditions.white_point);
+        // step 1: go to sharpened cone responses and apply chromatic adaptation
+        let rgb = &*CAT16 * vector![xyz_c.x * 100.0, xyz_c.y * 100.0, xyz_c.z * 100.0];
+        // step 2: nonlinear response compression
+        let rgb_a: Vec<f64> = (0..3)
+            .map(|i| adapt_response(vc.d_rgb[i] * rgb[i], vc.f_l))
+            .collect();
+        // step 3: opponent color dimensions and hue
+        let a = rgb_a[0] - 12.0 * rgb_a[1] / 11.0 + rgb_a[2] / 11.0;
+        let b = (rgb_a[0] + rgb_a[1] - 2.0 * rgb_a[2]) / 9.0;
+        let unbounded_h = b.atan2(a).to_degrees();
+        let h = if unbounded_h < 0.0 {
+            unbounded_h + 360.0
+        } else {
+            unbounded_h
+        };
+        // step 4: achromatic response, lightness and brightness
+        let achromatic = (2.0 * rgb_a[0] + rgb_a[1] + 0.05 * rgb_a[2] - 0.305) * vc.n_bb;
+        let j = 100.0 * (achromatic / vc.a_w).max(0.0).powf(vc.c * vc.z);
+        let q = (4.0 / vc.c) * (j / 100.0).sqrt() * (vc.a_w + 4.0) * vc.f_l.powf(0.25);
+        // step 5: chroma, colorfulness, and saturation
+        let t = (50000.0 / 13.0 * vc.n_c * vc.n_bb * eccentricity(h) * a.hypot(b))
+            / (rgb_a[0] + rgb_a[1] + 21.0 / 20.0 * rgb_a[2]);
+        let c = t.powf(0.9) * (j / 100.0).sqrt() * (1.64 - 0.29f64.powf(vc.n)).powf(0.73);
+        let m = c * vc.f_l.powf(0.25);
+        let s = if q == 0.0 { 0.0 } else { 100.0 * (m / q).sqrt() };
+        CAM16Color { j, c, h, m, s, q }
+    }
+
+    /// Finds the XYZ color that would have this appearance under the given viewing conditions, in
+    /// the given illuminant. Only lightness, chroma, and hue are used.
+    pub fn to_xyz_with(&self, conditions: &ViewingConditions, illuminant: Illuminant) -> XYZColor {
+        let vc = conditions.derived();
+        // undo step 5: get t back from chroma
+        let t = if self.j == 0.0 {
+            0.0
+        } else {
+            (self.c / ((self.j / 100.0).sqrt() * (1.64 - 0.29f64.powf(vc.n)).powf(0.73)))
+                .powf(1.0 / 0.9)
+        };
+        // undo step 4: get the achromatic response back from lightness
+        let achromatic = vc.a_w * (self.j / 100.0).powf(1.0 / (vc.c * vc.z));
+        // undo step 3: this closed form for a and b avoids dividing by zero for grays
+        let p_1 = 50000.0 / 13.0 * vc.n_c * vc.n_bb * eccentricity(self.h);
+        let p_2 = achromatic / vc.n_bb + 0.305;
+        let (sin, cos) = self.h.to_radians().sin_cos();
+        let gamma = 23.0 * p_2 * t / (23.0 * p_1 + 11.0 * t * cos + 108.0 * t * sin);
+        let a = gamma * cos;
+        let b = gamma * sin;
+        let rgb_a = [
+            (460.0 * p_2 + 451.0 * a + 288.0 * b) / 1403.0,
+            (460.0 * p_2 - 891.0 * a - 261.0 * b) / 1403.0,
+            (460.0 * p_2 - 220.0 * a - 6300.0 * b) / 1403.0,
+        ];
+        // undo steps 2 and 1
+        let rgb = vector![
+            unadapt_response(rgb_a[0], vc.f_l) / vc.d_rgb[0],
+            unadapt_response(rgb_a[1], vc.f_l) / vc.d_rgb[1],
+            unadapt_response(rgb_a[2], vc.f_l) / vc.d_rgb[2]
+        ];
+        let xyz = CAT16_LU.solve(rgb).expect("Matrix is invertible.");
+        XYZColor {
+            x: xyz[0] / 100.0,
+            y: xyz[1] / 100.0,
+            z: xyz[2] / 100.0,
+            illuminant: conditions.white_point,
+        }
+        .color_adapt(illuminant)
+    }
+}
+
+impl Color for CAM16Color {
+    /// Converts from XYZ to CAM16 using the default viewing conditions. To use different ones, use
+    /// [`from_xyz_with`](#method.from_xyz_with).
+    fn from_xyz(xyz: XYZColor) -> CAM16Color {
+        CAM16Color::from_xyz_with(xyz, &ViewingConditions::default())
+    }
+    /// Converts from CAM16 to XYZ using the default viewing conditions. To use different ones, use
+    /// [`to_xyz_with`](#method.to_xyz_with).
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        self.to_xyz_with(&ViewingConditions::default(), illuminant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use consts::TEST_PRECISION;
+
+    #[test]
+    fn test_cam16_reference_values() {
+        // reference values from the colour-science Python package, which follow the worked example
+        // in the CAM16 paper
+        let white = Illuminant::Custom([0.9505, 1.0, 1.0888]);
+        let conditions = ViewingConditions {
+            white_point: white,
+            adapting_luminance: 318.31,
+            background_luminance: 20.0,
+            surround: Surround::Average,
+        };
+        let xyz = XYZColor {
+            x: 0.1901,
+            y: 0.2000,
+            z: 0.2178,
+            illuminant: white,
+        };
+        let cam = CAM16Color::from_xyz_with(xyz, &conditions);
+        assert!((cam.j - 41.73120791).abs() <= 1e-6);
+        assert!((cam.c - 0.10335574).abs() <= 1e-6);
+        assert!((cam.h - 217.06795977).abs() <= 1e-6);
+        assert!((cam.m - 0.10743678).abs() <= 1e-6);
+        assert!((cam.q - 195.37170900).abs() <= 1e-6);
+        assert!((cam.s - 100.0 * (cam.m / cam.q).sqrt()).abs() <= 1e-10);
+    }
+    #[test]
+    fn test_cam16_xyz_conversion() {
+        for surround in [Surround::Average, Surround::Dim, Surround::Dark].iter() {
+            let conditions = ViewingConditions {
+                white_point: Illuminant::D50,
+                adapting_luminance: 40.0,
+                background_luminance: 18.0,
+                surround: *surround,
+            };
+            let xyz = XYZColor {
+                x: 0.4,
+                y: 0.2,
+                z: 0.6,
+                illuminant: Illuminant::D65,
+            };
+            let cam = CAM16Color::from_xyz_with(xyz, &conditions);
+            let xyz2 = cam.to_xyz_with(&conditions, Illuminant::D65);
+            assert!((xyz.x - xyz2.x).abs() <= 1e-10);
+            assert!((xyz.y - xyz2.y).abs() <= 1e-10);
+            assert!((xyz.z - xyz2.z).abs() <= 1e-10);
+            assert!(xyz.distance(&xyz2) <= TEST_PRECISION);
+        }
+    }
+    #[test]
+    fn test_cam16_gray() {
+        // white should have a lightness of 100, and black should go back to no light at all
+        let white: CAM16Color = XYZColor::white_point(Illuminant::D65).convert();
+        assert!((white.j - 100.0).abs() <= 1e-10);
+        let black = CAM16Color {
+            j: 0.0,
+            c: 0.0,
+            h: 0.0,
+            m: 0.0,
+            s: 0.0,
+            q: 0.0,
+        };
+        let black_xyz = black.to_xyz(Illuminant::D65);
+        assert!(black_xyz.y.abs() <= 1e-10);
+    }
+}
@@ -0,0 +1,188 @@
+//! This module implements the math behind correlated color temperature (CCT): the chromaticities of
+//! the Planckian (black body) and CIE daylight loci, and Ohno's method for finding the closest point
+//! on the Planckian locus to a given chromaticity. The public interface is
+//! [`Illuminant::from_cct`](../illuminants/enum.Illuminant.html#method.from_cct),
+//! [`XYZColor::cct`](../color/struct.XYZColor.html#method.cct), and
+//! [`RGBColor::from_temperature`](../color/struct.RGBColor.html#method.from_temperature).
+
+use cmf::CIE_1931_2_DEGREE;
+
+/// The lowest temperature, in Kelvin, that illuminants and CCT calculation support.
+pub(crate) const MIN_CCT: f64 = 1000.0;
+/// The highest temperature, in Kelvin, supported by the CIE daylight locus.
+pub(crate) const MAX_DAYLIGHT_CCT: f64 = 25000.0;
+/// The highest temperature, in Kelvin, that CCT calculation will return: the same as the daylight
+/// locus, so that every illuminant made from a CCT has one.
+pub(crate) const MAX_CCT: f64 = MAX_DAYLIGHT_CCT;
+/// The temperature below which the Planckian locus is used for illuminants, and above which the CIE
+/// daylight locus is, following CIE 15.
+pub(crate) const DAYLIGHT_CUTOFF: f64 = 4000.0;
+/// Below this absolute Duv, Ohno's triangular solution is more accurate than the parabolic one.
+const TRIANGULAR_DUV_MAX: f64 = 0.002;
+
+/// Returns the CIE 1960 UCS (u, v) chromaticity of a black body at the given temperature, by
+/// integrating Planck's law against the CIE 1931 2° color matching functions. Rational
+/// approximations like Krystek's are only accurate up to 15000 K, which doesn't cover the whole
+/// daylight locus.
+pub(crate) fn planckian_uv(t: f64) -> (f64, f64) {
+    // the second radiation constant, in nm K
+    let c2 = 1.4388e7;
+    let mut xyz = [0.0; 3];
+    for row in CIE_1931_2_DEGREE.iter() {
+        let power = row[0].powi(-5) / ((c2 / (row[0] * t)).exp() - 1.0);
+        for i in 0..3 {
+            xyz[i] += power * row[i + 1];
+        }
+    }
+    let denom = xyz[0] + 15.0 * xyz[1] + 3.0 * xyz[2];
+    (4.0 * xyz[0] / denom, 6.0 * xyz[1] / denom)
+}
+
+/// Returns the CIE 1931 (x, y) chromaticity of a black body at the given temperature.
+pub(crate) fn planckian_xy(t: f64) -> (f64, f64) {
+    let (u, v) = planckian_uv(t);
+    let denom = 2.0 * u - 8.0 * v + 4.0;
+    (3.0 * u / denom, 2.0 * v / denom)
+}
+
+/// Returns the CIE 1931 (x, y) chromaticity of CIE daylight at the given temperature, using the
+/// formulas from CIE 15. These are only defined from 4000 K to 25000 K.
+pub(crate) fn daylight_xy(t: f64) -> (f64, f64) {
+    let x = if t <= 7000.0 {
+        -4.6070e9 / t.powi(3) + 2.9678e6 / t.powi(2) + 0.09911e3 / t + 0.244_063
+    } else {
+        -2.0064e9 / t.powi(3) + 1.9018e6 / t.powi(2) + 0.24748e3 / t + 0.237_040
+    };
+    (x, -3.0 * x * x + 2.87 * x - 0.275)
+}
+
+/// Finds the correlated color temperature and Duv of the given CIE 1960 UCS chromaticity using
+/// Ohno's method ("Practical Use and Calculation of CCT and Duv", 2014). This searches a table of
+/// points on the Planckian locus for the closest one, and then narrows the table around it until
+/// it's fine enough to interpolate: the triangular solution is used close to the locus, and the
+/// parabolic solution farther away. Results are clamped to the range 1000 K to 15000 K. Returns
+/// `None` if the chromaticity or the result isn't a finite number.
+pub(crate) fn ohno_cct(u: f64, v: f64) -> Option<(f64, f64)> {
+    if !u.is_finite() || !v.is_finite() {
+        return None;
+    }
+    let mut lo = MIN_CCT;
+    let mut hi = MAX_CCT;
+    // each round of the cascade has this many table entries: the first one is spaced about 1%
+    // apart, which is what Ohno recommends
+    let n = 325;
+    loop {
+        let ratio = (hi / lo).powf(1.0 / (n - 1) as f64);
+        let temps: Vec<f64> = (0..n).map(|i| lo * ratio.powi(i as i32)).collect();
+        let dists: Vec<f64> = temps
+            .iter()
+            .map(|&t| {
+                let (ut, vt) = planckian_uv(t);
+                (u - ut).hypot(v - vt)
+            })
+            .collect();
+        let closest = (1..n - 1)
+            .min_by(|&i, &j| dists[i].total_cmp(&dists[j]))
+            .unwrap();
+        let (t0, t1, t2) = (temps[closest - 1], temps[closest], temps[closest + 1]);
+        if ratio - 1.0 > 1e-4 && closest > 1 && closest < n - 2 {
+            lo = t0;
+            hi = t2;
+            continue;
+        }
+        let (d0, d1, d2) = (dists[closest - 1], dists[closest], dists[closest + 1]);
+        let (u0, v0) = planckian_uv(t0);
+        let (u2, v2) = planckian_uv(t2);
+
+        // the triangular solution: treat the locus as straight between the two neighbors
+        let l = (u2 - u0).hypot(v2 - v0);
+        let x = (d0 * d0 - d2 * d2 + l * l) / (2.0 * l);
+        let t_tri = t0 + (t2 - t0) * x / l;
+        let v_tri = v0 + (v2 - v0) * x / l;
+        let duv_tri = (d0 * d0 - x * x).max(0.0).sqrt() * (v - v_tri).signum();
+        if duv_tri.abs() < TRIANGULAR_DUV_MAX {
+            return finite_cct(t_tri, duv_tri);
+        }
+
+        // the parabolic solution: fit a parabola to distance as a function of temperature
+        let denom = (t2 - t1) * (t0 - t2) * (t1 - t0);
+        let a = (t0 * (d2 - d1) + t1 * (d0 - d2) + t2 * (d1 - d0)) / denom;
+        let b = -(t0 * t0 * (d2 - d1) + t1 * t1 * (d0 - d2) + t2 * t2 * (d1 - d0)) / denom;
+        let c = -(d0 * (t2 - t1) * t1 * t2 + d1 * (t0 - t2) * t0 * t2 + d2 * (t1 - t0) * t0 * t1)
+            / denom;
+        let t_par = -b / (2.0 * a);
+        let (_, v_par) = planckian_uv(t_par);
+        let duv_par = (a * t_par * t_par + b * t_par + c) * (v - v_par).signum();
+        return finite_cct(t_par, duv_par);
+    }
+}
+
+/// Clamps a CCT to the supported range, returning `None` if it or the Duv isn't a finite number.
+fn finite_cct(t: f64, duv: f64) -> Option<(f64, f64)> {
+    if t.is_finite() && duv.is_finite() {
+        Some((t.clamp(MIN_CCT, MAX_CCT), duv))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_loci() {
+        // Planckian chromaticity of illuminant A, from CIE 15
+        let (x, y) = planckian_xy(2856.0);
+        assert!((x - 0.44757).abs() <= 5e-4);
+        assert!((y - 0.40745).abs() <= 5e-4);
+        // the CIE daylight locus should give D65 and D50, with the old value of c2: the tabulated
+        // chromaticities come from the rounded spectra, so they're only close
+        let (x, y) = daylight_xy(6500.0 * 1.4388 / 1.438);
+        assert!((x - 0.31271).abs() <= 2e-4);
+        assert!((y - 0.32902).abs() <= 2e-4);
+        let (x, y) = daylight_xy(5000.0 * 1.4388 / 1.438);
+        assert!((x - 0.34567).abs() <= 2e-4);
+        assert!((y - 0.35850).abs() <= 2e-4);
+    }
+
+    #[test]
+    fn test_ohno_on_locus() {
+        for &t in [
+            1500.0, 2000.0, 3000.0, 4000.0, 6500.0, 10000.0, 14000.0, 20000.0, 24000.0,
+        ]
+        .iter()
+        {
+            let (u, v) = planckian_uv(t);
+            let (cct, duv) = ohno_cct(u, v).unwrap();
+            assert!((cct - t).abs() <= 0.1, "{} {}", t, cct);
+            assert!(duv.abs() <= 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_ohno_off_locus() {
+        // points 0.01 away from the locus along the normal should come back with the same CCT
+        for &t in [2500.0, 5000.0, 9000.0, 20000.0].iter() {
+            let (u0, v0) = planckian_uv(t - 0.5);
+            let (u1, v1) = planckian_uv(t + 0.5);
+            let (ut, vt) = planckian_uv(t);
+            let len = (u1 - u0).hypot(v1 - v0);
+            // the normal pointing towards higher v
+            let (nu, nv) = (-(v1 - v0) / len, (u1 - u0) / len);
+            let (nu, nv) = if nv < 0.0 { (-nu, -nv) } else { (nu, nv) };
+            for &duv in [0.01, -0.01, 0.003].iter() {
+                let (cct, duv2) = ohno_cct(ut + nu * duv, vt + nv * duv).unwrap();
+                assert!((cct - t).abs() <= 2.0, "{} {}", t, cct);
+                assert!((duv2 - duv).abs() <= 1e-5, "{} {}", duv, duv2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ohno_undefined() {
+        assert_eq!(ohno_cct(f64::NAN, 0.3), None);
+        assert_eq!(ohno_cct(0.2, f64::INFINITY), None);
+    }
+}
@@ -0,0 +1,283 @@
+//! This module contains the CIE standard observers' color matching functions: how strongly each
+//! wavelength of light stimulates the X, Y, and Z responses. Each row is `[wavelength, x̄, ȳ, z̄]`,
+//! sampled every 5 nm from 360 nm to 830 nm. These are the basis of all of CIE XYZ, and so of all
+//! of Scarlet: they're what defines which colors are real, and how to turn a spectrum into a
+//! color. The data are from CIE 15:2018, which is in turn copied from the original CIE publications.
+
+/// A CIE standard observer: a model of how an average person matches colors, defined by a set of
+/// color matching functions. All XYZ coordinates are relative to some observer, so the same
+/// spectrum has different coordinates for different observers. Scarlet uses the 1931 observer
+/// unless told otherwise, as does almost everyone else.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Observer {
+    /// The CIE 1931 2° standard observer, for small samples. This is the default.
+    CIE1931,
+    /// The CIE 1964 10° supplementary standard observer, for samples more than about 4° across.
+    CIE1964,
+}
+
+impl Default for Observer {
+    /// The CIE 1931 2° standard observer.
+    fn default() -> Observer {
+        Observer::CIE1931
+    }
+}
+
+impl Observer {
+    /// Gets this observer's color matching functions, as rows of `[wavelength, x̄, ȳ, z̄]`.
+    pub fn color_matching_functions(&self) -> &'static [[f64; 4]; 95] {
+        match *self {
+            Observer::CIE1931 => &CIE_1931_2_DEGREE,
+            Observer::CIE1964 => &CIE_1964_10_DEGREE,
+        }
+    }
+}
+
+/// The CIE 1931 2° standard observer, which is the observer used everywhere else in Scarlet. It
+/// was measured using a small field of view, and so it describes color matches for small samples,
+/// about the size of a thumbnail at arm's length.
+pub static CIE_1931_2_DEGREE: [[f64; 4]; 95] = [
+    [360.0, 0.00012990, 0.00000392, 0.00060610],
+    [365.0, 0.00023210, 0.00000697, 0.00108600],
+    [370.0, 0.00041490, 0.00001239, 0.00194600],
+    [375.0, 0.00074160, 0.00002202, 0.00348600],
+    [380.0, 0.00136800, 0.00003900, 0.00645000],
+    [385.0, 0.00223600, 0.00006400, 0.01054999],
+    [390.0, 0.00424300, 0.00012000, 0.02005001],
+    [395.0, 0.00765000, 0.00021700, 0.03621000],
+    [400.0, 0.01431000, 0.00039600, 0.06785001],
+    [405.0, 0.02319000, 0.00064000, 0.11020000],
+    [410.0, 0.04351000, 0.00121000, 0.20740000],
+    [415.0, 0.07763000, 0.00218000, 0.37130000],
+    [420.0, 0.13438000, 0.00400000, 0.64560000],
+    [425.0, 0.21477000, 0.00730000, 1.03905010],
+    [430.0, 0.28390000, 0.01160000, 1.38560000],
+    [435.0, 0.32850000, 0.01684000, 1.62296000],
+    [440.0, 0.34828000, 0.02300000, 1.74706000],
+    [445.0, 0.34806000, 0.02980000, 1.78260000],
+    [450.0, 0.33620000, 0.03800000, 1.77211000],
+    [455.0, 0.31870000, 0.04800000, 1.74410000],
+    [460.0, 0.29080000, 0.06000000, 1.66920000],
+    [465.0, 0.25110000, 0.07390000, 1.52810000],
+    [470.0, 0.19536000, 0.09098000, 1.28764000],
+    [475.0, 0.14210000, 0.11260000, 1.04190000],
+    [480.0, 0.09564000, 0.13902000, 0.81295010],
+    [485.0, 0.05795001, 0.16930000, 0.61620000],
+    [490.0, 0.03201000, 0.20802000, 0.46518000],
+    [495.0, 0.01470000, 0.25860000, 0.35330000],
+    [500.0, 0.00490000, 0.32300000, 0.27200000],
+    [505.0, 0.00240000, 0.40730000, 0.21230000],
+    [510.0, 0.00930000, 0.50300000, 0.15820000],
+    [515.0, 0.02910000, 0.60820000, 0.11170000],
+    [520.0, 0.06327000, 0.71000000, 0.07824999],
+    [525.0, 0.10960000, 0.79320000, 0.05725001],
+    [530.0, 0.16550000, 0.86200000, 0.04216000],
+    [535.0, 0.22574990, 0.91485010, 0.02984000],
+    [540.0, 0.29040000, 0.95400000, 0.02030000],
+    [545.0, 0.35970000, 0.98030000, 0.01340000],
+    [550.0, 0.43344990, 0.99495010, 0.00875000],
+    [555.0, 0.51205010, 1.00000000, 0.00575000],
+    [560.0, 0.59450000, 0.99500000, 0.00390000],
+    [565.0, 0.67840000, 0.97860000, 0.00275000],
+    [570.0, 0.76210000, 0.95200000, 0.00210000],
+    [575.0, 0.84250000, 0.91540000, 0.00180000],
+    [580.0, 0.91630000, 0.87000000, 0.00165000],
+    [585.0, 0.97860000, 0.81630000, 0.00140000],
+    [590.0, 1.02630000, 0.75700000, 0.00110000],
+    [595.0, 1.05670000, 0.69490000, 0.00100000],
+    [600.0, 1.06220000, 0.63100000, 0.00080000],
+    [605.0, 1.04560000, 0.56680000, 0.00060000],
+    [610.0, 1.00260000, 0.50300000, 0.00034000],
+    [615.0, 0.93840000, 0.44120000, 0.00024000],
+    [620.0, 0.85444990, 0.38100000, 0.00019000],
+    [625.0, 0.75140000, 0.32100000, 0.00010000],
+    [630.0, 0.64240000, 0.26500000, 0.00005000],
+    [635.0, 0.54190000, 0.21700000, 0.00003000],
+    [640.0, 0.44790000, 0.17500000, 0.00002000],
+    [645.0, 0.36080000, 0.13820000, 0.00001000],
+    [650.0, 0.28350000, 0.10700000, 0.00000000],
+    [655.0, 0.21870000, 0.08160000, 0.00000000],
+    [660.0, 0.16490000, 0.06100000, 0.00000000],
+    [665.0, 0.12120000, 0.04458000, 0.00000000],
+    [670.0, 0.08740000, 0.03200000, 0.00000000],
+    [675.0, 0.06360000, 0.02320000, 0.00000000],
+    [680.0, 0.04677000, 0.01700000, 0.00000000],
+    [685.0, 0.03290000, 0.01192000, 0.00000000],
+    [690.0, 0.02270000, 0.00821000, 0.00000000],
+    [695.0, 0.01584000, 0.00572300, 0.00000000],
+    [700.0, 0.01135916, 0.00410200, 0.00000000],
+    [705.0, 0.00811092, 0.00292900, 0.00000000],
+    [710.0, 0.00579035, 0.00209100, 0.00000000],
+    [715.0, 0.00410646, 0.00148400, 0.00000000],
+    [720.0, 0.00289933, 0.00104700, 0.00000000],
+    [725.0, 0.00204919, 0.00074000, 0.00000000],
+    [730.0, 0.00143997, 0.00052000, 0.00000000],
+    [735.0, 0.00099995, 0.00036110, 0.00000000],
+    [740.0, 0.00069008, 0.00024920, 0.00000000],
+    [745.0, 0.00047602, 0.00017190, 0.00000000],
+    [750.0, 0.00033230, 0.00012000, 0.00000000],
+    [755.0, 0.00023483, 0.00008480, 0.00000000],
+    [760.0, 0.00016615, 0.00006000, 0.00000000],
+    [765.0, 0.00011741, 0.00004240, 0.00000000],
+    [770.0, 0.00008308, 0.00003000, 0.00000000],
+    [775.0, 0.00005871, 0.00002120, 0.00000000],
+    [780.0, 0.00004151, 0.00001499, 0.00000000],
+    [785.0, 0.00002935, 0.00001060, 0.00000000],
+    [790.0, 0.00002067, 0.00000747, 0.00000000],
+    [795.0, 0.00001456, 0.00000526, 0.00000000],
+    [800.0, 0.00001025, 0.00000370, 0.00000000],
+    [805.0, 0.00000722, 0.00000261, 0.00000000],
+    [810.0, 0.00000509, 0.00000184, 0.00000000],
+    [815.0, 0.00000358, 0.00000129, 0.00000000],
+    [820.0, 0.00000252, 0.00000091, 0.00000000],
+    [825.0, 0.00000178, 0.00000064, 0.00000000],
+    [830.0, 0.00000125, 0.00000045, 0.00000000],
+];
+
+/// The CIE 1964 10° supplementary standard observer, measured using a larger field of view. This
+/// describes color matches for samples more than about 4° across, like paint chips or walls, better
+/// than the 1931 observer does.
+pub static CIE_1964_10_DEGREE: [[f64; 4]; 95] = [
+    [360.0, 0.000000122200, 0.000000013398, 0.000000535027],
+    [365.0, 0.000000919270, 0.000000100650, 0.00000402830],
+    [370.0, 0.00000595860, 0.000000651100, 0.0000261437],
+    [375.0, 0.0000330100, 0.00000360160, 0.000145000],
+    [380.0, 0.000159952, 0.0000173800, 0.000704776],
+    [385.0, 0.00066244, 0.0000715700, 0.00292780],
+    [390.0, 0.00236160, 0.000253400, 0.0104822],
+    [395.0, 0.00724200, 0.000768500, 0.0323680],
+    [400.0, 0.0191097, 0.00200440, 0.0860109],
+    [405.0, 0.0434000, 0.00450900, 0.197120],
+    [410.0, 0.0847360, 0.00875600, 0.389366],
+    [415.0, 0.140638, 0.0144560, 0.656760],
+    [420.0, 0.204492, 0.0213910, 0.972542],
+    [425.0, 0.264737, 0.0294970, 1.28250],
+    [430.0, 0.314679, 0.0386760, 1.55348],
+    [435.0, 0.357719, 0.0496020, 1.79850],
+    [440.0, 0.383734, 0.0620770, 1.96728],
+    [445.0, 0.386726, 0.0747040, 2.02730],
+    [450.0, 0.370702, 0.0894560, 1.99480],
+    [455.0, 0.342957, 0.106256, 1.90070],
+    [460.0, 0.302273, 0.128201, 1.74537],
+    [465.0, 0.254085, 0.152761, 1.55490],
+    [470.0, 0.195618, 0.185190, 1.31756],
+    [475.0, 0.132349, 0.219940, 1.03020],
+    [480.0, 0.0805070, 0.253589, 0.772125],
+    [485.0, 0.0410720, 0.297665, 0.570060],
+    [490.0, 0.0161720, 0.339133, 0.415254],
+    [495.0, 0.00513200, 0.395379, 0.302356],
+    [500.0, 0.00381600, 0.460777, 0.218502],
+    [505.0, 0.0154440, 0.531360, 0.159249],
+    [510.0, 0.0374650, 0.606741, 0.112044],
+    [515.0, 0.0713580, 0.685660, 0.0822480],
+    [520.0, 0.117749, 0.761757, 0.0607090],
+    [525.0, 0.172953, 0.823330, 0.0430500],
+    [530.0, 0.236491, 0.875211, 0.0304510],
+    [535.0, 0.304213, 0.923810, 0.0205840],
+    [540.0, 0.376772, 0.961988, 0.0136760],
+    [545.0, 0.451584, 0.982200, 0.00791800],
+    [550.0, 0.529826, 0.991761, 0.00398800],
+    [555.0, 0.616053, 0.999110, 0.00109100],
+    [560.0, 0.705224, 0.997340, 0.0],
+    [565.0, 0.793832, 0.982380, 0.0],
+    [570.0, 0.878655, 0.955552, 0.0],
+    [575.0, 0.951162, 0.915175, 0.0],
+    [580.0, 1.01416, 0.868934, 0.0],
+    [585.0, 1.07430, 0.825623, 0.0],
+    [590.0, 1.11852, 0.777405, 0.0],
+    [595.0, 1.13430, 0.720353, 0.0],
+    [600.0, 1.12399, 0.658341, 0.0],
+    [605.0, 1.08910, 0.593878, 0.0],
+    [610.0, 1.03048, 0.527963, 0.0],
+    [615.0, 0.950740, 0.461834, 0.0],
+    [620.0, 0.856297, 0.398057, 0.0],
+    [625.0, 0.754930, 0.339554, 0.0],
+    [630.0, 0.647467, 0.283493, 0.0],
+    [635.0, 0.535110, 0.228254, 0.0],
+    [640.0, 0.431567, 0.179828, 0.0],
+    [645.0, 0.343690, 0.140211, 0.0],
+    [650.0, 0.268329, 0.107633, 0.0],
+    [655.0, 0.204300, 0.0811870, 0.0],
+    [660.0, 0.152568, 0.0602810, 0.0],
+    [665.0, 0.112210, 0.0440960, 0.0],
+    [670.0, 0.0812606, 0.0318004, 0.0],
+    [675.0, 0.0579300, 0.0226017, 0.0],
+    [680.0, 0.0408508, 0.0159051, 0.0],
+    [685.0, 0.0286230, 0.0111303, 0.0],
+    [690.0, 0.0199413, 0.00774880, 0.0],
+    [695.0, 0.0138420, 0.00537510, 0.0],
+    [700.0, 0.00957688, 0.00371774, 0.0],
+    [705.0, 0.00660520, 0.00256456, 0.0],
+    [710.0, 0.00455263, 0.00176847, 0.0],
+    [715.0, 0.00314470, 0.00122239, 0.0],
+    [720.0, 0.00217496, 0.000846190, 0.0],
+    [725.0, 0.00150570, 0.000586440, 0.0],
+    [730.0, 0.00104476, 0.000407410, 0.0],
+    [735.0, 0.000727450, 0.000284041, 0.0],
+    [740.0, 0.000508258, 0.000198730, 0.0],
+    [745.0, 0.000356380, 0.000139550, 0.0],
+    [750.0, 0.000250969, 0.0000984280, 0.0],
+    [755.0, 0.000177730, 0.0000697640, 0.0],
+    [760.0, 0.000126390, 0.0000496730, 0.0],
+    [765.0, 0.0000903100, 0.0000355500, 0.0],
+    [770.0, 0.0000648356, 0.0000255340, 0.0],
+    [775.0, 0.0000467300, 0.0000184150, 0.0],
+    [780.0, 0.0000338200, 0.0000133110, 0.0],
+    [785.0, 0.0000245300, 0.00000967000, 0.0],
+    [790.0, 0.0000178000, 0.00000702800, 0.0],
+    [795.0, 0.0000129500, 0.00000512100, 0.0],
+    [800.0, 0.00000941000, 0.00000373700, 0.0],
+    [805.0, 0.00000686000, 0.00000272900, 0.0],
+    [810.0, 0.00000500000, 0.00000199400, 0.0],
+    [815.0, 0.00000365000, 0.00000145800, 0.0],
+    [820.0, 0.00000267000, 0.00000106700, 0.0],
+    [825.0, 0.00000195000, 0.000000780000, 0.0],
+    [830.0, 0.00000143000, 0.000000570000, 0.0],
+];
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use illuminants::Illuminant;
+
+    #[test]
+    fn test_illuminant_a() {
+        // illuminant A is defined by Planck's law, so integrating it should give the tabulated white
+        // points for both observers
+        let planck = |nm: f64| {
+            let m = nm * 1e-9;
+            m.powi(-5) / ((1.435e-2 / (m * 2848.0)).exp() - 1.0)
+        };
+        for &(table, wp) in [
+            (&CIE_1931_2_DEGREE, Illuminant::A.white_point()),
+            (
+                &CIE_1964_10_DEGREE,
+                Illuminant::A.white_point_for(Observer::CIE1964).unwrap(),
+            ),
+        ]
+        .iter()
+        {
+            let mut xyz = [0.0; 3];
+            for row in table.iter() {
+                for i in 0..3 {
+                    xyz[i] += planck(row[0]) * row[i + 1];
+                }
+            }
+            assert!((xyz[0] / xyz[1] - wp[0]).abs() <= 1e-4);
+            assert!((xyz[2] / xyz[1] - wp[2]).abs() <= 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_equal_energy() {
+        // the functions are normalized so that equal-energy white has the same X, Y, and Z
+        for table in [&CIE_1931_2_DEGREE, &CIE_1964_10_DEGREE].iter() {
+            let y: f64 = table.iter().map(|row| row[2]).sum();
+            for i in [1, 3].iter() {
+                let sum: f64 = table.iter().map(|row| row[*i]).sum();
+                assert!((sum / y - 1.0).abs() <= 5e-4);
+            }
+        }
+    }
+}
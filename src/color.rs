@@ -35,16 +35,19 @@ use std::str::FromStr;
 use std::string::ToString;
 
 use super::coord::Coord;
+use adaptation::ChromaticAdaptation;
+use cct::ohno_cct;
 use colors::cielabcolor::CIELABColor;
 use colors::cielchcolor::CIELCHColor;
+use colors::linearrgbcolor::LinearRGBColor;
 use consts;
-use consts::BRADFORD_TRANSFORM as BRADFORD;
-use consts::BRADFORD_TRANSFORM_LU as BRADFORD_LU;
 use consts::STANDARD_RGB_TRANSFORM as SRGB;
 use consts::STANDARD_RGB_TRANSFORM_LU as SRGB_LU;
-use csscolor::{parse_rgb_str, CSSParseError};
+use csscolor::{css_function, css_number, parse_rgb_str, CSSFormat, CSSParseError, ToCSS};
+use delta_e::DeltaE;
 use illuminants::Illuminant;
 use std::fmt::Debug;
+use transfer::{TransferFunction, SRGB as SRGBTransfer};
 
 use rulinalg::vector::Vector;
 
@@ -84,10 +87,13 @@ impl XYZColor {
     /// algorithms for doing so exist: it is most likely that each person has their own idiosyncrasies
     /// with chromatic adaptation and so there is no perfect solution. Scarlet implements the
     /// *Bradford transform*, which is generally acknowledged to be one of the leading chromatic
-    /// adaptation transforms. Nonetheless, for exact color science work other models are more
-    /// appropriate, such as CIECAM02 if you can measure viewing conditions exactly. This transform
-    /// may not give very good results when used with custom illuminants that wildly differ, but with
-    /// the standard illuminants it does a very good job.
+    /// adaptation transforms: [`color_adapt_with()`] allows choosing others. Nonetheless, for exact
+    /// color science work other models are more appropriate, such as CIECAM02 if you can measure
+    /// viewing conditions exactly. This transform may not give very good results when used with
+    /// custom illuminants that wildly differ, but with the standard illuminants it does a very good
+    /// job.
+    ///
+    /// [`color_adapt_with()`]: #method.color_adapt_with
     /// # Example: The Fabled Dress
     /// The most accessible way of describing color transformation is to take a look at [this
     /// image](https://upload.wikimedia.org/wikipedia/en/a/a8/The_Dress_%28viral_phenomenon%29.png),
@@ -135,45 +141,43 @@ impl XYZColor {
     /// println!("Gold: {}, White: {}", gold_rgb.to_string(), white_rgb.to_string());
     /// ```
     pub fn color_adapt(&self, other_illuminant: Illuminant) -> XYZColor {
+        self.color_adapt_with(other_illuminant, ChromaticAdaptation::Bradford, 1.0)
+    }
+    /// Converts from one illuminant to a different one, like [`color_adapt()`], but using the given
+    /// chromatic adaptation transform and degree of adaptation. A degree of 1 means the observer
+    /// adapts completely to the new illuminant, and a degree of 0 means they don't adapt at all, so
+    /// the coordinates stay the same. See the [`adaptation`] module for more on both.
+    ///
+    /// [`color_adapt()`]: #method.color_adapt
+    /// [`adaptation`]: ../adaptation/index.html
+    /// # Example
+    /// Someone looking at a screen in a room lit by warmer D50 light only partially adapts to the
+    /// screen's D65 white, so that white looks slightly blue to them.
+    ///
+    /// ```
+    /// # use scarlet::prelude::*;
+    /// # use scarlet::adaptation::ChromaticAdaptation;
+    /// # use scarlet::color::XYZColor;
+    /// let screen_white = XYZColor::white_point(Illuminant::D65);
+    /// let seen = screen_white.color_adapt_with(Illuminant::D50, ChromaticAdaptation::CAT16, 0.6);
+    /// // there's no change at all without adaptation
+    /// let unadapted = screen_white.color_adapt_with(Illuminant::D50, ChromaticAdaptation::CAT16, 0.0);
+    /// assert!((unadapted.z - screen_white.z).abs() <= 1e-10);
+    /// // partial adaptation lands somewhere in between
+    /// let adapted = screen_white.color_adapt_with(Illuminant::D50, ChromaticAdaptation::CAT16, 1.0);
+    /// assert!(adapted.z < seen.z && seen.z < unadapted.z);
+    /// ```
+    pub fn color_adapt_with(
+        &self,
+        other_illuminant: Illuminant,
+        method: ChromaticAdaptation,
+        degree: f64,
+    ) -> XYZColor {
         // no need to transform if same illuminant
         if other_illuminant == self.illuminant {
             *self
         } else {
-            // convert to Bradford RGB space
-            // &* needed because lazy_static uses a different type which implements Deref
-            let rgb = &*BRADFORD * vector![self.x, self.y, self.z];
-
-            // get the RGB values for the white point of the illuminant we are currently using and
-            // the one we want: wr here stands for "white reference", i.e., the one we're converting
-            // to
-            let rgb_w = &*BRADFORD * Vector::from(self.illuminant.white_point().to_vec());
-            let rgb_wr = &*BRADFORD * Vector::from(other_illuminant.white_point().to_vec());
-
-            // perform the transform
-            // this usually includes a parameter indicating how much you want to adapt, but it's
-            // assumed that we want total adaptation: D = 1. Maybe this could change someday?
-
-            // because each white point has already been normalized to Y = 1, we don't need ap
-            // factor for it, which simplifies calculation even more than setting D = 1 and makes it
-            // just a linear transform
-            // scale by the ratio of luminance: it should always be 1, but with rounding error it
-            // isn't
-            let r_c = rgb[0] * rgb_wr[0] / rgb_w[0];
-            let g_c = rgb[1] * rgb_wr[1] / rgb_w[1];
-            // there's a slight nonlinearity here that I will omit
-            let b_c = rgb[2] * rgb_wr[2] / rgb_w[2];
-            // convert back to XYZ using inverse of previous matrix
-
-            // using LU decomposition for accuracy
-            let xyz_c = BRADFORD_LU
-                .solve(vector![r_c, g_c, b_c])
-                .expect("Matrix is invertible.");
-            XYZColor {
-                x: xyz_c[0],
-                y: xyz_c[1],
-                z: xyz_c[2],
-                illuminant: other_illuminant,
-            }
+            method.adapt(self, other_illuminant, degree)
         }
     }
     /// Returns `true` if the given other XYZ color's coordinates are all within acceptable error of
@@ -215,6 +219,33 @@ impl XYZColor {
         let other_c = other.color_adapt(self.illuminant);
         self.approx_equal(&other_c)
     }
+    /// Gets the correlated color temperature (CCT) of this color in Kelvin, along with its Duv: the
+    /// temperature of the black body radiator whose color is closest to this one, and the signed
+    /// distance from it in the CIE 1960 UCS, positive if this color is greener and negative if it is
+    /// pinker. These are computed using Ohno's method. CCT is really only meaningful for colors close
+    /// to white, with a Duv of at most 0.05 or so. The chromaticity of this color is used as is,
+    /// without adapting it to any illuminant, and temperatures are clamped between 1000 K and 25000
+    /// K, the same range as [`Illuminant::from_cct`]. Black has no chromaticity, so its CCT is
+    /// undefined: this returns `None` for it and for colors with NaN or infinite coordinates.
+    ///
+    /// [`Illuminant::from_cct`]: ../illuminants/enum.Illuminant.html#method.from_cct
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::color::XYZColor;
+    /// # use scarlet::illuminants::Illuminant;
+    /// // D65 has a CCT of 6504 K, and is slightly green of the Planckian locus
+    /// let (cct, duv) = XYZColor::white_point(Illuminant::D65).cct().unwrap();
+    /// assert!((cct - 6504.0).abs() <= 5.0);
+    /// assert!((duv - 0.0032).abs() <= 1e-4);
+    /// ```
+    pub fn cct(&self) -> Option<(f64, f64)> {
+        let denom = self.x + 15.0 * self.y + 3.0 * self.z;
+        if denom == 0.0 {
+            return None;
+        }
+        ohno_cct(4.0 * self.x / denom, 6.0 * self.y / denom)
+    }
     /// Gets the XYZColor corresponding to pure white in the given light environment.
     /// # Example
     ///
@@ -648,107 +679,57 @@ pub trait Color: Sized {
     /// assert!(green1.distance(&green2) / blue1.distance(&blue2) < 0.992);
     /// ```
     fn distance<T: Color>(&self, other: &T) -> f64 {
-        // implementation reference found here:
-        // https://pdfs.semanticscholar.org/969b/c38ea067dd22a47a44bcb59c23807037c8d8.pdf
-
-        // I'm going to match the notation in that text pretty much exactly: it's the only way to
-        // keep this both concise and readable
-
-        // first convert to LAB
-        let lab1: CIELABColor = self.convert();
-        let lab2: CIELABColor = other.convert();
-        // step 1: calculation of C and h
-        // the method hypot returns sqrt(a^2 + b^2)
-        let c_star_1: f64 = lab1.a.hypot(lab1.b);
-        let c_star_2: f64 = lab2.a.hypot(lab2.b);
-
-        let c_bar_ab: f64 = (c_star_1 + c_star_2) / 2.0;
-        let g = 0.5 * (1.0 - ((c_bar_ab.powi(7)) / (c_bar_ab.powi(7) + 25.0f64.powi(7))).sqrt());
-
-        let a_prime_1 = (1.0 + g) * lab1.a;
-        let a_prime_2 = (1.0 + g) * lab2.a;
-
-        let c_prime_1 = a_prime_1.hypot(lab1.b);
-        let c_prime_2 = a_prime_2.hypot(lab2.b);
-
-        // this closure simply does the atan2 like CIELCH, but safely accounts for a == b == 0
-        // we're gonna do this twice, so I just use a closure
-        let h_func = |a: f64, b: f64| {
-            if a == 0.0 && b == 0.0 {
-                0.0
-            } else {
-                let val = b.atan2(a).to_degrees();
-                if val < 0.0 {
-                    val + 360.0
-                } else {
-                    val
-                }
-            }
-        };
-
-        let h_prime_1 = h_func(a_prime_1, lab1.b);
-        let h_prime_2 = h_func(a_prime_2, lab2.b);
-
-        // step 2: computing delta L, delta C, and delta H
-        // take a deep breath, you got this!
-
-        let delta_l = lab2.l - lab1.l;
-        let delta_c = c_prime_2 - c_prime_1;
-        // essentially, compute the difference in hue but keep it in the right range
-        let delta_angle_h = if c_prime_1 * c_prime_2 == 0.0 {
-            0.0
-        } else if (h_prime_2 - h_prime_1).abs() <= 180.0 {
-            h_prime_2 - h_prime_1
-        } else if h_prime_2 - h_prime_1 > 180.0 {
-            h_prime_2 - h_prime_1 - 360.0
-        } else {
-            h_prime_2 - h_prime_1 + 360.0
-        };
-        // now get the Cartesian equivalent of the angle difference in hue
-        // this also corrects for chromaticity mattering less at low luminances
-        let delta_h =
-            2.0 * (c_prime_1 * c_prime_2).sqrt() * (delta_angle_h / 2.0).to_radians().sin();
-
-        // step 3: the color difference
-        // if you're reading this, it's not too late to back out
-        let l_bar_prime = (lab1.l + lab2.l) / 2.0;
-        let c_bar_prime = (c_prime_1 + c_prime_2) / 2.0;
-        let h_bar_prime = if c_prime_1 * c_prime_2 == 0.0 {
-            h_prime_1 + h_prime_2
-        } else if (h_prime_2 - h_prime_1).abs() <= 180.0 {
-            (h_prime_1 + h_prime_2) / 2.0
-        } else if h_prime_1 + h_prime_2 < 360.0 {
-            (h_prime_1 + h_prime_2 + 360.0) / 2.0
-        } else {
-            (h_prime_1 + h_prime_2 - 360.0) / 2.0
-        };
-
-        // we're gonna use this a lot
-        let deg_cos = |x: f64| x.to_radians().cos();
+        self.distance_with(other, DeltaE::default())
+    }
 
-        let t = 1.0 - 0.17 * deg_cos(h_bar_prime - 30.0)
-            + 0.24 * deg_cos(2.0 * h_bar_prime)
-            + 0.32 * deg_cos(3.0 * h_bar_prime + 6.0)
-            - 0.20 * deg_cos(4.0 * h_bar_prime - 63.0);
+    /// Returns the distance between the given color and another using any of the color difference
+    /// formulas in [`DeltaE`](../delta_e/enum.DeltaE.html), with whatever parametric weights are
+    /// needed. The color this is called on is treated as the reference color, which matters for the
+    /// formulas that aren't symmetric. [`distance`](#method.distance) is the same as calling this
+    /// with `DeltaE::default()`, which is CIEDE2000 with all weights equal to 1.
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::prelude::*;
+    /// # use scarlet::delta_e::DeltaE;
+    /// let standard = RGBColor{r: 0.2, g: 0.5, b: 0.3};
+    /// let sample = RGBColor{r: 0.22, g: 0.5, b: 0.29};
+    /// // CMC 2:1, as is common for textiles
+    /// let cmc = standard.distance_with(&sample, DeltaE::CMC{l: 2., c: 1.});
+    /// // CIE76, which is just the distance in CIELAB
+    /// let de76 = standard.distance_with(&sample, DeltaE::CIE76);
+    /// assert_eq!(standard.distance_with(&sample, DeltaE::default()), standard.distance(&sample));
+    /// println!("{} {}", cmc, de76);
+    /// ```
+    fn distance_with<T: Color>(&self, other: &T, method: DeltaE) -> f64 {
+        method.distance(self, other)
+    }
 
-        let delta_theta = 30.0 * (-((h_bar_prime - 275.0) / 25.0).powi(2)).exp();
-        let r_c = 2.0 * (c_bar_prime.powi(7) / (c_bar_prime.powi(7) + 25.0f64.powi(7))).sqrt();
-        let s_l = 1.0
-            + ((0.015 * (l_bar_prime - 50.0).powi(2))
-                / (20.0 + (l_bar_prime - 50.0).powi(2)).sqrt());
-        let s_c = 1.0 + 0.045 * c_bar_prime;
-        let s_h = 1.0 + 0.015 * c_bar_prime * t;
-        let r_t = -r_c * (2.0 * delta_theta).to_radians().sin();
-        // finally, the end result
-        // in the original there are three parametric weights, used for weighting differences in
-        // lightness, chroma, or hue. In pretty much any application, including this one, all of
-        // these are 1, so they're omitted
-        ((delta_l / s_l).powi(2)
-            + (delta_c / s_c).powi(2)
-            + (delta_h / s_h).powi(2)
-            + r_t * (delta_c / s_c) * (delta_h / s_h))
-            .sqrt()
+    /// Returns a metric of the distance between the given color and another, computed as the
+    /// Euclidean distance between them in [CAM16-UCS](../colors/cam16ucscolor/index.html) under the
+    /// default viewing conditions. This agrees with human perception about as well as
+    /// [`distance`](#method.distance) does, but is on a different scale: a difference of 1 in
+    /// CAM16-UCS is noticeably smaller than a difference of 1 in CIEDE2000.
+    ///
+    /// The advantage of this metric is that it's smooth. CIEDE2000 has discontinuities where the mean
+    /// hue angle jumps from one branch to the other, so small changes in a color can cause jumps in
+    /// the distance. If you're optimizing colors numerically, this is usually a better choice.
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::prelude::*;
+    /// let red = RGBColor{r: 0.8, g: 0.1, b: 0.1};
+    /// let orange = RGBColor{r: 0.8, g: 0.4, b: 0.1};
+    /// let dark_red = RGBColor{r: 0.4, g: 0.05, b: 0.05};
+    /// // symmetric, like any distance
+    /// assert!((red.cam16_distance(&orange) - orange.cam16_distance(&red)).abs() <= 1e-10);
+    /// assert!(red.cam16_distance(&red) <= 1e-10);
+    /// println!("{} {}", red.cam16_distance(&orange), red.cam16_distance(&dark_red));
+    /// ```
+    fn cam16_distance<T: Color>(&self, other: &T) -> f64 {
+        self.distance_with(other, DeltaE::CAM16UCS)
     }
+
     /// Using the metric that two colors with a CIEDE2000 distance of less than 1 are
     /// indistinguishable, determines whether two colors are visually distinguishable from each
     /// other. For more, check out [this guide](../color_distance.html).
@@ -769,6 +750,28 @@ pub trait Color: Sized {
     fn visually_indistinguishable<T: Color>(&self, other: &T) -> bool {
         self.distance(other) <= 1.0
     }
+
+    /// Gets the relative luminance of this color as defined by WCAG 2: the luminance of the color in
+    /// sRGB, from 0 for black to 1 for white. Colors outside of the sRGB gamut are clipped to it
+    /// first. This is the basis of the WCAG contrast ratio: see the
+    /// [`contrast`](../contrast/index.html) module for that.
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::prelude::*;
+    /// let white = RGBColor{r: 1., g: 1., b: 1.};
+    /// let green = RGBColor{r: 0., g: 1., b: 0.};
+    /// let blue = RGBColor{r: 0., g: 0., b: 1.};
+    /// assert!((white.relative_luminance() - 1.0).abs() <= 1e-10);
+    /// // green is far brighter than blue
+    /// assert!((green.relative_luminance() - 0.7152).abs() <= 1e-10);
+    /// assert!((blue.relative_luminance() - 0.0722).abs() <= 1e-10);
+    /// ```
+    fn relative_luminance(&self) -> f64 {
+        let rgb: RGBColor = self.convert();
+        let lin = |c: f64| SRGBTransfer.decode(c.clamp(0.0, 1.0));
+        0.2126 * lin(rgb.r) + 0.7152 * lin(rgb.g) + 0.0722 * lin(rgb.b)
+    }
 }
 
 impl Color for XYZColor {
@@ -946,14 +949,10 @@ impl Color for RGBColor {
 
         let lin_rgb_vec = &*SRGB * vector![xyz_d65.x, xyz_d65.y, xyz_d65.z];
         // now we scale for gamma correction
-        let gamma_correct = |x: &f64| {
-            if x <= &0.0031308 {
-                12.92 * x
-            } else {
-                1.055 * x.powf(1.0 / 2.4) - 0.055
-            }
-        };
-        let float_vec: Vec<f64> = lin_rgb_vec.iter().map(gamma_correct).collect();
+        let float_vec: Vec<f64> = lin_rgb_vec
+            .iter()
+            .map(|&x| SRGBTransfer.encode(x))
+            .collect();
         RGBColor {
             r: float_vec[0],
             g: float_vec[1],
@@ -961,16 +960,9 @@ impl Color for RGBColor {
         }
     }
     fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
-        let uncorrect_gamma = |x: &f64| {
-            if x <= &0.04045 {
-                x / 12.92
-            } else {
-                ((x + 0.055) / 1.055).powf(2.4)
-            }
-        };
         let rgb_vec: Vector<f64> = vec![self.r, self.g, self.b]
             .iter()
-            .map(uncorrect_gamma)
+            .map(|&x| SRGBTransfer.decode(x))
             .collect();
 
         // invert the matrix multiplication used in from_xyz()
@@ -989,6 +981,38 @@ impl Color for RGBColor {
     }
 }
 
+impl ToCSS for RGBColor {
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        let p = format.precision;
+        let args = [
+            css_number(self.r * 255.0, p),
+            css_number(self.g * 255.0, p),
+            css_number(self.b * 255.0, p),
+        ];
+        css_function("rgb", &args, alpha, format)
+    }
+}
+
+impl ToCSS for XYZColor {
+    /// Uses `color(xyz-d65 ...)` for D65 colors and `color(xyz-d50 ...)` for everything else,
+    /// adapting to D50 first if need be.
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        let (space, xyz) = if self.illuminant == Illuminant::D65 {
+            ("xyz-d65", *self)
+        } else {
+            ("xyz-d50", self.color_adapt(Illuminant::D50))
+        };
+        let p = format.precision;
+        let args = [
+            space.to_string(),
+            css_number(xyz.x, p),
+            css_number(xyz.y, p),
+            css_number(xyz.z, p),
+        ];
+        css_function("color", &args, alpha, format)
+    }
+}
+
 /// An error type that results from an invalid attempt to convert a string into an RGB color.
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
 pub enum RGBParseError {
@@ -1033,6 +1057,38 @@ impl Error for RGBParseError {
 }
 
 impl RGBColor {
+    /// Gets the color of a light with the given correlated color temperature in Kelvin, as it
+    /// appears on a display without adapting to it: lower temperatures are orange and higher ones
+    /// are blue. The light is given by [`Illuminant::from_cct`], and is scaled so that it is as
+    /// bright as it can be while staying in gamut. This is useful for previewing the color of a
+    /// light bulb, for example.
+    ///
+    /// [`Illuminant::from_cct`]: ../illuminants/enum.Illuminant.html#method.from_cct
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::prelude::*;
+    /// let candle = RGBColor::from_temperature(2000.0);
+    /// let daylight = RGBColor::from_temperature(6504.0);
+    /// let sky = RGBColor::from_temperature(12000.0);
+    /// assert_eq!(candle.to_string(), "#FF8B16");
+    /// assert_eq!(daylight.to_string(), "#FFFFFF");
+    /// assert!(sky.b > sky.r);
+    /// ```
+    pub fn from_temperature(kelvin: f64) -> RGBColor {
+        let light = XYZColor::white_point(Illuminant::from_cct(kelvin));
+        // keep the light's own color, instead of adapting it to the display's white
+        let linear = LinearRGBColor::from_xyz(XYZColor {
+            illuminant: Illuminant::D65,
+            ..light
+        });
+        let max = linear.r.max(linear.g).max(linear.b);
+        RGBColor::from(LinearRGBColor {
+            r: (linear.r / max).clamp(0.0, 1.0),
+            g: (linear.g / max).clamp(0.0, 1.0),
+            b: (linear.b / max).clamp(0.0, 1.0),
+        })
+    }
     /// Given a string that represents a hex code, returns the RGB color that the given hex code
     /// represents. Four formats are accepted: `"#rgb"` as a shorthand for `"#rrggbb"`, `#rrggbb` by
     /// itself, and either of those formats without `#`: `"rgb"` or `"rrggbb"` are acceptable. Returns
@@ -1202,6 +1258,86 @@ mod tests {
         assert_eq!(c2.to_string(), "#F4B621");
         assert_eq!(c3.to_string(), "#00FF00");
     }
+    #[test]
+    fn test_cct() {
+        // published CCTs from CIE 15:2018
+        let expected = [
+            (Illuminant::A, 2856.0),
+            (Illuminant::C, 6774.0),
+            (Illuminant::D50, 5003.0),
+            (Illuminant::D65, 6504.0),
+            (Illuminant::F2, 4230.0),
+            (Illuminant::F7, 6500.0),
+            (Illuminant::F11, 4000.0),
+            (Illuminant::LEDB3, 4103.0),
+        ];
+        for &(illuminant, cct) in expected.iter() {
+            let (cct2, duv) = XYZColor::white_point(illuminant).cct().unwrap();
+            assert!((cct2 - cct).abs() <= 10.0, "{:?} {}", illuminant, cct2);
+            assert!(duv.abs() <= 0.005);
+        }
+        // the CCT of an illuminant made from a CCT should be the same
+        for &t in [
+            1500.0, 2700.0, 3999.0, 4001.0, 5500.0, 9000.0, 15000.0, 20000.0, 25000.0,
+        ]
+        .iter()
+        {
+            let (cct, _) = XYZColor::white_point(Illuminant::from_cct(t))
+                .cct()
+                .unwrap();
+            assert!((cct - t).abs() <= 0.05 * t, "{} {}", t, cct);
+        }
+        // the CCT doesn't depend on brightness
+        let dim = XYZColor {
+            x: 0.3,
+            y: 0.3,
+            z: 0.3,
+            illuminant: Illuminant::D65,
+        };
+        let bright = XYZColor {
+            x: 0.9,
+            y: 0.9,
+            z: 0.9,
+            illuminant: Illuminant::D65,
+        };
+        let (dim_cct, dim_duv) = dim.cct().unwrap();
+        let (bright_cct, bright_duv) = bright.cct().unwrap();
+        assert!((dim_cct - bright_cct).abs() <= 1e-6);
+        assert!((dim_duv - bright_duv).abs() <= 1e-6);
+        // black has no chromaticity, and so no CCT
+        let black = XYZColor {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            illuminant: Illuminant::D65,
+        };
+        assert_eq!(black.cct(), None);
+        let nan = XYZColor {
+            x: f64::NAN,
+            y: 0.5,
+            z: 0.5,
+            illuminant: Illuminant::D65,
+        };
+        assert_eq!(nan.cct(), None);
+    }
+
+    #[test]
+    fn test_from_temperature() {
+        // Mitchell Charity's black body color table, for CIE 1931 2° and sRGB
+        let expected = [(2000.0, (255, 137, 18)), (3000.0, (255, 180, 107))];
+        for &(t, (r, g, b)) in expected.iter() {
+            let (r2, g2, b2) = RGBColor::from_temperature(t).int_rgb_tup();
+            assert_eq!(r2, r);
+            assert!((i32::from(g2) - g).abs() <= 5);
+            assert!((i32::from(b2) - b).abs() <= 5);
+        }
+        // daylight temperatures are about white, and higher ones are bluer
+        let (r, g, b) = RGBColor::from_temperature(6504.0).int_rgb_tup();
+        assert!(r >= 254 && g >= 254 && b >= 254);
+        let cool = RGBColor::from_temperature(20000.0);
+        assert!(cool.b > cool.g && cool.g > cool.r);
+    }
+
     #[test]
     fn test_xyz_color_adaptation() {
         // I can literally not find a single API or something that does this so I can check the
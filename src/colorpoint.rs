@@ -4,18 +4,19 @@
 //! don't require `From<Coord>`. This makes it easy to provide these for custom
 //! [`Color`](color/trait.Color.html) types.
 
-use super::geo::prelude::*;
-use super::geo::{Closest, LineString, Point};
-use color::{Color, XYZColor};
-use colors::cieluvcolor::CIELUVColor;
+use color::Color;
+use colors::uvprimecolor::UVPrimeColor;
 use coord::Coord;
-use visual_gamut::read_cie_spectral_data;
+use visual_gamut::{closest_on_locus, is_outside_locus};
 
 /// Some errors that might pop up when dealing with colors as coordinates.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ColorCalcError {
     /// Returned when the number of weights given and the number of colors being averaged differ.
     MismatchedWeights,
+    /// Returned when a color's chromaticity isn't a finite number, as happens with NaN or infinite
+    /// coordinates, so it can't be compared to the range of human vision.
+    UndefinedChromaticity,
 }
 
 /// A trait that indicates that the current Color can be embedded in 3D space. This also requires
@@ -85,64 +86,44 @@ pub trait ColorPoint: Color + Into<Coord> + From<Coord> + Clone + Copy {
 
     /// Returns `true` if the color is outside the range of human vision. Uses the CIE 1931 standard
     /// observer spectral data.
-    fn is_imaginary(&self) -> bool {
-        let (_wavelengths, xyz_data) = read_cie_spectral_data();
-        // convert to chromaticity coordinates
-        // use the explicit formulae instead of CIELUVColor to reduce rounding errors
-        // we only care about those coordinates
-        let uv_func = |xyz: XYZColor| {
-            let denom = xyz.x + 15.0 * xyz.y + 3.0 * xyz.z;
-            (4.0 * xyz.x / denom, 9.0 * xyz.y / denom)
-        };
-        let self_uv: (f64, f64) = uv_func(self.convert());
-        let uv_data: Vec<(f64, f64)> = xyz_data.into_iter().map(uv_func).collect();
-        let self_point = Point::new(self_uv.0, self_uv.1);
-
-        // this is an annoying algorithm, so I'm using a crate instead
-        let line: LineString<f64> = uv_data.into();
-        line.contains(&self_point)
+    /// # Errors
+    /// Returns `ColorCalcError::UndefinedChromaticity` if the color's chromaticity can't be
+    /// computed, because it has NaN or infinite coordinates.
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::prelude::*;
+    /// # use scarlet::colors::ROMMRGBColor;
+    /// // every sRGB color is real
+    /// assert!(!RGBColor{r: 0., g: 0., b: 1.}.is_imaginary().unwrap());
+    /// // but ProPhoto RGB's blue primary isn't
+    /// let blue = ROMMRGBColor{r: 0., g: 0., b: 1.};
+    /// assert!(blue.is_imaginary().unwrap());
+    /// ```
+    fn is_imaginary(&self) -> Result<bool, ColorCalcError> {
+        // convert to chromaticity coordinates: we only care about those
+        let self_uv: UVPrimeColor = self.convert();
+        is_outside_locus(self_uv.u, self_uv.v).ok_or(ColorCalcError::UndefinedChromaticity)
     }
 
-    /// Returns the closest color that can be seen by the human eye. If the color is not imaginary,
-    /// returns itself.
-    fn closest_real_color(&self) -> Self {
+    /// Returns the closest color that can be seen by the human eye, keeping the same luminance. If
+    /// the color is not imaginary, returns itself.
+    /// # Errors
+    /// Returns `ColorCalcError::UndefinedChromaticity` if the color's chromaticity can't be
+    /// computed, because it has NaN or infinite coordinates.
+    fn closest_real_color(&self) -> Result<Self, ColorCalcError> {
         // if real color, return itself
-        if !self.is_imaginary() {
-            *self
+        if !self.is_imaginary()? {
+            Ok(*self)
         } else {
-            let (_wavelengths, xyz_data) = read_cie_spectral_data();
-            // convert to chromaticity coordinates
-            // use the explicit formulae instead of CIELUVColor to reduce rounding errors
-            // we only care about those coordinates
-            let uv_func = |xyz: XYZColor| {
-                let denom = xyz.x + 15.0 * xyz.y + 3.0 * xyz.z;
-                (4.0 * xyz.x / denom, 9.0 * xyz.y / denom)
-            };
-            // we need to keep luminance data to convert back, so we use CIELUV explicitly
-            let mut self_luv: CIELUVColor = self.convert();
-            let self_uv = (self_luv.u, self_luv.v);
-            let uv_data: Vec<(f64, f64)> = xyz_data.into_iter().map(uv_func).collect();
-            let self_point = Point::new(self_uv.0, self_uv.1);
-
-            // this is also an annoying algorithm: just use the crate
-            let line: LineString<f64> = uv_data.into();
-            let closest_point = line.closest_point(&self_point);
+            // convert to chromaticity coordinates, keeping luminance so we can convert back
+            let mut self_uv: UVPrimeColor = self.convert();
+            let (u, v) = closest_on_locus(self_uv.u, self_uv.v)
+                .ok_or(ColorCalcError::UndefinedChromaticity)?;
+            self_uv.u = u;
+            self_uv.v = v;
             // convert back into original type
-            match closest_point {
-                Closest::Intersection(p) => {
-                    self_luv.u = p.x();
-                    self_luv.v = p.y();
-                }
-                Closest::SinglePoint(p) => {
-                    self_luv.u = p.x();
-                    self_luv.v = p.y();
-                }
-                Closest::Indeterminate => {
-                    // should never happen
-                    panic!("Indeterminate closest point! Please report this error");
-                }
-            }
-            self_luv.convert()
+            Ok(self_uv.convert())
         }
     }
 
@@ -245,8 +226,9 @@ impl<T: Color + Into<Coord> + From<Coord> + Copy + Clone> ColorPoint for T {
 mod tests {
     #[allow(unused_imports)]
     use super::*;
-    use color::RGBColor;
+    use color::{RGBColor, XYZColor};
     use colors::cielabcolor::CIELABColor;
+    use illuminants::Illuminant;
 
     #[test]
     fn test_cielab_distance() {
@@ -264,6 +246,93 @@ mod tests {
         assert!((lab1.euclidean_distance(lab2) - 132.70150715).abs() <= 1e-7);
     }
     #[test]
+    fn test_is_imaginary() {
+        let real = [
+            CIELABColor {
+                l: 50.0,
+                a: 0.0,
+                b: 0.0,
+            },
+            CIELABColor {
+                l: 0.0,
+                a: 0.0,
+                b: 0.0,
+            },
+            CIELABColor {
+                l: 80.0,
+                a: -150.0,
+                b: 100.0,
+            },
+        ];
+        for color in real.iter() {
+            assert!(!color.is_imaginary().unwrap());
+            let closest = color.closest_real_color().unwrap();
+            assert_eq!(
+                (closest.l, closest.a, closest.b),
+                (color.l, color.a, color.b)
+            );
+        }
+        // far greener than any light can be
+        let green = CIELABColor {
+            l: 50.0,
+            a: -250.0,
+            b: 0.0,
+        };
+        assert!(green.is_imaginary().unwrap());
+        // the closest real color is on the edge of human vision, keeping the same lightness
+        let real_green = green.closest_real_color().unwrap();
+        assert!((real_green.l - 50.0).abs() <= 1e-8);
+        assert!(real_green.a > -250.0 && real_green.a < -150.0);
+        let inside = real_green.weighted_midpoint(real[0], 0.99);
+        assert!(!inside.is_imaginary().unwrap());
+
+        let nan = CIELABColor {
+            l: 50.0,
+            a: f64::NAN,
+            b: 0.0,
+        };
+        assert_eq!(
+            nan.is_imaginary(),
+            Err(ColorCalcError::UndefinedChromaticity)
+        );
+        assert_eq!(
+            nan.closest_real_color().err(),
+            Some(ColorCalcError::UndefinedChromaticity)
+        );
+    }
+    #[test]
+    fn test_is_imaginary_polarity() {
+        // the spectral locus bounds the real colors, so the check has to be whether a color is
+        // outside of the area it encloses, not whether it lies on the curve itself: the latter
+        // called nearly everything real and monochromatic light imaginary
+        let white: CIELABColor = XYZColor::white_point(Illuminant::D65).convert();
+        assert!(!white.is_imaginary().unwrap());
+        let red = RGBColor {
+            r: 1.,
+            g: 0.,
+            b: 0.,
+        };
+        assert!(!red.is_imaginary().unwrap());
+        // a negative X response is impossible for any light
+        let negative: CIELABColor = XYZColor {
+            x: -0.2,
+            y: 0.5,
+            z: 0.5,
+            illuminant: Illuminant::D65,
+        }
+        .convert();
+        assert!(negative.is_imaginary().unwrap());
+        // and so is anything past the line of purples
+        let purple: CIELABColor = XYZColor {
+            x: 0.5,
+            y: 0.01,
+            z: 0.5,
+            illuminant: Illuminant::D65,
+        }
+        .convert();
+        assert!(purple.is_imaginary().unwrap());
+    }
+    #[test]
     fn test_grad_scale() {
         let start = RGBColor::from_hex_code("#11457c").unwrap();
         let end = RGBColor::from_hex_code("#774bdc").unwrap();
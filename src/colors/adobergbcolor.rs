@@ -7,7 +7,12 @@ use color::{Color, XYZColor};
 use consts::ADOBE_RGB_TRANSFORM as ADOBE_RGB;
 use consts::ADOBE_RGB_TRANSFORM_LU as ADOBE_RGB_LU;
 use coord::Coord;
+use csscolor::{css_function, css_number, CSSFormat, ToCSS};
 use illuminants::Illuminant;
+use transfer::{Gamma, TransferFunction};
+
+/// The transfer function of Adobe RGB, a pure power function with exponent 563/256, about 2.2.
+const ADOBE_GAMMA: Gamma = Gamma(563.0 / 256.0);
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 /// A color in the Adobe RGB color space. This is a rarer color space, but one that is still pretty
@@ -67,22 +72,21 @@ impl Color for AdobeRGBColor {
         };
 
         // now we apply gamma transformation
-        let gamma = |x: f64| x.powf(256.0 / 563.0);
-
         AdobeRGBColor {
-            r: gamma(clamp(rgb[0])),
-            g: gamma(clamp(rgb[1])),
-            b: gamma(clamp(rgb[2])),
+            r: ADOBE_GAMMA.encode(clamp(rgb[0])),
+            g: ADOBE_GAMMA.encode(clamp(rgb[1])),
+            b: ADOBE_GAMMA.encode(clamp(rgb[2])),
         }
     }
     /// Converts from Adobe RGB to an XYZ color in a given illuminant (via chromatic adaptation).
     fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
-        // undo gamma transformation
-        let ungamma = |x: f64| x.powf(563.0 / 256.0);
-
         // more efficient/accurate than using inverses
         let xyz_vec = ADOBE_RGB_LU
-            .solve(vector![ungamma(self.r), ungamma(self.g), ungamma(self.b)])
+            .solve(vector![
+                ADOBE_GAMMA.decode(self.r),
+                ADOBE_GAMMA.decode(self.g),
+                ADOBE_GAMMA.decode(self.b)
+            ])
             .expect("Matrix is invertible.");
 
         XYZColor {
@@ -121,6 +125,19 @@ impl Bound for AdobeRGBColor {
     }
 }
 
+impl ToCSS for AdobeRGBColor {
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        let p = format.precision;
+        let args = [
+            "a98-rgb".to_string(),
+            css_number(self.r, p),
+            css_number(self.g, p),
+            css_number(self.b, p),
+        ];
+        css_function("color", &args, alpha, format)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
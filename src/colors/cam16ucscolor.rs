@@ -0,0 +1,161 @@
+//! This module implements CAM16-UCS, the uniform color space built on top of the CAM16 color
+//! appearance model (see the [`cam16`](../../cam16/index.html) module). CAM16 itself predicts
+//! appearance very well, but its lightness, colorfulness, and hue correlates aren't laid out so that
+//! equal distances correspond to equal visual differences. CAM16-UCS compresses lightness and
+//! colorfulness so that they are, which makes plain Euclidean distance a very good color difference
+//! metric: one that, unlike CIEDE2000, is smooth everywhere.
+
+use cam16::{CAM16Color, ViewingConditions};
+use color::{Color, XYZColor};
+use coord::Coord;
+use csscolor::ToCSS;
+use illuminants::Illuminant;
+
+/// A color in the CAM16-UCS uniform color space. Like CIELAB and Oklab, this has a lightness
+/// component and two opponent color axes, but it depends on the viewing conditions, using the
+/// default [`ViewingConditions`] unless specified otherwise.
+/// # Example
+/// Distances in CAM16-UCS are a good match for perceived differences, so it's easy to check that
+/// the same step in sRGB is much more visible near black than near white.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::colors::CAM16UCSColor;
+/// let black: CAM16UCSColor = RGBColor{r: 0., g: 0., b: 0.}.convert();
+/// let dark_gray: CAM16UCSColor = RGBColor{r: 0.05, g: 0.05, b: 0.05}.convert();
+/// let light_gray: CAM16UCSColor = RGBColor{r: 0.95, g: 0.95, b: 0.95}.convert();
+/// let white: CAM16UCSColor = RGBColor{r: 1., g: 1., b: 1.}.convert();
+/// assert!(black.euclidean_distance(dark_gray) > light_gray.euclidean_distance(white));
+/// ```
+///
+/// [`ViewingConditions`]: ../../cam16/struct.ViewingConditions.html
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct CAM16UCSColor {
+    /// The compressed lightness, *J'*. Ranges from 0 to 100 for colors no brighter than white.
+    pub j: f64,
+    /// The red-green opponent axis, *a'*. Positive values are red and negative values are green.
+    pub a: f64,
+    /// The yellow-blue opponent axis, *b'*. Positive values are yellow and negative values are blue.
+    pub b: f64,
+}
+
+impl CAM16UCSColor {
+    /// Gets the CAM16-UCS coordinates of a color with the appearance given by CAM16 under the given
+    /// viewing conditions.
+    pub fn from_xyz_with(xyz: XYZColor, conditions: &ViewingConditions) -> CAM16UCSColor {
+        let cam = CAM16Color::from_xyz_with(xyz, conditions);
+        // colorfulness and lightness are compressed: the hue stays the same
+        let j = 1.7 * cam.j / (1.0 + 0.007 * cam.j);
+        let m = (1.0 + 0.0228 * cam.m).ln() / 0.0228;
+        let (sin, cos) = cam.h.to_radians().sin_cos();
+        CAM16UCSColor {
+            j,
+            a: m * cos,
+            b: m * sin,
+        }
+    }
+    /// Converts back to an XYZ color in the given illuminant, under the given viewing conditions.
+    pub fn to_xyz_with(&self, conditions: &ViewingConditions, illuminant: Illuminant) -> XYZColor {
+        let j = self.j / (1.7 - 0.007 * self.j);
+        let m = ((0.0228 * self.a.hypot(self.b)).exp() - 1.0) / 0.0228;
+        let unbounded_h = self.b.atan2(self.a).to_degrees();
+        let h = if unbounded_h < 0.0 {
+            unbounded_h + 360.0
+        } else {
+            unbounded_h
+        };
+        // the inverse model only needs lightness, chroma, and hue, but colorfulness is what's stored
+        let c = m / conditions.derived().f_l.powf(0.25);
+        CAM16Color {
+            j,
+            c,
+            h,
+            m,
+            // these aren't used in the inverse model
+            s: 0.0,
+            q: 0.0,
+        }
+        .to_xyz_with(conditions, illuminant)
+    }
+}
+
+impl Color for CAM16UCSColor {
+    /// Converts from XYZ to CAM16-UCS using the default viewing conditions. To use different ones,
+    /// use [`from_xyz_with`](#method.from_xyz_with).
+    fn from_xyz(xyz: XYZColor) -> CAM16UCSColor {
+        CAM16UCSColor::from_xyz_with(xyz, &ViewingConditions::default())
+    }
+    /// Converts from CAM16-UCS to XYZ using the default viewing conditions. To use different ones,
+    /// use [`to_xyz_with`](#method.to_xyz_with).
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        self.to_xyz_with(&ViewingConditions::default(), illuminant)
+    }
+}
+
+impl From<Coord> for CAM16UCSColor {
+    fn from(c: Coord) -> CAM16UCSColor {
+        CAM16UCSColor {
+            j: c.x,
+            a: c.y,
+            b: c.z,
+        }
+    }
+}
+
+impl From<CAM16UCSColor> for Coord {
+    fn from(val: CAM16UCSColor) -> Coord {
+        Coord {
+            x: val.j,
+            y: val.a,
+            z: val.b,
+        }
+    }
+}
+
+impl ToCSS for CAM16UCSColor {}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use cam16::Surround;
+    use consts::TEST_PRECISION;
+
+    #[test]
+    fn test_cam16_ucs_reference_values() {
+        // the same example as in the cam16 tests, with the reference J, M, and h compressed using the
+        // formulas in the paper
+        let white = Illuminant::Custom([0.9505, 1.0, 1.0888]);
+        let conditions = ViewingConditions {
+            white_point: white,
+            adapting_luminance: 318.31,
+            background_luminance: 20.0,
+            surround: Surround::Average,
+        };
+        let xyz = XYZColor {
+            x: 0.1901,
+            y: 0.2000,
+            z: 0.2178,
+            illuminant: white,
+        };
+        let ucs = CAM16UCSColor::from_xyz_with(xyz, &conditions);
+        assert!((ucs.j - 54.90445025).abs() <= 1e-6);
+        assert!((ucs.a - -0.08562125).abs() <= 1e-6);
+        assert!((ucs.b - -0.06467961).abs() <= 1e-6);
+    }
+    #[test]
+    fn test_cam16_ucs_xyz_conversion() {
+        let xyz = XYZColor {
+            x: 0.3,
+            y: 0.53,
+            z: 0.65,
+            illuminant: Illuminant::D50,
+        };
+        let ucs: CAM16UCSColor = xyz.convert();
+        let xyz2 = ucs.to_xyz(Illuminant::D50);
+        assert!((xyz.x - xyz2.x).abs() <= 1e-10);
+        assert!((xyz.y - xyz2.y).abs() <= 1e-10);
+        assert!((xyz.z - xyz2.z).abs() <= 1e-10);
+        assert!(xyz.distance(&xyz2) <= TEST_PRECISION);
+    }
+}
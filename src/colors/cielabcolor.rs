@@ -8,6 +8,7 @@
 
 use color::{Color, XYZColor};
 use coord::Coord;
+use csscolor::{css_function, css_number, CSSFormat, ToCSS};
 use illuminants::Illuminant;
 
 /// A color in the CIELAB color space.
@@ -143,6 +144,18 @@ impl Into<Coord> for CIELABColor {
     }
 }
 
+impl ToCSS for CIELABColor {
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        let p = format.precision;
+        let args = [
+            css_number(self.l, p) + "%",
+            css_number(self.a, p),
+            css_number(self.b, p),
+        ];
+        css_function("lab", &args, alpha, format)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
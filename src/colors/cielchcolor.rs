@@ -5,6 +5,7 @@
 use super::cielabcolor::CIELABColor;
 use color::{Color, XYZColor};
 use coord::Coord;
+use csscolor::{css_function, css_number, CSSFormat, ToCSS};
 use illuminants::Illuminant;
 
 /// A cylindrical form of CIELAB, analogous to the relationship between HSL and RGB.
@@ -102,6 +103,18 @@ impl Into<Coord> for CIELCHColor {
     }
 }
 
+impl ToCSS for CIELCHColor {
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        let p = format.precision;
+        let args = [
+            css_number(self.l, p) + "%",
+            css_number(self.c, p),
+            css_number(self.h, p) + "deg",
+        ];
+        css_function("lch", &args, alpha, format)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
@@ -4,6 +4,7 @@
 use super::cieluvcolor::CIELUVColor;
 use color::{Color, XYZColor};
 use coord::Coord;
+use csscolor::ToCSS;
 use illuminants::Illuminant;
 
 /// The polar version of CIELUV, analogous to the relationship between CIELCH and CIELAB. Sometimes
@@ -94,6 +95,8 @@ impl Into<Coord> for CIELCHuvColor {
     }
 }
 
+impl ToCSS for CIELCHuvColor {}
+
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
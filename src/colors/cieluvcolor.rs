@@ -4,6 +4,7 @@
 
 use color::{Color, XYZColor};
 use coord::Coord;
+use csscolor::ToCSS;
 use illuminants::Illuminant;
 
 /// A similar color system to CIELAB, adapted at the same time and with similar goals. It attempts to
@@ -127,6 +128,8 @@ impl Into<Coord> for CIELUVColor {
     }
 }
 
+impl ToCSS for CIELUVColor {}
+
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
@@ -0,0 +1,530 @@
+//! This module implements RGB color spaces that are defined at the use site instead of in Scarlet,
+//! such as a calibrated monitor or a proprietary camera space. Every RGB space is described by the
+//! same few things: the chromaticities of its three primaries, its white point, and its transfer
+//! function. Implementing [`RgbSpace`](trait.RgbSpace.html) with those gives a color type,
+//! [`CustomRGBColor`](struct.CustomRGBColor.html), that works like any other in Scarlet. Spaces
+//! that are only known at runtime, like a monitor profile built from measurements, can't be a
+//! type, so [`RGBProfile`](struct.RGBProfile.html) describes them as a value instead.
+//!
+//! The matrix that goes between RGB and XYZ is derived from the primaries and white point: each
+//! primary's chromaticity gives a direction in XYZ, and the lengths along those directions are
+//! chosen so that full red, green, and blue add up to the white point. See [Bruce Lindbloom's
+//! page](http://www.brucelindbloom.com/index.html?Eqn_RGB_XYZ_Matrix.html) on the topic.
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock};
+
+use rulinalg::matrix::decomposition::PartialPivLu;
+use rulinalg::matrix::Matrix;
+
+use bound::Bound;
+use color::{Color, XYZColor};
+use coord::Coord;
+use csscolor::ToCSS;
+use illuminants::Illuminant;
+use transfer::TransferFunction;
+
+/// A description of an RGB color space. This uses associated functions instead of values so that
+/// the color space can be part of the type of
+/// [`CustomRGBColor`](struct.CustomRGBColor.html), which is what lets it implement
+/// [`Color`](../../color/trait.Color.html). The matrix is only computed once for each space, and
+/// then cached. For spaces that are only known at runtime, like measured monitor profiles, use
+/// [`RGBProfile`](struct.RGBProfile.html).
+///
+/// # Example
+/// Describe sRGB from scratch, which gives the same results as
+/// [`RGBColor`](../../color/struct.RGBColor.html).
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::colors::{CustomRGBColor, RgbSpace};
+/// # use scarlet::transfer::SRGB;
+/// struct SRGBSpace;
+/// impl RgbSpace for SRGBSpace {
+///     type Transfer = SRGB;
+///     fn primaries() -> [(f64, f64); 3] {
+///         [(0.64, 0.33), (0.30, 0.60), (0.15, 0.06)]
+///     }
+///     fn white_point() -> Illuminant {
+///         Illuminant::D65
+///     }
+///     fn transfer() -> SRGB {
+///         SRGB
+///     }
+/// }
+/// let color = CustomRGBColor::<SRGBSpace>::new(0.2, 0.6, 0.8);
+/// let rgb: RGBColor = color.convert();
+/// assert_eq!(rgb.to_string(), "#3399CC");
+/// ```
+pub trait RgbSpace: 'static {
+    /// The type of the transfer function. Any of the ones in the
+    /// [`transfer`](../../transfer/index.html) module work, as does a custom one.
+    type Transfer: TransferFunction;
+    /// The CIE 1931 xy chromaticities of the red, green, and blue primaries, in that order.
+    fn primaries() -> [(f64, f64); 3];
+    /// The white point: the color that full red, green, and blue make together.
+    fn white_point() -> Illuminant;
+    /// The transfer function, which converts between component values and linear light.
+    fn transfer() -> Self::Transfer;
+    /// The matrix that converts linear RGB to XYZ relative to the white point. This is derived from
+    /// the primaries and white point by default, but can be overridden to cache it or to use a
+    /// matrix from a specification directly.
+    ///
+    /// # Panics
+    /// If the primaries all lie on a line, no matrix exists, and this panics.
+    fn rgb_to_xyz_matrix() -> Matrix<f64> {
+        derive_rgb_to_xyz_matrix(Self::primaries(), Self::white_point())
+            .expect("Primaries are not collinear.")
+    }
+}
+
+/// Derives the matrix that converts linear RGB to XYZ from the primaries and white point, returning
+/// `None` if the primaries all lie on a line.
+fn derive_rgb_to_xyz_matrix(
+    primaries: [(f64, f64); 3],
+    white_point: Illuminant,
+) -> Option<Matrix<f64>> {
+    // the XYZ of each primary at Y = 1, as columns
+    let columns: Vec<[f64; 3]> = primaries
+        .iter()
+        .map(|&(x, y)| [x / y, 1.0, (1.0 - x - y) / y])
+        .collect();
+    let unscaled = Matrix::new(
+        3,
+        3,
+        (0..9).map(|i| columns[i % 3][i / 3]).collect::<Vec<f64>>(),
+    );
+    // scale each primary so they add up to white
+    let white = white_point.white_point();
+    let scales = PartialPivLu::decompose(unscaled.clone())
+        .ok()?
+        .solve(vector![white[0], white[1], white[2]])
+        .ok()?;
+    let matrix = Matrix::new(
+        3,
+        3,
+        (0..9)
+            .map(|i| unscaled[[i / 3, i % 3]] * scales[i % 3])
+            .collect::<Vec<f64>>(),
+    );
+    if matrix.data().iter().all(|x| x.is_finite()) {
+        Some(matrix)
+    } else {
+        None
+    }
+}
+
+/// Converts RGB components to XYZ relative to the given white, using a matrix from
+/// [`derive_rgb_to_xyz_matrix`].
+fn rgb_to_xyz<T: TransferFunction>(
+    matrix: &Matrix<f64>,
+    transfer: &T,
+    rgb: [f64; 3],
+    white_point: Illuminant,
+) -> XYZColor {
+    let xyz_vec = matrix
+        * vector![
+            transfer.decode(rgb[0]),
+            transfer.decode(rgb[1]),
+            transfer.decode(rgb[2])
+        ];
+    XYZColor {
+        x: xyz_vec[0],
+        y: xyz_vec[1],
+        z: xyz_vec[2],
+        illuminant: white_point,
+    }
+}
+
+/// Converts an XYZ color, already adapted to the space's white point, to RGB components, using the
+/// LU decomposition of the space's matrix.
+fn xyz_to_rgb<T: TransferFunction>(
+    lu: &PartialPivLu<f64>,
+    transfer: &T,
+    xyz: XYZColor,
+) -> [f64; 3] {
+    let rgb = lu
+        .solve(vector![xyz.x, xyz.y, xyz.z])
+        .expect("Primaries are not collinear.");
+    [
+        transfer.encode(rgb[0]),
+        transfer.encode(rgb[1]),
+        transfer.encode(rgb[2]),
+    ]
+}
+
+/// The matrix of an [`RgbSpace`](trait.RgbSpace.html) along with its LU decomposition, which is
+/// what converting from XYZ needs.
+struct SpaceMatrices {
+    to_xyz: Matrix<f64>,
+    lu: PartialPivLu<f64>,
+}
+
+lazy_static! {
+    // the matrices of every RgbSpace that has been used, so that they're only computed once
+    static ref SPACE_MATRICES: RwLock<HashMap<TypeId, Arc<SpaceMatrices>>> =
+        RwLock::new(HashMap::new());
+}
+
+/// Gets the cached matrices for an RGB space, computing them if this is the first time.
+fn space_matrices<S: RgbSpace>() -> Arc<SpaceMatrices> {
+    let id = TypeId::of::<S>();
+    if let Some(matrices) = SPACE_MATRICES
+        .read()
+        .expect("Matrix cache is never poisoned.")
+        .get(&id)
+    {
+        return matrices.clone();
+    }
+    let to_xyz = S::rgb_to_xyz_matrix();
+    let lu = PartialPivLu::decompose(to_xyz.clone()).expect("Primaries are not collinear.");
+    SPACE_MATRICES
+        .write()
+        .expect("Matrix cache is never poisoned.")
+        .entry(id)
+        .or_insert_with(|| Arc::new(SpaceMatrices { to_xyz, lu }))
+        .clone()
+}
+
+/// An RGB color space described by values instead of a type, for spaces that are only known at
+/// runtime, such as a monitor profile built from colorimeter measurements. Because Scarlet's color
+/// conversions are associated functions, colors in a space like this can't be a type of their own
+/// like [`CustomRGBColor`](struct.CustomRGBColor.html): instead, the profile converts between RGB
+/// components and other colors itself. The matrices are computed once, when the profile is made.
+/// # Example
+/// Build sRGB from its specification, as if it had been measured, and use it to convert colors.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::colors::{CIELABColor, RGBProfile};
+/// # use scarlet::transfer::SRGB;
+/// let primaries = [(0.64, 0.33), (0.30, 0.60), (0.15, 0.06)];
+/// let profile = RGBProfile::new(primaries, Illuminant::D65, SRGB).unwrap();
+/// let color: RGBColor = profile.to_color([0.2, 0.6, 0.8]);
+/// assert_eq!(color.to_string(), "#3399CC");
+/// let lab: CIELABColor = color.convert();
+/// let rgb = profile.from_color(&lab);
+/// assert!((rgb[1] - 0.6).abs() <= 1e-9);
+/// // primaries on a line don't make a color space
+/// let line = [(0.2, 0.2), (0.3, 0.3), (0.4, 0.4)];
+/// assert!(RGBProfile::new(line, Illuminant::D65, SRGB).is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RGBProfile<T: TransferFunction> {
+    primaries: [(f64, f64); 3],
+    white_point: Illuminant,
+    transfer: T,
+    to_xyz: Matrix<f64>,
+    lu: PartialPivLu<f64>,
+}
+
+impl<T: TransferFunction> RGBProfile<T> {
+    /// Makes a new profile from the CIE 1931 xy chromaticities of its red, green, and blue
+    /// primaries, its white point, and its transfer function. Returns `None` if the primaries all
+    /// lie on a line, in which case no matrix to and from XYZ exists.
+    pub fn new(
+        primaries: [(f64, f64); 3],
+        white_point: Illuminant,
+        transfer: T,
+    ) -> Option<RGBProfile<T>> {
+        let to_xyz = derive_rgb_to_xyz_matrix(primaries, white_point)?;
+        let lu = PartialPivLu::decompose(to_xyz.clone()).ok()?;
+        Some(RGBProfile {
+            primaries,
+            white_point,
+            transfer,
+            to_xyz,
+            lu,
+        })
+    }
+    /// The CIE 1931 xy chromaticities of the red, green, and blue primaries, in that order.
+    pub fn primaries(&self) -> [(f64, f64); 3] {
+        self.primaries
+    }
+    /// The white point: the color that full red, green, and blue make together.
+    pub fn white_point(&self) -> Illuminant {
+        self.white_point
+    }
+    /// The transfer function, which converts between component values and linear light.
+    pub fn transfer(&self) -> &T {
+        &self.transfer
+    }
+    /// The matrix that converts linear RGB to XYZ relative to the white point.
+    pub fn rgb_to_xyz_matrix(&self) -> &Matrix<f64> {
+        &self.to_xyz
+    }
+    /// Converts RGB components in this space to an XYZ color in the given illuminant (via chromatic
+    /// adaptation).
+    pub fn to_xyz(&self, rgb: [f64; 3], illuminant: Illuminant) -> XYZColor {
+        rgb_to_xyz(&self.to_xyz, &self.transfer, rgb, self.white_point).color_adapt(illuminant)
+    }
+    /// Converts an XYZ color to RGB components in this space, adapting it to the white point first.
+    /// Values outside of the gamut are not clipped.
+    pub fn from_xyz(&self, xyz: XYZColor) -> [f64; 3] {
+        xyz_to_rgb(&self.lu, &self.transfer, xyz.color_adapt(self.white_point))
+    }
+    /// Converts RGB components in this space to a color of any type.
+    pub fn to_color<C: Color>(&self, rgb: [f64; 3]) -> C {
+        C::from_xyz(self.to_xyz(rgb, self.white_point))
+    }
+    /// Converts a color of any type to RGB components in this space. Values outside of the gamut
+    /// are not clipped.
+    pub fn from_color<C: Color>(&self, color: &C) -> [f64; 3] {
+        self.from_xyz(color.to_xyz(self.white_point))
+    }
+}
+
+/// A color in an RGB space described by the type parameter, an implementation of
+/// [`RgbSpace`](trait.RgbSpace.html). The components should range from 0 to 1, but can go past
+/// that for colors outside of the gamut, which conversions don't clip: use
+/// [`Bound`](../../bound/trait.Bound.html) for that. See the [`RgbSpace`](trait.RgbSpace.html)
+/// documentation for example usage.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CustomRGBColor<S: RgbSpace> {
+    /// The red primary component.
+    pub r: f64,
+    /// The green primary component.
+    pub g: f64,
+    /// The blue primary component.
+    pub b: f64,
+    #[serde(skip)]
+    space: PhantomData<S>,
+}
+
+impl<S: RgbSpace> CustomRGBColor<S> {
+    /// Makes a new color from its red, green, and blue components. This is needed instead of a
+    /// struct literal because of the marker for the color space.
+    pub fn new(r: f64, g: f64, b: f64) -> CustomRGBColor<S> {
+        CustomRGBColor {
+            r,
+            g,
+            b,
+            space: PhantomData,
+        }
+    }
+}
+
+// these are written out instead of derived, as deriving them would require the color space marker
+// to implement them too
+impl<S: RgbSpace> Clone for CustomRGBColor<S> {
+    fn clone(&self) -> CustomRGBColor<S> {
+        *self
+    }
+}
+
+impl<S: RgbSpace> Copy for CustomRGBColor<S> {}
+
+impl<S: RgbSpace> fmt::Debug for CustomRGBColor<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomRGBColor")
+            .field("r", &self.r)
+            .field("g", &self.g)
+            .field("b", &self.b)
+            .finish()
+    }
+}
+
+impl<S: RgbSpace> Color for CustomRGBColor<S> {
+    /// Converts a given XYZ color to this RGB space, adapting it to the space's white point first.
+    fn from_xyz(xyz: XYZColor) -> CustomRGBColor<S> {
+        let xyz_c = xyz.color_adapt(S::white_point());
+        let rgb = xyz_to_rgb(&space_matrices::<S>().lu, &S::transfer(), xyz_c);
+        CustomRGBColor::new(rgb[0], rgb[1], rgb[2])
+    }
+    /// Converts from this RGB space to an XYZ color in a given illuminant (via chromatic
+    /// adaptation).
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        rgb_to_xyz(
+            &space_matrices::<S>().to_xyz,
+            &S::transfer(),
+            [self.r, self.g, self.b],
+            S::white_point(),
+        )
+        .color_adapt(illuminant)
+    }
+}
+
+impl<S: RgbSpace> From<Coord> for CustomRGBColor<S> {
+    fn from(c: Coord) -> CustomRGBColor<S> {
+        CustomRGBColor::new(c.x, c.y, c.z)
+    }
+}
+
+impl<S: RgbSpace> From<CustomRGBColor<S>> for Coord {
+    fn from(c: CustomRGBColor<S>) -> Coord {
+        Coord {
+            x: c.r,
+            y: c.g,
+            z: c.b,
+        }
+    }
+}
+
+impl<S: RgbSpace> Bound for CustomRGBColor<S> {
+    fn bounds() -> [(f64, f64); 3] {
+        [(0., 1.), (0., 1.), (0., 1.)]
+    }
+}
+
+impl<S: RgbSpace> ToCSS for CustomRGBColor<S> {}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use color::RGBColor;
+    use colors::AdobeRGBColor;
+    use consts::TEST_PRECISION;
+    use transfer::{Gamma, PQ};
+
+    // Adobe RGB, as its own specification describes it
+    struct AdobeRGB;
+    impl RgbSpace for AdobeRGB {
+        type Transfer = Gamma;
+        fn primaries() -> [(f64, f64); 3] {
+            [(0.64, 0.33), (0.21, 0.71), (0.15, 0.06)]
+        }
+        fn white_point() -> Illuminant {
+            Illuminant::D65
+        }
+        fn transfer() -> Gamma {
+            Gamma(563.0 / 256.0)
+        }
+    }
+
+    // Display P3 with a greenish white, like a monitor that hasn't been calibrated well
+    struct GreenishP3;
+    impl RgbSpace for GreenishP3 {
+        type Transfer = Gamma;
+        fn primaries() -> [(f64, f64); 3] {
+            [(0.680, 0.320), (0.265, 0.690), (0.150, 0.060)]
+        }
+        fn white_point() -> Illuminant {
+            Illuminant::Custom([0.30, 0.34, 0.36])
+        }
+        fn transfer() -> Gamma {
+            Gamma(2.2)
+        }
+    }
+
+    // HDR10: Rec. 2020 primaries with PQ
+    struct HDR10;
+    impl RgbSpace for HDR10 {
+        type Transfer = PQ;
+        fn primaries() -> [(f64, f64); 3] {
+            [(0.708, 0.292), (0.170, 0.797), (0.131, 0.046)]
+        }
+        fn white_point() -> Illuminant {
+            Illuminant::D65
+        }
+        fn transfer() -> PQ {
+            PQ::default()
+        }
+    }
+
+    #[test]
+    fn test_custom_rgb_xyz_conversion() {
+        let xyz1 = XYZColor {
+            x: 0.4,
+            y: 0.2,
+            z: 0.5,
+            illuminant: Illuminant::D75,
+        };
+        let xyz2 = CustomRGBColor::<GreenishP3>::from_xyz(xyz1).to_xyz(Illuminant::D75);
+        assert!(xyz1.approx_equal(&xyz2));
+        assert!(xyz1.distance(&xyz2) <= TEST_PRECISION);
+    }
+
+    #[test]
+    fn test_derived_matrix() {
+        // white should be exactly the white point
+        let white = CustomRGBColor::<GreenishP3>::new(1., 1., 1.)
+            .to_xyz(Illuminant::Custom([0.30, 0.34, 0.36]));
+        assert!((white.x - 0.30 / 0.34).abs() <= 1e-12);
+        assert!((white.y - 1.).abs() <= 1e-12);
+        assert!((white.z - 0.36 / 0.34).abs() <= 1e-12);
+        // the derived matrix should be close to the rounded one Scarlet uses, which is also
+        // relative to a slightly different white
+        let colors = [(0.2, 0.4, 0.6), (1., 0., 0.), (0.9, 0.8, 0.1)];
+        for &(r, g, b) in colors.iter() {
+            let custom = CustomRGBColor::<AdobeRGB>::new(r, g, b);
+            let adobe = AdobeRGBColor { r, g, b };
+            assert!(custom.distance(&adobe) <= 0.2);
+        }
+    }
+
+    #[test]
+    fn test_rgb_profile() {
+        // a profile made from the same data should match the type
+        let profile = RGBProfile::new(
+            GreenishP3::primaries(),
+            GreenishP3::white_point(),
+            GreenishP3::transfer(),
+        )
+        .unwrap();
+        let colors = [
+            (0.2, 0.4, 0.6),
+            (1., 0., 0.),
+            (0.9, 0.8, 0.1),
+            (1.2, -0.1, 0.5),
+        ];
+        for &(r, g, b) in colors.iter() {
+            let custom = CustomRGBColor::<GreenishP3>::new(r, g, b);
+            let xyz = profile.to_xyz([r, g, b], Illuminant::D50);
+            assert!(xyz.distance(&custom.to_xyz(Illuminant::D50)) <= 1e-12);
+            let rgb = profile.from_xyz(profile.to_xyz([r, g, b], profile.white_point()));
+            assert!((rgb[0] - r).abs() <= 1e-9);
+            assert!((rgb[1] - g).abs() <= 1e-9);
+            assert!((rgb[2] - b).abs() <= 1e-9);
+        }
+        assert_eq!(
+            profile.rgb_to_xyz_matrix().data(),
+            space_matrices::<GreenishP3>().to_xyz.data()
+        );
+        // primaries on a line, or with a zero y, have no matrix
+        let line = [(0.2, 0.2), (0.3, 0.3), (0.4, 0.4)];
+        assert!(RGBProfile::new(line, Illuminant::D65, Gamma(2.2)).is_none());
+        let flat = [(0.64, 0.33), (0.3, 0.0), (0.15, 0.06)];
+        assert!(RGBProfile::new(flat, Illuminant::D65, Gamma(2.2)).is_none());
+    }
+
+    #[test]
+    fn test_custom_rgb_bounds() {
+        // D65 gray needs less green to make up for the green tint of the white point
+        let gray: CustomRGBColor<GreenishP3> = RGBColor {
+            r: 0.5,
+            g: 0.5,
+            b: 0.5,
+        }
+        .convert();
+        assert!(CustomRGBColor::<GreenishP3>::in_gamut(&gray));
+        assert!(gray.g < gray.r && gray.g < gray.b);
+        let clamped: CustomRGBColor<GreenishP3> =
+            CustomRGBColor::<GreenishP3>::clamp(CustomRGBColor::new(1.2, 0.5, -0.1));
+        assert!((clamped.r - 1.0).abs() <= 1e-9);
+        assert!((clamped.g - 0.5).abs() <= 1e-9);
+        assert!(clamped.b.abs() <= 1e-6);
+    }
+
+    #[test]
+    fn test_hdr_custom_rgb() {
+        // SDR white is reference white, at 203 nits
+        let white: CustomRGBColor<HDR10> = RGBColor {
+            r: 1.,
+            g: 1.,
+            b: 1.,
+        }
+        .convert();
+        assert!((white.r - 0.5807).abs() <= 1e-3);
+        assert!((white.g - 0.5807).abs() <= 1e-3);
+        assert!((white.b - 0.5807).abs() <= 1e-3);
+        // and a 1000 nit highlight is brighter than sRGB can go
+        let highlight = CustomRGBColor::<HDR10>::new(0.7518, 0.7518, 0.7518);
+        assert!(!RGBColor::in_gamut(&highlight));
+        assert!((highlight.to_xyz(Illuminant::D65).y - 1000.0 / 203.0).abs() <= 1e-2);
+    }
+}
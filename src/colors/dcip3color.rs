@@ -0,0 +1,143 @@
+//! A module that implements the DCI-P3 color space, the standard for digital cinema projection. It
+//! shares its primaries with [Display P3](../displayp3color/index.html), but is meant for a dark
+//! theater instead of a monitor: it uses a plain gamma of 2.6 and a greenish white point of its own,
+//! the DCI white, with chromaticity (0.314, 0.351).
+
+use bound::Bound;
+use color::{Color, XYZColor};
+use consts::DCI_P3_TRANSFORM as DCI_P3;
+use consts::DCI_P3_TRANSFORM_LU as DCI_P3_LU;
+use coord::Coord;
+use csscolor::ToCSS;
+use illuminants::Illuminant;
+use transfer::{Gamma, TransferFunction};
+
+/// The DCI white point, as an `[X, Y, Z]` array normalized so that Y is 1.
+const DCI_WHITE: [f64; 3] = [0.314 / 0.351, 1.0, 0.335 / 0.351];
+/// The transfer function of DCI-P3, a pure power function.
+const DCI_GAMMA: Gamma = Gamma(2.6);
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+/// A color in the DCI-P3 color space. Each component ranges from 0 to 1. Because of the different
+/// white point, equal components aren't neutral under D65: DCI-P3 white looks slightly green next to
+/// sRGB white, as projectors are calibrated for that.
+/// # Example
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::colors::DCIP3Color;
+/// let white = DCIP3Color{r: 1., g: 1., b: 1.};
+/// let srgb_white: RGBColor = white.convert();
+/// // a bit of green shows through
+/// assert!(srgb_white.g > srgb_white.r && srgb_white.g > srgb_white.b);
+/// ```
+pub struct DCIP3Color {
+    /// The red primary component. This is a float that should range between 0 and 1.
+    pub r: f64,
+    /// The green primary component. This is a float that should range between 0 and 1.
+    pub g: f64,
+    /// The blue primary component. This is a float that should range between 0 and 1.
+    pub b: f64,
+}
+
+impl Color for DCIP3Color {
+    /// Converts a given XYZ color to DCI-P3, first adapting it to the DCI white point. Values outside
+    /// of the gamut are not clipped: use [`Bound`](../../bound/trait.Bound.html) for that.
+    fn from_xyz(xyz: XYZColor) -> DCIP3Color {
+        let xyz_c = xyz.color_adapt(Illuminant::Custom(DCI_WHITE));
+        let rgb = &*DCI_P3 * vector![xyz_c.x, xyz_c.y, xyz_c.z];
+
+        DCIP3Color {
+            r: DCI_GAMMA.encode(rgb[0]),
+            g: DCI_GAMMA.encode(rgb[1]),
+            b: DCI_GAMMA.encode(rgb[2]),
+        }
+    }
+    /// Converts from DCI-P3 to an XYZ color in a given illuminant (via chromatic adaptation).
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        let xyz_vec = DCI_P3_LU
+            .solve(vector![
+                DCI_GAMMA.decode(self.r),
+                DCI_GAMMA.decode(self.g),
+                DCI_GAMMA.decode(self.b)
+            ])
+            .expect("Matrix is invertible.");
+
+        XYZColor {
+            x: xyz_vec[0],
+            y: xyz_vec[1],
+            z: xyz_vec[2],
+            illuminant: Illuminant::Custom(DCI_WHITE),
+        }
+        .color_adapt(illuminant)
+    }
+}
+
+impl From<Coord> for DCIP3Color {
+    fn from(c: Coord) -> DCIP3Color {
+        DCIP3Color {
+            r: c.x,
+            g: c.y,
+            b: c.z,
+        }
+    }
+}
+
+impl From<DCIP3Color> for Coord {
+    fn from(c: DCIP3Color) -> Coord {
+        Coord {
+            x: c.r,
+            y: c.g,
+            z: c.b,
+        }
+    }
+}
+
+impl Bound for DCIP3Color {
+    fn bounds() -> [(f64, f64); 3] {
+        [(0., 1.), (0., 1.), (0., 1.)]
+    }
+}
+
+// CSS has no syntax for DCI-P3, so this uses color(xyz-d50 ...)
+impl ToCSS for DCIP3Color {}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use colors::DisplayP3Color;
+    use consts::TEST_PRECISION;
+
+    #[test]
+    fn test_dci_p3_xyz_conversion() {
+        let xyz1 = XYZColor {
+            x: 0.4,
+            y: 0.2,
+            z: 0.5,
+            illuminant: Illuminant::D75,
+        };
+        let xyz2 = DCIP3Color::from_xyz(xyz1).to_xyz(Illuminant::D75);
+        assert!(xyz1.approx_equal(&xyz2));
+        assert!(xyz1.distance(&xyz2) <= TEST_PRECISION);
+    }
+    #[test]
+    fn test_dci_p3_white_point() {
+        let white = DCIP3Color {
+            r: 1.,
+            g: 1.,
+            b: 1.,
+        }
+        .to_xyz(Illuminant::Custom(DCI_WHITE));
+        assert!((white.x - DCI_WHITE[0]).abs() <= 1e-8);
+        assert!((white.y - 1.).abs() <= 1e-8);
+        assert!((white.z - DCI_WHITE[2]).abs() <= 1e-8);
+        // the same primaries as Display P3, so the same gamut up to the white point
+        let p3_white = DisplayP3Color {
+            r: 1.,
+            g: 1.,
+            b: 1.,
+        };
+        assert!(DCIP3Color::in_gamut(&p3_white));
+    }
+}
@@ -0,0 +1,159 @@
+//! A module that implements the Display P3 color space, Apple's adaptation of the DCI-P3 primaries
+//! used in digital cinema for computer displays. It keeps the wider P3 primaries, which cover about
+//! half again as many colors as sRGB, but uses the D65 white point and transfer function of sRGB,
+//! so it works much like sRGB does. Most recent phones and laptops use it.
+
+use bound::Bound;
+use color::{Color, XYZColor};
+use consts::DISPLAY_P3_TRANSFORM as DISPLAY_P3;
+use consts::DISPLAY_P3_TRANSFORM_LU as DISPLAY_P3_LU;
+use coord::Coord;
+use csscolor::{css_function, css_number, CSSFormat, ToCSS};
+use illuminants::Illuminant;
+use transfer::{TransferFunction, SRGB};
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+/// A color in the Display P3 color space. Each component ranges from 0 to 1, just like in
+/// [`RGBColor`](../../color/struct.RGBColor.html), but the same numbers describe a more saturated
+/// color, as the primaries are further apart.
+/// # Example
+///
+/// The most saturated red in sRGB is well inside of Display P3.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::colors::DisplayP3Color;
+/// let red: DisplayP3Color = RGBColor{r: 1., g: 0., b: 0.}.convert();
+/// assert!(red.r < 0.95 && red.g > 0.15 && red.b > 0.1);
+/// // but the most saturated P3 red isn't in sRGB
+/// let p3_red = DisplayP3Color{r: 1., g: 0., b: 0.};
+/// assert!(!RGBColor::in_gamut(&p3_red));
+/// ```
+pub struct DisplayP3Color {
+    /// The red primary component. This is a float that should range between 0 and 1.
+    pub r: f64,
+    /// The green primary component. This is a float that should range between 0 and 1.
+    pub g: f64,
+    /// The blue primary component. This is a float that should range between 0 and 1.
+    pub b: f64,
+}
+
+impl Color for DisplayP3Color {
+    /// Converts a given XYZ color to Display P3. Display P3 is implicitly D65, so any color will be
+    /// converted to D65 before conversion. Values outside of the gamut are not clipped: use
+    /// [`Bound`](../../bound/trait.Bound.html) for that.
+    fn from_xyz(xyz: XYZColor) -> DisplayP3Color {
+        // convert to D65
+        let xyz_c = xyz.color_adapt(Illuminant::D65);
+        // matrix multiplication to get linear RGB
+        let rgb = &*DISPLAY_P3 * vector![xyz_c.x, xyz_c.y, xyz_c.z];
+
+        // now we apply gamma transformation, which is the same as sRGB's
+        DisplayP3Color {
+            r: SRGB.encode(rgb[0]),
+            g: SRGB.encode(rgb[1]),
+            b: SRGB.encode(rgb[2]),
+        }
+    }
+    /// Converts from Display P3 to an XYZ color in a given illuminant (via chromatic adaptation).
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        // more efficient/accurate than using inverses
+        let xyz_vec = DISPLAY_P3_LU
+            .solve(vector![
+                SRGB.decode(self.r),
+                SRGB.decode(self.g),
+                SRGB.decode(self.b)
+            ])
+            .expect("Matrix is invertible.");
+
+        XYZColor {
+            x: xyz_vec[0],
+            y: xyz_vec[1],
+            z: xyz_vec[2],
+            illuminant: Illuminant::D65,
+        }
+        .color_adapt(illuminant)
+    }
+}
+
+impl From<Coord> for DisplayP3Color {
+    fn from(c: Coord) -> DisplayP3Color {
+        DisplayP3Color {
+            r: c.x,
+            g: c.y,
+            b: c.z,
+        }
+    }
+}
+
+impl From<DisplayP3Color> for Coord {
+    fn from(c: DisplayP3Color) -> Coord {
+        Coord {
+            x: c.r,
+            y: c.g,
+            z: c.b,
+        }
+    }
+}
+
+impl Bound for DisplayP3Color {
+    fn bounds() -> [(f64, f64); 3] {
+        [(0., 1.), (0., 1.), (0., 1.)]
+    }
+}
+
+impl ToCSS for DisplayP3Color {
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        let p = format.precision;
+        let args = [
+            "display-p3".to_string(),
+            css_number(self.r, p),
+            css_number(self.g, p),
+            css_number(self.b, p),
+        ];
+        css_function("color", &args, alpha, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use color::RGBColor;
+    use consts::TEST_PRECISION;
+
+    #[test]
+    fn test_display_p3_xyz_conversion() {
+        let xyz1 = XYZColor {
+            x: 0.4,
+            y: 0.2,
+            z: 0.5,
+            illuminant: Illuminant::D75,
+        };
+        let xyz2 = DisplayP3Color::from_xyz(xyz1).to_xyz(Illuminant::D75);
+        assert!(xyz1.approx_equal(&xyz2));
+        assert!(xyz1.distance(&xyz2) <= TEST_PRECISION);
+    }
+    #[test]
+    fn test_display_p3_white_and_srgb_red() {
+        let white: DisplayP3Color = RGBColor {
+            r: 1.,
+            g: 1.,
+            b: 1.,
+        }
+        .convert();
+        assert!((white.r - 1.).abs() <= 1e-4);
+        assert!((white.g - 1.).abs() <= 1e-4);
+        assert!((white.b - 1.).abs() <= 1e-4);
+        // reference values from the CSS Color 4 sample code: sRGB red is (0.9175, 0.2003, 0.1386)
+        let red: DisplayP3Color = RGBColor {
+            r: 1.,
+            g: 0.,
+            b: 0.,
+        }
+        .convert();
+        assert!((red.r - 0.9175).abs() <= 1e-3);
+        assert!((red.g - 0.2003).abs() <= 1e-3);
+        assert!((red.b - 0.1386).abs() <= 1e-3);
+    }
+}
@@ -24,7 +24,7 @@ use std::str::FromStr;
 use bound::Bound;
 use color::{Color, RGBColor, XYZColor};
 use coord::Coord;
-use csscolor::{parse_hsl_hsv_tuple, CSSParseError};
+use csscolor::{css_function, css_number, parse_hsl_hsv_tuple, CSSFormat, CSSParseError, ToCSS};
 use illuminants::Illuminant;
 
 /// A color in the HSL color space, a direct transformation of the sRGB space. sHSL is used to
@@ -202,6 +202,20 @@ impl FromStr for HSLColor {
     }
 }
 
+impl ToCSS for HSLColor {
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        let p = format.precision;
+        // the legacy syntax doesn't allow units on the hue
+        let hue_unit = if format.legacy { "" } else { "deg" };
+        let args = [
+            css_number(self.h, p) + hue_unit,
+            css_number(self.s * 100.0, p) + "%",
+            css_number(self.l * 100.0, p) + "%",
+        ];
+        css_function("hsl", &args, alpha, format)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
@@ -12,7 +12,7 @@ use std::str::FromStr;
 use bound::Bound;
 use color::{Color, RGBColor, XYZColor};
 use coord::Coord;
-use csscolor::{parse_hsl_hsv_tuple, CSSParseError};
+use csscolor::{css_function, css_number, parse_hsl_hsv_tuple, CSSFormat, CSSParseError, ToCSS};
 use illuminants::Illuminant;
 
 /// An HSV color, defining parameters for hue, saturation, and value from the RGB space. This is sHSV
@@ -175,6 +175,20 @@ impl FromStr for HSVColor {
     }
 }
 
+impl ToCSS for HSVColor {
+    /// Uses `hwb()`, which describes the same colors as HSV with whiteness and blackness instead of
+    /// saturation and value.
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        let p = format.precision;
+        let args = [
+            css_number(self.h, p) + "deg",
+            css_number((1.0 - self.s) * self.v * 100.0, p) + "%",
+            css_number((1.0 - self.v) * 100.0, p) + "%",
+        ];
+        css_function("hwb", &args, alpha, format)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
@@ -0,0 +1,194 @@
+//! This module implements linear-light sRGB: the same primaries and white point as
+//! [`RGBColor`](../../color/struct.RGBColor.html), but without the sRGB transfer function, so each
+//! component is proportional to the amount of light. Mixing light happens linearly, so blending,
+//! averaging, and gradients done in this space behave like physical light does, unlike in
+//! gamma-encoded sRGB, where the middle of a gradient from black to white is too dark.
+
+use bound::Bound;
+use color::{Color, RGBColor, XYZColor};
+use consts::STANDARD_RGB_TRANSFORM as SRGB;
+use consts::STANDARD_RGB_TRANSFORM_LU as SRGB_LU;
+use coord::Coord;
+use csscolor::{css_function, css_number, CSSFormat, ToCSS};
+use illuminants::Illuminant;
+use transfer::{TransferFunction, SRGB as SRGBTransfer};
+
+/// A color in linear-light sRGB, with components that range from 0 to 1 for colors in the sRGB
+/// gamut. Converting to and from [`RGBColor`](../../color/struct.RGBColor.html) with `From` and
+/// `Into` only applies the transfer function, which is cheaper and more precise than going through
+/// XYZ with [`convert`](../../color/trait.Color.html#method.convert).
+/// # Example
+/// Mixing black and white light equally gives a color noticeably lighter than mixing the sRGB
+/// values does.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::colors::LinearRGBColor;
+/// let black = RGBColor{r: 0., g: 0., b: 0.};
+/// let white = RGBColor{r: 1., g: 1., b: 1.};
+/// let gamma_mix = black.midpoint(white);
+/// let linear_mix: RGBColor = LinearRGBColor::from(black)
+///     .midpoint(LinearRGBColor::from(white))
+///     .into();
+/// assert_eq!(gamma_mix.to_string(), "#808080");
+/// assert_eq!(linear_mix.to_string(), "#BCBCBC");
+/// ```
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct LinearRGBColor {
+    /// The amount of red light, from 0 to 1.
+    pub r: f64,
+    /// The amount of green light, from 0 to 1.
+    pub g: f64,
+    /// The amount of blue light, from 0 to 1.
+    pub b: f64,
+}
+
+impl Color for LinearRGBColor {
+    /// Converts a given XYZ color to linear sRGB, adapting to D65 first. Values outside of the gamut
+    /// are not clipped.
+    fn from_xyz(xyz: XYZColor) -> LinearRGBColor {
+        let xyz_d65 = xyz.color_adapt(Illuminant::D65);
+        let rgb = &*SRGB * vector![xyz_d65.x, xyz_d65.y, xyz_d65.z];
+        LinearRGBColor {
+            r: rgb[0],
+            g: rgb[1],
+            b: rgb[2],
+        }
+    }
+    /// Converts from linear sRGB to an XYZ color in a given illuminant (via chromatic adaptation).
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        let xyz_vec = SRGB_LU
+            .solve(vector![self.r, self.g, self.b])
+            .expect("Matrix is invertible.");
+        XYZColor {
+            x: xyz_vec[0],
+            y: xyz_vec[1],
+            z: xyz_vec[2],
+            illuminant: Illuminant::D65,
+        }
+        .color_adapt(illuminant)
+    }
+}
+
+impl From<RGBColor> for LinearRGBColor {
+    fn from(rgb: RGBColor) -> LinearRGBColor {
+        LinearRGBColor {
+            r: SRGBTransfer.decode(rgb.r),
+            g: SRGBTransfer.decode(rgb.g),
+            b: SRGBTransfer.decode(rgb.b),
+        }
+    }
+}
+
+impl From<LinearRGBColor> for RGBColor {
+    fn from(rgb: LinearRGBColor) -> RGBColor {
+        RGBColor {
+            r: SRGBTransfer.encode(rgb.r),
+            g: SRGBTransfer.encode(rgb.g),
+            b: SRGBTransfer.encode(rgb.b),
+        }
+    }
+}
+
+impl From<Coord> for LinearRGBColor {
+    fn from(c: Coord) -> LinearRGBColor {
+        LinearRGBColor {
+            r: c.x,
+            g: c.y,
+            b: c.z,
+        }
+    }
+}
+
+impl From<LinearRGBColor> for Coord {
+    fn from(c: LinearRGBColor) -> Coord {
+        Coord {
+            x: c.r,
+            y: c.g,
+            z: c.b,
+        }
+    }
+}
+
+impl Bound for LinearRGBColor {
+    fn bounds() -> [(f64, f64); 3] {
+        [(0., 1.), (0., 1.), (0., 1.)]
+    }
+}
+
+impl ToCSS for LinearRGBColor {
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        let p = format.precision;
+        let args = [
+            "srgb-linear".to_string(),
+            css_number(self.r, p),
+            css_number(self.g, p),
+            css_number(self.b, p),
+        ];
+        css_function("color", &args, alpha, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use colorpoint::ColorPoint;
+    use consts::TEST_PRECISION;
+
+    #[test]
+    fn test_linear_rgb_xyz_conversion() {
+        let xyz1 = XYZColor {
+            x: 0.4,
+            y: 0.2,
+            z: 0.5,
+            illuminant: Illuminant::D75,
+        };
+        let xyz2 = LinearRGBColor::from_xyz(xyz1).to_xyz(Illuminant::D75);
+        assert!(xyz1.approx_equal(&xyz2));
+        assert!(xyz1.distance(&xyz2) <= TEST_PRECISION);
+    }
+
+    #[test]
+    fn test_direct_conversion_matches_xyz() {
+        let colors = [
+            RGBColor::from_hex_code("#123456").unwrap(),
+            RGBColor::from_hex_code("#FF8000").unwrap(),
+            RGBColor {
+                r: 1.1,
+                g: -0.1,
+                b: 0.5,
+            },
+        ];
+        for rgb in colors.iter() {
+            let direct = LinearRGBColor::from(*rgb);
+            let via_xyz: LinearRGBColor = rgb.convert();
+            assert!((direct.r - via_xyz.r).abs() <= 1e-10);
+            assert!((direct.g - via_xyz.g).abs() <= 1e-10);
+            assert!((direct.b - via_xyz.b).abs() <= 1e-10);
+            let back = RGBColor::from(direct);
+            assert!((back.r - rgb.r).abs() <= 1e-10);
+            assert!((back.g - rgb.g).abs() <= 1e-10);
+            assert!((back.b - rgb.b).abs() <= 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_linear_gradient() {
+        let red = LinearRGBColor::from(RGBColor {
+            r: 1.,
+            g: 0.,
+            b: 0.,
+        });
+        let green = LinearRGBColor::from(RGBColor {
+            r: 0.,
+            g: 1.,
+            b: 0.,
+        });
+        let grad = red.gradient(&green);
+        let middle = grad(0.5);
+        assert!((middle.r - 0.5).abs() <= 1e-10);
+        assert!((middle.g - 0.5).abs() <= 1e-10);
+        assert_eq!(RGBColor::from(middle).to_string(), "#BCBC00");
+    }
+}
@@ -3,20 +3,42 @@
 //!
 //! [`Color`]: ../color/trait.Color.html
 pub mod adobergbcolor;
+pub mod cam16ucscolor;
 pub mod cielabcolor;
 pub mod cielchcolor;
 pub mod cielchuvcolor;
 pub mod cieluvcolor;
+pub mod customrgbcolor;
+pub mod dcip3color;
+pub mod displayp3color;
 pub mod hslcolor;
 pub mod hsvcolor;
+pub mod linearrgbcolor;
+pub mod oklabcolor;
+pub mod oklchcolor;
+pub mod rec2020color;
+pub mod rec709color;
 pub mod rommrgbcolor;
+pub mod uvprimecolor;
+pub mod xyycolor;
 
 // for convenience, use this namespace for the color objects
 pub use self::adobergbcolor::AdobeRGBColor;
+pub use self::cam16ucscolor::CAM16UCSColor;
 pub use self::cielabcolor::CIELABColor;
 pub use self::cielchcolor::CIELCHColor;
 pub use self::cielchuvcolor::CIELCHuvColor;
 pub use self::cieluvcolor::CIELUVColor;
+pub use self::customrgbcolor::{CustomRGBColor, RGBProfile, RgbSpace};
+pub use self::dcip3color::DCIP3Color;
+pub use self::displayp3color::DisplayP3Color;
 pub use self::hslcolor::HSLColor;
 pub use self::hsvcolor::HSVColor;
+pub use self::linearrgbcolor::LinearRGBColor;
+pub use self::oklabcolor::OklabColor;
+pub use self::oklchcolor::OklchColor;
+pub use self::rec2020color::Rec2020Color;
+pub use self::rec709color::Rec709Color;
 pub use self::rommrgbcolor::ROMMRGBColor;
+pub use self::uvprimecolor::UVPrimeColor;
+pub use self::xyycolor::XYYColor;
@@ -0,0 +1,184 @@
+//! This module implements the [Oklab color space](https://bottosson.github.io/posts/oklab/), a
+//! perceptual color space designed by Björn Ottosson in 2020. Oklab has the same structure as
+//! CIELAB—a lightness axis and two opponent color axes—but it was fit to modern color appearance
+//! data, which makes hue much more uniform. In particular, blues in CIELAB drift towards purple as
+//! chroma changes, which doesn't happen in Oklab. The tradeoff is that Oklab is much newer and is
+//! not a formal standard, although CSS Color Level 4 has adopted it.
+
+use color::{Color, XYZColor};
+use consts::OKLAB_LAB_TRANSFORM as OKLAB_LAB;
+use consts::OKLAB_LAB_TRANSFORM_LU as OKLAB_LAB_LU;
+use consts::OKLAB_LMS_TRANSFORM as OKLAB_LMS;
+use consts::OKLAB_LMS_TRANSFORM_LU as OKLAB_LMS_LU;
+use coord::Coord;
+use csscolor::{css_function, css_number, CSSFormat, ToCSS};
+use illuminants::Illuminant;
+
+/// A color in the Oklab color space.
+/// # Example
+/// A gradient between blue and white in Oklab keeps its hue, unlike CIELAB which passes through
+/// purple on the way.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::colors::OklabColor;
+/// let blue: OklabColor = RGBColor{r: 0., g: 0., b: 1.}.convert();
+/// let white: OklabColor = RGBColor{r: 1., g: 1., b: 1.}.convert();
+/// let grad = blue.gradient(&white);
+/// for i in 0..5 {
+///     println!("{}", grad(i as f64 / 4.).convert::<RGBColor>().to_string());
+/// }
+/// // white has no chroma, so a and b are essentially 0
+/// assert!(white.a.abs() <= 1e-3 && white.b.abs() <= 1e-3);
+/// assert!((white.l - 1.).abs() <= 1e-3);
+/// ```
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct OklabColor {
+    /// The perceived lightness of the color. Unlike CIELAB, this ranges from 0 for black to 1 for
+    /// diffuse white.
+    pub l: f64,
+    /// The green-red opponent axis. Negative values are green and positive values are red. Visible
+    /// colors are usually within -0.4 and 0.4.
+    pub a: f64,
+    /// The blue-yellow opponent axis. Negative values are blue and positive values are
+    /// yellow. Visible colors are usually within -0.4 and 0.4.
+    pub b: f64,
+}
+
+impl Color for OklabColor {
+    /// Converts a given XYZ color to Oklab. Oklab is defined relative to D65, so any other
+    /// illuminant is chromatically adapted to D65 first.
+    fn from_xyz(xyz: XYZColor) -> OklabColor {
+        let xyz_c = xyz.color_adapt(Illuminant::D65);
+        // first get the approximate cone responses
+        // &* needed because lazy_static uses a different type which implements Deref
+        let lms = &*OKLAB_LMS * vector![xyz_c.x, xyz_c.y, xyz_c.z];
+        // the nonlinearity is a simple cube root, which unlike CIELAB has no linear segment:
+        // cbrt() is used instead of powf() so that negative values don't become NaN
+        let lms_prime = vector![lms[0].cbrt(), lms[1].cbrt(), lms[2].cbrt()];
+        let lab = &*OKLAB_LAB * lms_prime;
+        OklabColor {
+            l: lab[0],
+            a: lab[1],
+            b: lab[2],
+        }
+    }
+    /// Converts from Oklab to an XYZ color in a given illuminant (via chromatic adaptation).
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        // invert each step of from_xyz() in reverse order, using LU decomposition for accuracy
+        let lms_prime = OKLAB_LAB_LU
+            .solve(vector![self.l, self.a, self.b])
+            .expect("Matrix is invertible.");
+        let lms = vector![
+            lms_prime[0].powi(3),
+            lms_prime[1].powi(3),
+            lms_prime[2].powi(3)
+        ];
+        let xyz = OKLAB_LMS_LU.solve(lms).expect("Matrix is invertible.");
+        XYZColor {
+            x: xyz[0],
+            y: xyz[1],
+            z: xyz[2],
+            illuminant: Illuminant::D65,
+        }
+        .color_adapt(illuminant)
+    }
+}
+
+impl From<Coord> for OklabColor {
+    fn from(c: Coord) -> OklabColor {
+        OklabColor {
+            l: c.x,
+            a: c.y,
+            b: c.z,
+        }
+    }
+}
+
+impl From<OklabColor> for Coord {
+    fn from(val: OklabColor) -> Coord {
+        Coord {
+            x: val.l,
+            y: val.a,
+            z: val.b,
+        }
+    }
+}
+
+impl ToCSS for OklabColor {
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        let p = format.precision;
+        let args = [
+            css_number(self.l * 100.0, p) + "%",
+            css_number(self.a, p),
+            css_number(self.b, p),
+        ];
+        css_function("oklab", &args, alpha, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use color::RGBColor;
+    use consts::TEST_PRECISION;
+
+    #[test]
+    fn test_oklab_xyz_conversion() {
+        let xyz = XYZColor {
+            x: 0.4,
+            y: 0.2,
+            z: 0.6,
+            illuminant: Illuminant::D65,
+        };
+        let lab = OklabColor::from_xyz(xyz);
+        let xyz2 = lab.to_xyz(Illuminant::D65);
+        assert!((xyz.x - xyz2.x).abs() <= 1e-10);
+        assert!((xyz.y - xyz2.y).abs() <= 1e-10);
+        assert!((xyz.z - xyz2.z).abs() <= 1e-10);
+        assert!(xyz.distance(&xyz2) <= TEST_PRECISION);
+    }
+    #[test]
+    fn test_oklab_xyz_conversion_different_illuminant() {
+        let xyz = XYZColor {
+            x: 0.3,
+            y: 0.53,
+            z: 0.65,
+            illuminant: Illuminant::D50,
+        };
+        let lab: OklabColor = xyz.convert();
+        let xyz2: XYZColor = lab.to_xyz(Illuminant::D50);
+        assert!(xyz2.approx_visually_equal(&xyz));
+        assert!(xyz.distance(&xyz2) <= TEST_PRECISION);
+    }
+    #[test]
+    fn test_oklab_reference_values() {
+        // reference values from https://bottosson.github.io/posts/oklab/, which are only given to
+        // three decimal places
+        let white: OklabColor = XYZColor {
+            x: 0.950,
+            y: 1.000,
+            z: 1.089,
+            illuminant: Illuminant::D65,
+        }
+        .convert();
+        assert!((white.l - 1.000).abs() <= 1e-3);
+        assert!(white.a.abs() <= 1e-3);
+        assert!(white.b.abs() <= 1e-3);
+        let blue: OklabColor = XYZColor {
+            x: 0.0,
+            y: 0.0,
+            z: 1.0,
+            illuminant: Illuminant::D65,
+        }
+        .convert();
+        assert!((blue.l - 0.153).abs() <= 1e-3);
+        assert!((blue.a - -1.415).abs() <= 1e-3);
+        assert!((blue.b - -0.449).abs() <= 1e-3);
+        // round trip through sRGB as well
+        let rgb = RGBColor::from_hex_code("#4B7ACD").unwrap();
+        let lab: OklabColor = rgb.convert();
+        assert_eq!(lab.convert::<RGBColor>().to_string(), "#4B7ACD");
+    }
+}
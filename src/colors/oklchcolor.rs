@@ -0,0 +1,134 @@
+//! This file implements the Oklch color space, a cylindrical transformation of Oklab that uses chroma
+//! and hue instead of two opponent color axes. It has the same relationship to Oklab that CIELCH has
+//! to CIELAB, but inherits Oklab's much more uniform hue.
+
+use super::oklabcolor::OklabColor;
+use color::{Color, XYZColor};
+use coord::Coord;
+use csscolor::{css_function, css_number, CSSFormat, ToCSS};
+use illuminants::Illuminant;
+
+/// A cylindrical form of Oklab, analogous to the relationship between CIELCH and CIELAB.
+/// # Example
+/// Changing the chroma of a blue in Oklch keeps it looking blue, whereas CIELCH shifts it towards
+/// purple.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::colors::{CIELCHColor, OklchColor};
+/// let blue = RGBColor{r: 0., g: 0., b: 1.};
+/// let mut ok_blue: OklchColor = blue.convert();
+/// let mut lch_blue: CIELCHColor = blue.convert();
+/// ok_blue.c = ok_blue.c / 2.;
+/// lch_blue.c = lch_blue.c / 2.;
+/// println!("{}", ok_blue.convert::<RGBColor>().to_string());
+/// println!("{}", lch_blue.convert::<RGBColor>().to_string());
+/// // prints #274EAB
+/// //        #4D329F
+/// ```
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct OklchColor {
+    /// The lightness component, identical to Oklab's. Ranges between 0 and 1.
+    pub l: f64,
+    /// The chroma component: the distance from the neutral gray of the same lightness. This is
+    /// always positive, and for visible colors is usually below 0.4.
+    pub c: f64,
+    /// The hue component, in degrees. Ranges from 0 to 360. Unlike CIELCH, the unique hues don't line
+    /// up with the quarter turns: red is about 30 degrees, yellow about 110, green about 140, and
+    /// blue about 265.
+    pub h: f64,
+}
+
+impl Color for OklchColor {
+    /// Converts from XYZ to Oklch by way of Oklab.
+    fn from_xyz(xyz: XYZColor) -> OklchColor {
+        let lab = OklabColor::from_xyz(xyz);
+        let c = lab.a.hypot(lab.b);
+        // atan2 gives a value between -180 and 180 degrees: shift negative angles into range
+        let unbounded_h = lab.b.atan2(lab.a).to_degrees();
+        let h = if unbounded_h < 0.0 {
+            unbounded_h + 360.0
+        } else {
+            unbounded_h
+        };
+        OklchColor { l: lab.l, c, h }
+    }
+    /// Converts from Oklch back to XYZ by way of Oklab, chromatically adapting it as Oklab does.
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        let (sin, cos) = self.h.to_radians().sin_cos();
+        OklabColor {
+            l: self.l,
+            a: self.c * cos,
+            b: self.c * sin,
+        }
+        .to_xyz(illuminant)
+    }
+}
+
+impl From<Coord> for OklchColor {
+    fn from(c: Coord) -> OklchColor {
+        OklchColor {
+            l: c.x,
+            c: c.y,
+            h: c.z,
+        }
+    }
+}
+
+impl From<OklchColor> for Coord {
+    fn from(val: OklchColor) -> Coord {
+        Coord {
+            x: val.l,
+            y: val.c,
+            z: val.h,
+        }
+    }
+}
+
+impl ToCSS for OklchColor {
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        let p = format.precision;
+        let args = [
+            css_number(self.l * 100.0, p) + "%",
+            css_number(self.c, p),
+            css_number(self.h, p) + "deg",
+        ];
+        css_function("oklch", &args, alpha, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use consts::TEST_PRECISION;
+
+    #[test]
+    fn test_oklch_xyz_conversion() {
+        let xyz = XYZColor {
+            x: 0.2,
+            y: 0.42,
+            z: 0.23,
+            illuminant: Illuminant::D65,
+        };
+        let lch: OklchColor = xyz.convert();
+        let xyz2: XYZColor = lch.to_xyz(Illuminant::D65);
+        assert!((xyz.x - xyz2.x).abs() <= 1e-10);
+        assert!((xyz.y - xyz2.y).abs() <= 1e-10);
+        assert!((xyz.z - xyz2.z).abs() <= 1e-10);
+        assert!(xyz.distance(&xyz2) <= TEST_PRECISION);
+    }
+    #[test]
+    fn test_oklch_matches_oklab() {
+        let lab = OklabColor {
+            l: 0.6,
+            a: -0.1,
+            b: -0.1,
+        };
+        let lch: OklchColor = lab.convert();
+        assert!((lch.l - 0.6).abs() <= 1e-10);
+        assert!((lch.c - 0.02f64.sqrt()).abs() <= 1e-10);
+        assert!((lch.h - 225.).abs() <= 1e-10);
+        assert!(lab.distance(&lch) <= TEST_PRECISION);
+    }
+}
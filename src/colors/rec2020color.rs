@@ -0,0 +1,161 @@
+//! A module that implements the Rec. 2020 color space (ITU-R BT.2020), the standard for UHD video.
+//! Its primaries are monochromatic, so it covers far more colors than sRGB, Display P3, or Adobe
+//! RGB. Like [Rec. 709](../rec709color/index.html), it pairs a camera OETF, given by
+//! [`BT2020`](../../transfer/struct.BT2020.html) and [`oetf`](fn.oetf.html), with the
+//! [`BT1886`](../../transfer/struct.BT1886.html) display EOTF, and
+//! [`Rec2020Color`](struct.Rec2020Color.html) converts using the EOTF.
+
+use bound::Bound;
+use color::{Color, XYZColor};
+use consts::REC_2020_TRANSFORM as REC_2020;
+use consts::REC_2020_TRANSFORM_LU as REC_2020_LU;
+use coord::Coord;
+use csscolor::ToCSS;
+use illuminants::Illuminant;
+use transfer::{TransferFunction, BT1886, BT2020};
+
+/// The BT.2020 OETF, which turns linear scene light between 0 and 1 into a code value between 0 and
+/// 1, as a camera would. This is the BT.709 OETF with more precise constants, and is shorthand for
+/// encoding with [`BT2020`](../../transfer/struct.BT2020.html).
+pub fn oetf(light: f64) -> f64 {
+    BT2020.encode(light)
+}
+
+/// The inverse of the BT.2020 OETF, which recovers linear scene light from a code value.
+pub fn inverse_oetf(value: f64) -> f64 {
+    BT2020.decode(value)
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+/// A color in the Rec. 2020 color space, given as code values between 0 and 1. As with
+/// [`Rec709Color`](../rec709color/struct.Rec709Color.html), conversions assume an ideal BT.1886
+/// reference display, and values outside of 0-1 are extended symmetrically.
+/// # Example
+///
+/// Rec. 2020 contains all of sRGB, but not the other way around.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::colors::Rec2020Color;
+/// let green = RGBColor{r: 0., g: 1., b: 0.};
+/// assert!(Rec2020Color::in_gamut(&green));
+/// let green_2020 = Rec2020Color{r: 0., g: 1., b: 0.};
+/// assert!(!RGBColor::in_gamut(&green_2020));
+/// ```
+pub struct Rec2020Color {
+    /// The red primary component. This is a float that should range between 0 and 1.
+    pub r: f64,
+    /// The green primary component. This is a float that should range between 0 and 1.
+    pub g: f64,
+    /// The blue primary component. This is a float that should range between 0 and 1.
+    pub b: f64,
+}
+
+impl Color for Rec2020Color {
+    /// Converts a given XYZ color to Rec. 2020. Rec. 2020 is implicitly D65, so any color will be
+    /// converted to D65 before conversion. Values outside of the gamut are not clipped: use
+    /// [`Bound`](../../bound/trait.Bound.html) for that.
+    fn from_xyz(xyz: XYZColor) -> Rec2020Color {
+        let xyz_c = xyz.color_adapt(Illuminant::D65);
+        let rgb = &*REC_2020 * vector![xyz_c.x, xyz_c.y, xyz_c.z];
+        let eotf = BT1886::default();
+        Rec2020Color {
+            r: eotf.encode(rgb[0]),
+            g: eotf.encode(rgb[1]),
+            b: eotf.encode(rgb[2]),
+        }
+    }
+    /// Converts from Rec. 2020 to an XYZ color in a given illuminant (via chromatic adaptation).
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        let eotf = BT1886::default();
+        let xyz_vec = REC_2020_LU
+            .solve(vector![
+                eotf.decode(self.r),
+                eotf.decode(self.g),
+                eotf.decode(self.b)
+            ])
+            .expect("Matrix is invertible.");
+
+        XYZColor {
+            x: xyz_vec[0],
+            y: xyz_vec[1],
+            z: xyz_vec[2],
+            illuminant: Illuminant::D65,
+        }
+        .color_adapt(illuminant)
+    }
+}
+
+impl From<Coord> for Rec2020Color {
+    fn from(c: Coord) -> Rec2020Color {
+        Rec2020Color {
+            r: c.x,
+            g: c.y,
+            b: c.z,
+        }
+    }
+}
+
+impl From<Rec2020Color> for Coord {
+    fn from(c: Rec2020Color) -> Coord {
+        Coord {
+            x: c.r,
+            y: c.g,
+            z: c.b,
+        }
+    }
+}
+
+impl Bound for Rec2020Color {
+    fn bounds() -> [(f64, f64); 3] {
+        [(0., 1.), (0., 1.), (0., 1.)]
+    }
+}
+
+// CSS's rec2020 is defined with a different transfer function, so this uses color(xyz-d50 ...)
+impl ToCSS for Rec2020Color {}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use bound::GamutMap;
+    use color::RGBColor;
+    use consts::TEST_PRECISION;
+
+    #[test]
+    fn test_rec2020_xyz_conversion() {
+        let xyz1 = XYZColor {
+            x: 0.4,
+            y: 0.2,
+            z: 0.5,
+            illuminant: Illuminant::D75,
+        };
+        let xyz2 = Rec2020Color::from_xyz(xyz1).to_xyz(Illuminant::D75);
+        assert!(xyz1.approx_equal(&xyz2));
+        assert!(xyz1.distance(&xyz2) <= TEST_PRECISION);
+    }
+    #[test]
+    fn test_rec2020_oetf() {
+        for &x in &[0.0, 0.01, 0.018, 0.05, 0.2, 0.5, 0.9, 1.0] {
+            assert!((inverse_oetf(oetf(x)) - x).abs() <= 1e-12);
+        }
+        // the pieces meet exactly
+        let beta = 0.018_053_968_510_807;
+        assert!((4.5 * beta - oetf(beta)).abs() <= 1e-12);
+        assert!((oetf(1.0) - 1.0).abs() <= 1e-12);
+    }
+    #[test]
+    fn test_rec2020_gamut_mapping() {
+        let green = Rec2020Color {
+            r: 0.,
+            g: 1.,
+            b: 0.,
+        };
+        let clamped = RGBColor::clamp(green);
+        let mapped = RGBColor::gamut_map_with(green, GamutMap::CSS);
+        assert!(Rec2020Color::in_gamut(&clamped));
+        assert!(RGBColor::in_gamut(&mapped));
+        assert!(mapped.g < 1.0);
+    }
+}
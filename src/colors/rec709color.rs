@@ -0,0 +1,197 @@
+//! A module that implements the Rec. 709 color space (ITU-R BT.709), the standard for HD video. It
+//! shares its primaries and white point with sRGB, but not its transfer functions, so decoding
+//! video code values as sRGB gives colors that are slightly off, especially in the shadows.
+//!
+//! Video has two transfer functions instead of one. A camera encodes the light in the scene with
+//! the BT.709 *opto-electronic transfer function*, or OETF, given by
+//! [`BT709`](../../transfer/struct.BT709.html) and [`oetf`](fn.oetf.html). A display decodes code
+//! values with the BT.1886 *electro-optical transfer function*, or EOTF, given by
+//! [`BT1886`](../../transfer/struct.BT1886.html) and [`bt1886_eotf`](fn.bt1886_eotf.html). The two
+//! aren't inverses of each other on purpose:
+//! the mismatch boosts contrast for dim viewing environments. Colors are what a viewer sees, so
+//! [`Rec709Color`](struct.Rec709Color.html) converts using the EOTF of a reference display.
+
+use bound::Bound;
+use color::{Color, XYZColor};
+use consts::REC_709_TRANSFORM as REC_709;
+use consts::REC_709_TRANSFORM_LU as REC_709_LU;
+use coord::Coord;
+use csscolor::ToCSS;
+use illuminants::Illuminant;
+use transfer::{TransferFunction, BT1886, BT709};
+
+/// The BT.709 OETF, which turns linear scene light between 0 and 1 into a code value between 0 and
+/// 1, as a camera would. This is shorthand for encoding with
+/// [`BT709`](../../transfer/struct.BT709.html).
+pub fn oetf(light: f64) -> f64 {
+    BT709.encode(light)
+}
+
+/// The inverse of the BT.709 OETF, which recovers linear scene light from a code value. This is the
+/// right choice for processing camera footage in linear light, but not for reproducing what a
+/// display shows: use [`bt1886_eotf`](fn.bt1886_eotf.html) for that.
+pub fn inverse_oetf(value: f64) -> f64 {
+    BT709.decode(value)
+}
+
+/// The BT.1886 EOTF, which turns a code value between 0 and 1 into the light a reference display
+/// emits, relative to its white. `black` is the luminance of the display's black relative to its
+/// white, which is 0 for an ideal display; with that, this is a plain power function with exponent
+/// 2.4. This is shorthand for decoding with [`BT1886`](../../transfer/struct.BT1886.html).
+/// # Example
+///
+/// ```
+/// # use scarlet::colors::rec709color::bt1886_eotf;
+/// assert_eq!(bt1886_eotf(1.0, 0.0), 1.0);
+/// assert!((bt1886_eotf(0.5, 0.0) - 0.5f64.powf(2.4)).abs() < 1e-12);
+/// // a display with a bit of backlight bleed shows code value 0 as its black
+/// assert!((bt1886_eotf(0.0, 0.001) - 0.001).abs() < 1e-12);
+/// ```
+pub fn bt1886_eotf(value: f64, black: f64) -> f64 {
+    BT1886 { black }.decode(value)
+}
+
+/// The inverse of the BT.1886 EOTF, which gives the code value that makes a display with the given
+/// relative black level emit the given relative luminance.
+pub fn bt1886_inverse_eotf(light: f64, black: f64) -> f64 {
+    BT1886 { black }.encode(light)
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+/// A color in the Rec. 709 color space, given as code values between 0 and 1: divide 8-bit full
+/// range values by 255. Conversions assume an ideal BT.1886 reference display, with a black level
+/// of 0, so the transfer function is a plain power function with exponent 2.4. Negative values and
+/// values above 1 are extended symmetrically, so out-of-gamut colors survive conversion.
+/// # Example
+///
+/// The same code values are darker in Rec. 709 than in sRGB, as the display gamma is higher.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::colors::Rec709Color;
+/// let gray = Rec709Color{r: 0.5, g: 0.5, b: 0.5};
+/// let srgb: RGBColor = gray.convert();
+/// assert!(srgb.r < 0.5);
+/// // but white is the same
+/// let white: RGBColor = Rec709Color{r: 1., g: 1., b: 1.}.convert();
+/// assert_eq!(white.to_string(), "#FFFFFF");
+/// ```
+pub struct Rec709Color {
+    /// The red primary component. This is a float that should range between 0 and 1.
+    pub r: f64,
+    /// The green primary component. This is a float that should range between 0 and 1.
+    pub g: f64,
+    /// The blue primary component. This is a float that should range between 0 and 1.
+    pub b: f64,
+}
+
+impl Color for Rec709Color {
+    /// Converts a given XYZ color to Rec. 709. Rec. 709 is implicitly D65, so any color will be
+    /// converted to D65 before conversion. Values outside of the gamut are not clipped: use
+    /// [`Bound`](../../bound/trait.Bound.html) for that.
+    fn from_xyz(xyz: XYZColor) -> Rec709Color {
+        let xyz_c = xyz.color_adapt(Illuminant::D65);
+        let rgb = &*REC_709 * vector![xyz_c.x, xyz_c.y, xyz_c.z];
+        let eotf = BT1886::default();
+        Rec709Color {
+            r: eotf.encode(rgb[0]),
+            g: eotf.encode(rgb[1]),
+            b: eotf.encode(rgb[2]),
+        }
+    }
+    /// Converts from Rec. 709 to an XYZ color in a given illuminant (via chromatic adaptation).
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        let eotf = BT1886::default();
+        let xyz_vec = REC_709_LU
+            .solve(vector![
+                eotf.decode(self.r),
+                eotf.decode(self.g),
+                eotf.decode(self.b)
+            ])
+            .expect("Matrix is invertible.");
+
+        XYZColor {
+            x: xyz_vec[0],
+            y: xyz_vec[1],
+            z: xyz_vec[2],
+            illuminant: Illuminant::D65,
+        }
+        .color_adapt(illuminant)
+    }
+}
+
+impl From<Coord> for Rec709Color {
+    fn from(c: Coord) -> Rec709Color {
+        Rec709Color {
+            r: c.x,
+            g: c.y,
+            b: c.z,
+        }
+    }
+}
+
+impl From<Rec709Color> for Coord {
+    fn from(c: Rec709Color) -> Coord {
+        Coord {
+            x: c.r,
+            y: c.g,
+            z: c.b,
+        }
+    }
+}
+
+impl Bound for Rec709Color {
+    fn bounds() -> [(f64, f64); 3] {
+        [(0., 1.), (0., 1.), (0., 1.)]
+    }
+}
+
+// CSS has no syntax for Rec. 709, so this uses color(xyz-d50 ...)
+impl ToCSS for Rec709Color {}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use color::RGBColor;
+    use consts::TEST_PRECISION;
+
+    #[test]
+    fn test_rec709_xyz_conversion() {
+        let xyz1 = XYZColor {
+            x: 0.4,
+            y: 0.2,
+            z: 0.5,
+            illuminant: Illuminant::D75,
+        };
+        let xyz2 = Rec709Color::from_xyz(xyz1).to_xyz(Illuminant::D75);
+        assert!(xyz1.approx_equal(&xyz2));
+        assert!(xyz1.distance(&xyz2) <= TEST_PRECISION);
+    }
+    #[test]
+    fn test_rec709_transfer_functions() {
+        for &x in &[0.0, 0.01, 0.018, 0.05, 0.2, 0.5, 0.9, 1.0] {
+            assert!((inverse_oetf(oetf(x)) - x).abs() <= 1e-12);
+            assert!((bt1886_inverse_eotf(bt1886_eotf(x, 0.0), 0.0) - x).abs() <= 1e-12);
+            assert!((bt1886_inverse_eotf(bt1886_eotf(x, 0.01), 0.01) - x).abs() <= 1e-12);
+        }
+        // the two pieces of the OETF meet
+        assert!((oetf(0.018) - 0.081).abs() <= 1e-3);
+        // black level lifts the darkest code value but keeps white at 1
+        assert!((bt1886_eotf(1.0, 0.01) - 1.0).abs() <= 1e-12);
+        assert!((bt1886_eotf(0.0, 0.01) - 0.01).abs() <= 1e-12);
+    }
+    #[test]
+    fn test_rec709_srgb_primaries() {
+        // same primaries as sRGB, so pure colors stay pure, up to the rounding in the sRGB matrix
+        let red: Rec709Color = RGBColor {
+            r: 1.,
+            g: 0.,
+            b: 0.,
+        }
+        .convert();
+        assert!((red.r - 1.).abs() <= 1e-3);
+        assert!(BT1886::default().decode(red.g).abs() <= 1e-3);
+        assert!(BT1886::default().decode(red.b).abs() <= 1e-3);
+    }
+}
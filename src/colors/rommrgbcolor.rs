@@ -13,7 +13,9 @@ use color::{Color, XYZColor};
 use consts::ROMM_RGB_TRANSFORM as ROMM;
 use consts::ROMM_RGB_TRANSFORM_LU as ROMM_LU;
 use coord::Coord;
+use csscolor::{css_function, css_number, CSSFormat, ToCSS};
 use illuminants::Illuminant;
+use transfer::{TransferFunction, ROMM as ROMMTransfer};
 
 /// A color in the ROMM RGB color space, also known as the ProPhoto RGB space. This is a very wide RGB
 /// gamut, wider than both Adobe RGB and sRGB, but the tradeoff is that the colors it uses as
@@ -63,28 +65,7 @@ impl Color for ROMMRGBColor {
         // &* needed because lazy_static uses a different type which implements Deref
         let rr_gg_bb = &*ROMM * vector![xyz_c.x, xyz_c.y, xyz_c.z];
 
-        // like sRGB, there's a linear part and an exponential part to the gamma conversion
-        let gamma = |x: f64| {
-            // technically the spec I cite has a truncated version of the cutoff, but why not use the
-            // exact one if it's a nicer format and probably causes fewer float issues
-            if x < (2.0f64).powf(-9.0) {
-                x * 16.0
-            } else {
-                x.powf(1.0 / 1.8)
-            }
-        };
-
-        // as the spec describes, some "flare" can occur: to fix this, we apply a small fix so that
-        // black is just really small and not 0
-        let fix_flare = |x: f64| {
-            if x < 0.03125 {
-                0.003473 + 0.0622829 * x
-            } else {
-                0.003473 + 0.996527 * x.powf(1.8)
-            }
-        };
-
-        // we also need to clamp between 0 and 1
+        // we need to clamp between 0 and 1
         let clamp = |x: f64| {
             if x < 0.0 {
                 0.0
@@ -94,11 +75,12 @@ impl Color for ROMMRGBColor {
                 x
             }
         };
-        // now just apply these in sequence
+        // like sRGB, there's a linear part and an exponential part to the gamma conversion, and then
+        // the spec applies a small fix for "flare" so that black is just really small and not 0
         ROMMRGBColor {
-            r: fix_flare(gamma(clamp(rr_gg_bb[0]))),
-            g: fix_flare(gamma(clamp(rr_gg_bb[1]))),
-            b: fix_flare(gamma(clamp(rr_gg_bb[2]))),
+            r: ROMMTransfer.encode(clamp(rr_gg_bb[0])),
+            g: ROMMTransfer.encode(clamp(rr_gg_bb[1])),
+            b: ROMMTransfer.encode(clamp(rr_gg_bb[2])),
         }
     }
     /// Converts back from ROMM RGB to XYZ. As ROMM RGB uses D50, any other illuminant given will be
@@ -107,37 +89,10 @@ impl Color for ROMMRGBColor {
     /// function, as best as the library author can compute it. This is the most likely function to
     /// give mismatches with other libraries or contain errors.
     fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
-        // undo the gamma function, find the piecewise split
-        let gamma_inv = |x: f64| {
-            if x >= 0.03125 {
-                // junction of two piecewise parts
-                // this is the exponential part
-                x.powf(1.8)
-            } else {
-                // this is the linear part
-                x / 16.0
-            }
-        };
-
-        // we have to first undo the fix_flare function: there's a different cutoff for the piecewise
-        // function, because inputting 0.03125 doesn't produce 0.03125
-        // WolframAlpha is my source for all of the calcluations
-        let fix_flare_inv = |x: f64| {
-            // fix_flare(2 ^ -9) is cutoff
-            if x >= 0.005419340625 {
-                // x originally came out of the second part of the cutoff
-                ((x - 0.003473) / 0.996527).powf(1.0 / 1.8)
-            } else {
-                // x originally came out of the first part of the cutoff
-                (x - 0.003473) / 0.0622829
-            }
-        };
-
-        // now we undo gamma the same way
-
-        let r_c = gamma_inv(fix_flare_inv(self.r));
-        let g_c = gamma_inv(fix_flare_inv(self.g));
-        let b_c = gamma_inv(fix_flare_inv(self.b));
+        // undo the flare correction and the gamma function
+        let r_c = ROMMTransfer.decode(self.r);
+        let g_c = ROMMTransfer.decode(self.g);
+        let b_c = ROMMTransfer.decode(self.b);
         // The standard brilliantly decided to not even bother adding an inverse matrix. Scarlet uses
         // LU decomposition to avoid any precision loss when solving the equation for the right
         // values. This might differ from other solutions elsewhere: trust this one, unless you have
@@ -182,6 +137,20 @@ impl Bound for ROMMRGBColor {
     }
 }
 
+impl ToCSS for ROMMRGBColor {
+    /// Uses `color(prophoto-rgb ...)`: ProPhoto RGB is another name for ROMM RGB.
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        let p = format.precision;
+        let args = [
+            "prophoto-rgb".to_string(),
+            css_number(self.r, p),
+            css_number(self.g, p),
+            css_number(self.b, p),
+        ];
+        css_function("color", &args, alpha, format)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
@@ -0,0 +1,123 @@
+//! This module implements the CIE 1976 uniform chromaticity scale, or UCS, which describes
+//! chromaticity with the coordinates u' and v'. It's a projective transformation of the xy
+//! chromaticity diagram from [xyY](../xyycolor/index.html) that makes distances in the diagram
+//! correspond more closely to perceived differences, and it's the basis of
+//! [CIELUV](../cieluvcolor/index.html). Keeping luminance alongside the chromaticity makes it a full
+//! color space.
+
+use color::{Color, XYZColor};
+use csscolor::ToCSS;
+use illuminants::Illuminant;
+
+/// A color given by its CIE 1976 u'v' chromaticity and its luminance. Like
+/// [`XYZColor`](../../color/struct.XYZColor.html), this keeps the illuminant that it is relative to,
+/// and the chromaticity is that of the XYZ color it came from, without chromatic adaptation.
+/// # Example
+/// Chromaticity doesn't depend on luminance, so darkening a color doesn't change u' and v'.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::color::XYZColor;
+/// # use scarlet::colors::UVPrimeColor;
+/// let color = XYZColor{x: 0.3, y: 0.4, z: 0.2, illuminant: Illuminant::D65};
+/// let dark = XYZColor{x: 0.03, y: 0.04, z: 0.02, illuminant: Illuminant::D65};
+/// let uv1 = UVPrimeColor::from_xyz(color);
+/// let uv2 = UVPrimeColor::from_xyz(dark);
+/// assert!((uv1.u - uv2.u).abs() < 1e-12 && (uv1.v - uv2.v).abs() < 1e-12);
+/// assert!((uv1.luminance - 10. * uv2.luminance).abs() < 1e-12);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UVPrimeColor {
+    /// The u' chromaticity coordinate, 4X / (X + 15Y + 3Z). Visible colors range from 0 to about
+    /// 0.62.
+    pub u: f64,
+    /// The v' chromaticity coordinate, 9Y / (X + 15Y + 3Z). Visible colors range from 0 to about
+    /// 0.59.
+    pub v: f64,
+    /// The luminance Y, exactly as in XYZ. 1 is the luminance of the reference white.
+    pub luminance: f64,
+    /// The illuminant that the color is relative to.
+    pub illuminant: Illuminant,
+}
+
+impl Color for UVPrimeColor {
+    /// Converts from XYZ to u'v' and luminance, keeping the illuminant of the XYZ color. As black has
+    /// no chromaticity, it's given the chromaticity of the white point instead.
+    fn from_xyz(xyz: XYZColor) -> UVPrimeColor {
+        let denom = xyz.x + 15.0 * xyz.y + 3.0 * xyz.z;
+        let (u, v) = if denom == 0.0 {
+            let white = xyz.illuminant.white_point();
+            let white_denom = white[0] + 15.0 * white[1] + 3.0 * white[2];
+            (4.0 * white[0] / white_denom, 9.0 * white[1] / white_denom)
+        } else {
+            (4.0 * xyz.x / denom, 9.0 * xyz.y / denom)
+        };
+        UVPrimeColor {
+            u,
+            v,
+            luminance: xyz.y,
+            illuminant: xyz.illuminant,
+        }
+    }
+    /// Converts from u'v' and luminance to XYZ, chromatically adapting to the given illuminant.
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        let (x, z) = if self.v == 0.0 {
+            (0.0, 0.0)
+        } else {
+            (
+                self.luminance * 9.0 * self.u / (4.0 * self.v),
+                self.luminance * (12.0 - 3.0 * self.u - 20.0 * self.v) / (4.0 * self.v),
+            )
+        };
+        XYZColor {
+            x,
+            y: self.luminance,
+            z,
+            illuminant: self.illuminant,
+        }
+        .color_adapt(illuminant)
+    }
+}
+
+impl ToCSS for UVPrimeColor {}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use colors::XYYColor;
+    use consts::TEST_PRECISION;
+
+    #[test]
+    fn test_uv_prime_xyz_conversion() {
+        let xyz1 = XYZColor {
+            x: 0.4,
+            y: 0.2,
+            z: 0.5,
+            illuminant: Illuminant::D75,
+        };
+        let xyz2 = UVPrimeColor::from_xyz(xyz1).to_xyz(Illuminant::D75);
+        assert!(xyz1.approx_equal(&xyz2));
+        assert!(xyz1.distance(&xyz2) <= TEST_PRECISION);
+    }
+
+    #[test]
+    fn test_uv_prime_from_xy() {
+        // u' = 4x / (-2x + 12y + 3), v' = 9y / (-2x + 12y + 3)
+        let xyz = XYZColor {
+            x: 0.3,
+            y: 0.5,
+            z: 0.1,
+            illuminant: Illuminant::D50,
+        };
+        let xyy = XYYColor::from_xyz(xyz);
+        let uv = UVPrimeColor::from_xyz(xyz);
+        let denom = -2.0 * xyy.x + 12.0 * xyy.y + 3.0;
+        assert!((uv.u - 4.0 * xyy.x / denom).abs() <= 1e-12);
+        assert!((uv.v - 9.0 * xyy.y / denom).abs() <= 1e-12);
+        // D65 is at about (0.1978, 0.4683)
+        let d65 = UVPrimeColor::from_xyz(XYZColor::white_point(Illuminant::D65));
+        assert!((d65.u - 0.1978).abs() <= 1e-4);
+        assert!((d65.v - 0.4683).abs() <= 1e-4);
+    }
+}
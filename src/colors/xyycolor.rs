@@ -0,0 +1,139 @@
+//! This module implements the CIE xyY color space, which splits XYZ into a chromaticity, given by
+//! the coordinates x and y, and the luminance Y. The chromaticity is what the familiar horseshoe
+//! diagram of visible colors plots, and it's how white points and the primaries of RGB spaces are
+//! usually specified. Chromaticities are independent of luminance: a color and a darker version of
+//! it have the same x and y.
+
+use color::{Color, XYZColor};
+use csscolor::ToCSS;
+use illuminants::Illuminant;
+
+/// A color in CIE xyY space. Like [`XYZColor`](../../color/struct.XYZColor.html), this keeps the
+/// illuminant that it is relative to, and the chromaticity is the same as that of the XYZ color it
+/// came from, without any chromatic adaptation.
+/// # Example
+/// Get the chromaticity of D65, and use a chromaticity as a white point.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::color::XYZColor;
+/// # use scarlet::colors::XYYColor;
+/// let white = XYYColor::from_xyz(XYZColor::white_point(Illuminant::D65));
+/// assert!((white.x - 0.3127).abs() < 1e-4);
+/// assert!((white.y - 0.3290).abs() < 1e-4);
+/// // the DCI white point, used in cinema
+/// let dci = XYYColor{x: 0.314, y: 0.351, luminance: 1., illuminant: Illuminant::D65};
+/// let dci_white = dci.to_illuminant();
+/// assert!((dci_white.white_point()[0] - 0.314 / 0.351).abs() < 1e-12);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct XYYColor {
+    /// The x chromaticity coordinate, X / (X + Y + Z). Visible colors range from about 0 to 0.75.
+    pub x: f64,
+    /// The y chromaticity coordinate, Y / (X + Y + Z). Visible colors range from about 0 to 0.85.
+    pub y: f64,
+    /// The luminance Y, exactly as in XYZ. 1 is the luminance of the reference white.
+    pub luminance: f64,
+    /// The illuminant that the color is relative to.
+    pub illuminant: Illuminant,
+}
+
+impl XYYColor {
+    /// Makes a custom [`Illuminant`](../../illuminants/enum.Illuminant.html) whose white point has
+    /// this chromaticity, ignoring the luminance and the illuminant of this color. This is how most
+    /// standards give white points.
+    pub fn to_illuminant(&self) -> Illuminant {
+        Illuminant::Custom([self.x / self.y, 1.0, (1.0 - self.x - self.y) / self.y])
+    }
+}
+
+impl Color for XYYColor {
+    /// Converts from XYZ to xyY, keeping the illuminant of the XYZ color. As black has no
+    /// chromaticity, it's given the chromaticity of the white point instead, which is the usual
+    /// convention.
+    fn from_xyz(xyz: XYZColor) -> XYYColor {
+        let sum = xyz.x + xyz.y + xyz.z;
+        let (x, y) = if sum == 0.0 {
+            let white = xyz.illuminant.white_point();
+            let white_sum = white[0] + white[1] + white[2];
+            (white[0] / white_sum, white[1] / white_sum)
+        } else {
+            (xyz.x / sum, xyz.y / sum)
+        };
+        XYYColor {
+            x,
+            y,
+            luminance: xyz.y,
+            illuminant: xyz.illuminant,
+        }
+    }
+    /// Converts from xyY to XYZ, chromatically adapting to the given illuminant.
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        let (x, z) = if self.y == 0.0 {
+            (0.0, 0.0)
+        } else {
+            (
+                self.x * self.luminance / self.y,
+                (1.0 - self.x - self.y) * self.luminance / self.y,
+            )
+        };
+        XYZColor {
+            x,
+            y: self.luminance,
+            z,
+            illuminant: self.illuminant,
+        }
+        .color_adapt(illuminant)
+    }
+}
+
+impl ToCSS for XYYColor {}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use consts::TEST_PRECISION;
+
+    #[test]
+    fn test_xyy_xyz_conversion() {
+        let xyz1 = XYZColor {
+            x: 0.4,
+            y: 0.2,
+            z: 0.5,
+            illuminant: Illuminant::D75,
+        };
+        let xyy = XYYColor::from_xyz(xyz1);
+        assert!((xyy.x - 0.4 / 1.1).abs() <= 1e-12);
+        assert!((xyy.y - 0.2 / 1.1).abs() <= 1e-12);
+        assert_eq!(xyy.luminance, 0.2);
+        let xyz2 = xyy.to_xyz(Illuminant::D75);
+        assert!(xyz1.approx_equal(&xyz2));
+        assert!(xyz1.distance(&xyz2) <= TEST_PRECISION);
+    }
+
+    #[test]
+    fn test_xyy_black() {
+        let black = XYYColor::from_xyz(XYZColor {
+            x: 0.,
+            y: 0.,
+            z: 0.,
+            illuminant: Illuminant::D50,
+        });
+        let white = XYYColor::from_xyz(XYZColor::white_point(Illuminant::D50));
+        assert_eq!((black.x, black.y), (white.x, white.y));
+        assert_eq!(black.luminance, 0.);
+        let xyz = black.to_xyz(Illuminant::D50);
+        assert_eq!((xyz.x, xyz.y, xyz.z), (0., 0., 0.));
+    }
+
+    #[test]
+    fn test_to_illuminant() {
+        let d65 = XYYColor::from_xyz(XYZColor::white_point(Illuminant::D65)).to_illuminant();
+        let expected = Illuminant::D65.white_point();
+        let actual = d65.white_point();
+        for i in 0..3 {
+            assert!((expected[i] - actual[i]).abs() <= 1e-12);
+        }
+    }
+}
@@ -0,0 +1,519 @@
+//! This module implements compositing: placing one translucent color on top of another, the way a
+//! browser or a PDF renderer does. It follows the W3C [Compositing and Blending Level
+//! 1](https://www.w3.org/TR/compositing-1/) specification, which splits the process into two
+//! steps. First, the source and backdrop colors are *blended* with a [`BlendMode`], which decides
+//! what color to use where the two overlap: multiply, screen, and so on. Then the result is
+//! *composited* with a [`PorterDuff`] operator, which decides how much of the source and the
+//! backdrop each show through, based on their alpha values.
+//!
+//! Both steps work on the RGB components directly. Browsers and most image editors do this on
+//! gamma-encoded sRGB values, which is fast and what users have come to expect, but isn't
+//! physically accurate: mixing equal parts of black and white light gives a gray that's noticeably
+//! lighter than 50% sRGB gray. [`BlendSpace`] selects between the two, so you can match legacy
+//! output or get the physically correct result.
+//!
+//! [`BlendMode`]: enum.BlendMode.html
+//! [`PorterDuff`]: enum.PorterDuff.html
+//! [`BlendSpace`]: enum.BlendSpace.html
+
+use alpha::Alpha;
+use color::RGBColor;
+use transfer::{TransferFunction, SRGB};
+
+/// One of the twelve Porter-Duff compositing operators, which describe how much of the source and
+/// the backdrop show through based on the areas their alphas cover. The names follow the W3C
+/// specification, with "source" being the color being drawn and "destination" being the backdrop
+/// it's drawn on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PorterDuff {
+    /// Neither the source nor the backdrop is shown: the result is fully transparent.
+    Clear,
+    /// Only the source is shown.
+    Copy,
+    /// Only the backdrop is shown.
+    Destination,
+    /// The source is placed over the backdrop. This is the usual behavior.
+    SourceOver,
+    /// The backdrop is placed over the source.
+    DestinationOver,
+    /// The source is shown only where the backdrop is.
+    SourceIn,
+    /// The backdrop is shown only where the source is.
+    DestinationIn,
+    /// The source is shown only where the backdrop isn't.
+    SourceOut,
+    /// The backdrop is shown only where the source isn't.
+    DestinationOut,
+    /// The source is placed over the backdrop, but only where the backdrop is.
+    SourceAtop,
+    /// The backdrop is placed over the source, but only where the source is.
+    DestinationAtop,
+    /// Each is shown only where the other isn't.
+    Xor,
+}
+
+impl PorterDuff {
+    /// Returns the fractions of the source and backdrop, *F<sub>a</sub>* and *F<sub>b</sub>*, that
+    /// this operator keeps given the source and backdrop alphas.
+    fn fractions(self, alpha_s: f64, alpha_b: f64) -> (f64, f64) {
+        match self {
+            PorterDuff::Clear => (0.0, 0.0),
+            PorterDuff::Copy => (1.0, 0.0),
+            PorterDuff::Destination => (0.0, 1.0),
+            PorterDuff::SourceOver => (1.0, 1.0 - alpha_s),
+            PorterDuff::DestinationOver => (1.0 - alpha_b, 1.0),
+            PorterDuff::SourceIn => (alpha_b, 0.0),
+            PorterDuff::DestinationIn => (0.0, alpha_s),
+            PorterDuff::SourceOut => (1.0 - alpha_b, 0.0),
+            PorterDuff::DestinationOut => (0.0, 1.0 - alpha_s),
+            PorterDuff::SourceAtop => (alpha_b, 1.0 - alpha_s),
+            PorterDuff::DestinationAtop => (1.0 - alpha_b, alpha_s),
+            PorterDuff::Xor => (1.0 - alpha_b, 1.0 - alpha_s),
+        }
+    }
+}
+
+/// A blend mode, which determines the color used where the source and backdrop overlap. The
+/// separable modes, from `Multiply` to `Exclusion`, work on each RGB component independently; the
+/// last four work on the color as a whole, mixing the hue, saturation, and luminosity of the
+/// source and backdrop.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BlendMode {
+    /// The source color is used as is.
+    Normal,
+    /// Multiplies the source and backdrop, which always gives a darker color.
+    Multiply,
+    /// Multiplies the complements of the source and backdrop, which always gives a lighter color.
+    Screen,
+    /// Multiplies or screens depending on the backdrop, preserving its highlights and shadows.
+    Overlay,
+    /// Takes the darker of the source and backdrop in each component.
+    Darken,
+    /// Takes the lighter of the source and backdrop in each component.
+    Lighten,
+    /// Brightens the backdrop to reflect the source.
+    ColorDodge,
+    /// Darkens the backdrop to reflect the source.
+    ColorBurn,
+    /// Multiplies or screens depending on the source, like shining a harsh spotlight.
+    HardLight,
+    /// Darkens or lightens depending on the source, like shining a diffuse spotlight.
+    SoftLight,
+    /// Subtracts the darker of the two from the lighter.
+    Difference,
+    /// Like `Difference`, but with lower contrast.
+    Exclusion,
+    /// Uses the hue of the source with the saturation and luminosity of the backdrop.
+    Hue,
+    /// Uses the saturation of the source with the hue and luminosity of the backdrop.
+    Saturation,
+    /// Uses the hue and saturation of the source with the luminosity of the backdrop.
+    Color,
+    /// Uses the luminosity of the source with the hue and saturation of the backdrop.
+    Luminosity,
+}
+
+/// The RGB values used for blending and compositing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BlendSpace {
+    /// Uses the gamma-encoded sRGB values directly, as browsers do.
+    Gamma,
+    /// Decodes the sRGB values to linear light first, and encodes the result again afterwards.
+    /// This is physically accurate: it matches what actually happens when light is mixed.
+    Linear,
+}
+
+/// A full description of how to composite two colors: the blend mode, the Porter-Duff operator,
+/// and the space to do it in. The default is what browsers do when you don't ask for anything
+/// else: normal blending, source-over compositing, and gamma-encoded values.
+/// # Example
+/// Placing translucent red over an opaque blue backdrop gives purple, but a noticeably lighter one
+/// when the colors are mixed in linear light.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::alpha::Alpha;
+/// # use scarlet::compositing::{BlendMode, BlendSpace, Compositing, PorterDuff};
+/// let red = Alpha::new(RGBColor{r: 1., g: 0., b: 0.}, 0.5);
+/// let blue = Alpha::opaque(RGBColor{r: 0., g: 0., b: 1.});
+/// let browser = Compositing::default().composite(red, blue);
+/// assert_eq!(browser.to_string(), "#800080FF");
+/// let physical = Compositing {
+///     space: BlendSpace::Linear,
+///     ..Compositing::default()
+/// }
+/// .composite(red, blue);
+/// assert_eq!(physical.to_string(), "#BC00BCFF");
+/// // multiplying any color with white leaves it unchanged
+/// let white = Alpha::opaque(RGBColor{r: 1., g: 1., b: 1.});
+/// let multiply = Compositing {
+///     blend_mode: BlendMode::Multiply,
+///     operator: PorterDuff::SourceOver,
+///     space: BlendSpace::Gamma,
+/// };
+/// assert_eq!(multiply.composite(white, blue).to_string(), "#0000FFFF");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Compositing {
+    /// The blend mode used where the source and backdrop overlap.
+    pub blend_mode: BlendMode,
+    /// The Porter-Duff operator used to combine the blended color with the backdrop.
+    pub operator: PorterDuff,
+    /// Whether to work with gamma-encoded or linear-light values.
+    pub space: BlendSpace,
+}
+
+impl Default for Compositing {
+    fn default() -> Compositing {
+        Compositing {
+            blend_mode: BlendMode::Normal,
+            operator: PorterDuff::SourceOver,
+            space: BlendSpace::Gamma,
+        }
+    }
+}
+
+impl Compositing {
+    /// Composites the source color on top of the backdrop, returning the resulting color and its
+    /// alpha. If the result is fully transparent, its color is black.
+    pub fn composite(&self, source: Alpha<RGBColor>, backdrop: Alpha<RGBColor>) -> Alpha<RGBColor> {
+        let (alpha_s, alpha_b) = (source.alpha, backdrop.alpha);
+        let c_s = self.decode(source.color);
+        let c_b = self.decode(backdrop.color);
+
+        // where the backdrop is transparent, the source color is used without blending
+        let blended = blend(self.blend_mode, c_b, c_s);
+        let mixed = [
+            (1.0 - alpha_b) * c_s[0] + alpha_b * blended[0],
+            (1.0 - alpha_b) * c_s[1] + alpha_b * blended[1],
+            (1.0 - alpha_b) * c_s[2] + alpha_b * blended[2],
+        ];
+
+        let (f_a, f_b) = self.operator.fractions(alpha_s, alpha_b);
+        let alpha_o = alpha_s * f_a + alpha_b * f_b;
+        if alpha_o == 0.0 {
+            return Alpha::new(RGBColor::from((0, 0, 0)), 0.0);
+        }
+        // this is the premultiplied result: divide the alpha back out
+        let mut c_o = [0.0; 3];
+        for i in 0..3 {
+            c_o[i] = (alpha_s * f_a * mixed[i] + alpha_b * f_b * c_b[i]) / alpha_o;
+        }
+        Alpha::new(self.encode(c_o), alpha_o)
+    }
+
+    fn decode(&self, rgb: RGBColor) -> [f64; 3] {
+        match self.space {
+            BlendSpace::Gamma => [rgb.r, rgb.g, rgb.b],
+            BlendSpace::Linear => [SRGB.decode(rgb.r), SRGB.decode(rgb.g), SRGB.decode(rgb.b)],
+        }
+    }
+
+    fn encode(&self, rgb: [f64; 3]) -> RGBColor {
+        match self.space {
+            BlendSpace::Gamma => RGBColor {
+                r: rgb[0],
+                g: rgb[1],
+                b: rgb[2],
+            },
+            BlendSpace::Linear => RGBColor {
+                r: SRGB.encode(rgb[0]),
+                g: SRGB.encode(rgb[1]),
+                b: SRGB.encode(rgb[2]),
+            },
+        }
+    }
+}
+
+/// Computes B(C<sub>b</sub>, C<sub>s</sub>), the blended color, from the backdrop and source.
+fn blend(mode: BlendMode, c_b: [f64; 3], c_s: [f64; 3]) -> [f64; 3] {
+    let separable =
+        |f: fn(f64, f64) -> f64| [f(c_b[0], c_s[0]), f(c_b[1], c_s[1]), f(c_b[2], c_s[2])];
+    match mode {
+        BlendMode::Normal => c_s,
+        BlendMode::Multiply => separable(multiply),
+        BlendMode::Screen => separable(screen),
+        BlendMode::Overlay => separable(|b, s| hard_light(s, b)),
+        BlendMode::Darken => separable(f64::min),
+        BlendMode::Lighten => separable(f64::max),
+        BlendMode::ColorDodge => separable(color_dodge),
+        BlendMode::ColorBurn => separable(color_burn),
+        BlendMode::HardLight => separable(hard_light),
+        BlendMode::SoftLight => separable(soft_light),
+        BlendMode::Difference => separable(|b, s| (b - s).abs()),
+        BlendMode::Exclusion => separable(|b, s| b + s - 2.0 * b * s),
+        BlendMode::Hue => set_lum(set_sat(c_s, sat(c_b)), lum(c_b)),
+        BlendMode::Saturation => set_lum(set_sat(c_b, sat(c_s)), lum(c_b)),
+        BlendMode::Color => set_lum(c_s, lum(c_b)),
+        BlendMode::Luminosity => set_lum(c_b, lum(c_s)),
+    }
+}
+
+// the separable blend functions, each taking the backdrop and then the source component
+
+fn multiply(b: f64, s: f64) -> f64 {
+    b * s
+}
+
+fn screen(b: f64, s: f64) -> f64 {
+    b + s - b * s
+}
+
+fn hard_light(b: f64, s: f64) -> f64 {
+    if s <= 0.5 {
+        multiply(b, 2.0 * s)
+    } else {
+        screen(b, 2.0 * s - 1.0)
+    }
+}
+
+fn color_dodge(b: f64, s: f64) -> f64 {
+    if b == 0.0 {
+        0.0
+    } else if s >= 1.0 {
+        1.0
+    } else {
+        (b / (1.0 - s)).min(1.0)
+    }
+}
+
+fn color_burn(b: f64, s: f64) -> f64 {
+    if b >= 1.0 {
+        1.0
+    } else if s <= 0.0 {
+        0.0
+    } else {
+        1.0 - ((1.0 - b) / s).min(1.0)
+    }
+}
+
+fn soft_light(b: f64, s: f64) -> f64 {
+    if s <= 0.5 {
+        b - (1.0 - 2.0 * s) * b * (1.0 - b)
+    } else {
+        let d = if b <= 0.25 {
+            ((16.0 * b - 12.0) * b + 4.0) * b
+        } else {
+            b.sqrt()
+        };
+        b + (2.0 * s - 1.0) * (d - b)
+    }
+}
+
+// the helpers for the non-separable blend modes, matching the names in the specification
+
+fn lum(c: [f64; 3]) -> f64 {
+    0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
+
+fn clip_color(c: [f64; 3]) -> [f64; 3] {
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    let mut clipped = c;
+    for comp in clipped.iter_mut() {
+        if n < 0.0 {
+            *comp = l + (*comp - l) * l / (l - n);
+        }
+        if x > 1.0 {
+            *comp = l + (*comp - l) * (1.0 - l) / (x - l);
+        }
+    }
+    clipped
+}
+
+fn set_lum(c: [f64; 3], l: f64) -> [f64; 3] {
+    let d = l - lum(c);
+    clip_color([c[0] + d, c[1] + d, c[2] + d])
+}
+
+fn sat(c: [f64; 3]) -> f64 {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+fn set_sat(c: [f64; 3], s: f64) -> [f64; 3] {
+    // the smallest component goes to 0, the largest to s, and the middle one is scaled to match
+    let mut indices = [0, 1, 2];
+    indices.sort_by(|&i, &j| c[i].total_cmp(&c[j]));
+    let (min, mid, max) = (indices[0], indices[1], indices[2]);
+    let mut result = [0.0; 3];
+    if c[max] > c[min] {
+        result[mid] = (c[mid] - c[min]) * s / (c[max] - c[min]);
+        result[max] = s;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    fn rgba(r: f64, g: f64, b: f64, alpha: f64) -> Alpha<RGBColor> {
+        Alpha::new(RGBColor { r, g, b }, alpha)
+    }
+
+    fn assert_close(a: Alpha<RGBColor>, b: Alpha<RGBColor>) {
+        assert!((a.color.r - b.color.r).abs() <= 1e-10);
+        assert!((a.color.g - b.color.g).abs() <= 1e-10);
+        assert!((a.color.b - b.color.b).abs() <= 1e-10);
+        assert!((a.alpha - b.alpha).abs() <= 1e-10);
+    }
+
+    fn composite(
+        operator: PorterDuff,
+        source: Alpha<RGBColor>,
+        backdrop: Alpha<RGBColor>,
+    ) -> Alpha<RGBColor> {
+        Compositing {
+            operator,
+            ..Compositing::default()
+        }
+        .composite(source, backdrop)
+    }
+
+    #[test]
+    fn test_porter_duff_alphas() {
+        let source = rgba(1.0, 0.0, 0.0, 0.6);
+        let backdrop = rgba(0.0, 0.0, 1.0, 0.5);
+        let expected = [
+            (PorterDuff::Clear, 0.0),
+            (PorterDuff::Copy, 0.6),
+            (PorterDuff::Destination, 0.5),
+            (PorterDuff::SourceOver, 0.6 + 0.5 * 0.4),
+            (PorterDuff::DestinationOver, 0.6 * 0.5 + 0.5),
+            (PorterDuff::SourceIn, 0.6 * 0.5),
+            (PorterDuff::DestinationIn, 0.5 * 0.6),
+            (PorterDuff::SourceOut, 0.6 * 0.5),
+            (PorterDuff::DestinationOut, 0.5 * 0.4),
+            (PorterDuff::SourceAtop, 0.6 * 0.5 + 0.5 * 0.4),
+            (PorterDuff::DestinationAtop, 0.6 * 0.5 + 0.5 * 0.6),
+            (PorterDuff::Xor, 0.6 * 0.5 + 0.5 * 0.4),
+        ];
+        for &(operator, alpha) in expected.iter() {
+            assert!((composite(operator, source, backdrop).alpha - alpha).abs() <= 1e-10);
+        }
+    }
+    #[test]
+    fn test_porter_duff_colors() {
+        let source = rgba(1.0, 0.0, 0.0, 0.6);
+        let backdrop = rgba(0.0, 0.0, 1.0, 0.5);
+        assert_close(composite(PorterDuff::Copy, source, backdrop), source);
+        assert_close(
+            composite(PorterDuff::Destination, source, backdrop),
+            backdrop,
+        );
+        assert_close(
+            composite(PorterDuff::SourceIn, source, backdrop),
+            rgba(1.0, 0.0, 0.0, 0.3),
+        );
+        assert_close(
+            composite(PorterDuff::DestinationOut, source, backdrop),
+            rgba(0.0, 0.0, 1.0, 0.2),
+        );
+        // 0.6 of red and 0.2 of blue
+        assert_close(
+            composite(PorterDuff::SourceOver, source, backdrop),
+            rgba(0.75, 0.0, 0.25, 0.8),
+        );
+        // two opaque colors cancel out completely
+        let opaque = rgba(0.2, 0.4, 0.6, 1.0);
+        assert_eq!(composite(PorterDuff::Xor, opaque, opaque).alpha, 0.0);
+        assert_close(
+            composite(PorterDuff::Clear, source, backdrop),
+            rgba(0.0, 0.0, 0.0, 0.0),
+        );
+    }
+    #[test]
+    fn test_separable_blend_modes() {
+        let source = rgba(0.2, 0.5, 0.8, 1.0);
+        let backdrop = rgba(0.6, 0.4, 0.1, 1.0);
+        let expected = [
+            (BlendMode::Normal, [0.2, 0.5, 0.8]),
+            (BlendMode::Multiply, [0.12, 0.2, 0.08]),
+            (BlendMode::Screen, [0.68, 0.7, 0.82]),
+            (BlendMode::Overlay, [0.36, 0.4, 0.16]),
+            (BlendMode::Darken, [0.2, 0.4, 0.1]),
+            (BlendMode::Lighten, [0.6, 0.5, 0.8]),
+            (BlendMode::ColorDodge, [0.75, 0.8, 0.5]),
+            (BlendMode::ColorBurn, [0.0, 0.0, 0.0]),
+            (BlendMode::HardLight, [0.24, 0.4, 0.64]),
+            (
+                BlendMode::SoftLight,
+                [
+                    0.456,
+                    0.4,
+                    0.1 + 0.6 * (((16.0 * 0.1 - 12.0) * 0.1 + 4.0) * 0.1 - 0.1),
+                ],
+            ),
+            (BlendMode::Difference, [0.4, 0.1, 0.7]),
+            (BlendMode::Exclusion, [0.56, 0.5, 0.74]),
+        ];
+        for &(blend_mode, rgb) in expected.iter() {
+            let result = Compositing {
+                blend_mode,
+                ..Compositing::default()
+            }
+            .composite(source, backdrop);
+            assert_close(result, rgba(rgb[0], rgb[1], rgb[2], 1.0));
+        }
+    }
+    #[test]
+    fn test_non_separable_blend_modes() {
+        let source = rgba(0.9, 0.3, 0.2, 1.0);
+        let backdrop = rgba(0.3, 0.5, 0.6, 1.0);
+        let blended = |blend_mode| {
+            let result = Compositing {
+                blend_mode,
+                ..Compositing::default()
+            }
+            .composite(source, backdrop)
+            .color;
+            [result.r, result.g, result.b]
+        };
+        let c_s = [0.9, 0.3, 0.2];
+        let c_b = [0.3, 0.5, 0.6];
+        // these colors are far enough from the edges of the gamut that nothing gets clipped
+        let color = blended(BlendMode::Color);
+        assert!((lum(color) - lum(c_b)).abs() <= 1e-10);
+        assert!((sat(color) - sat(c_s)).abs() <= 1e-10);
+        let luminosity = blended(BlendMode::Luminosity);
+        assert!((lum(luminosity) - lum(c_s)).abs() <= 1e-10);
+        assert!((sat(luminosity) - sat(c_b)).abs() <= 1e-10);
+        let saturation = blended(BlendMode::Saturation);
+        assert!((lum(saturation) - lum(c_b)).abs() <= 1e-10);
+        assert!((sat(saturation) - sat(c_s)).abs() <= 1e-10);
+        let hue = blended(BlendMode::Hue);
+        assert!((lum(hue) - lum(c_b)).abs() <= 1e-10);
+        assert!((sat(hue) - sat(c_b)).abs() <= 1e-10);
+        // the hue is the source's: red is the largest component and green is the middle
+        assert!(hue[0] > hue[1] && hue[1] > hue[2]);
+        // NaN components shouldn't panic
+        for &blend_mode in &[BlendMode::Saturation, BlendMode::Color, BlendMode::Hue] {
+            Compositing {
+                blend_mode,
+                ..Compositing::default()
+            }
+            .composite(rgba(f64::NAN, 0.3, 0.2, 1.0), backdrop);
+            Compositing {
+                blend_mode,
+                ..Compositing::default()
+            }
+            .composite(source, rgba(0.3, f64::NAN, 0.6, 1.0));
+        }
+    }
+    #[test]
+    fn test_linear_blending() {
+        let black = rgba(0.0, 0.0, 0.0, 0.5);
+        let white = rgba(1.0, 1.0, 1.0, 1.0);
+        let linear = Compositing {
+            space: BlendSpace::Linear,
+            ..Compositing::default()
+        };
+        let gray = linear.composite(black, white);
+        assert!((SRGB.decode(gray.color.r) - 0.5).abs() <= 1e-10);
+        assert_eq!(gray.alpha, 1.0);
+        let gray = Compositing::default().composite(black, white);
+        assert!((gray.color.r - 0.5).abs() <= 1e-10);
+    }
+}
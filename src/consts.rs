@@ -29,6 +29,71 @@ lazy_static! {
     };
     pub(crate) static ref BRADFORD_TRANSFORM_LU: PartialPivLu<f64> =
         { PartialPivLu::decompose(BRADFORD_TRANSFORM.clone()).expect("Matrix is invertible.") };
+    // the CAT02 transform from CIECAM02, which CAT16 later replaced
+    pub(crate) static ref CAT02_TRANSFORM: Matrix<f64> = {
+        matrix![00.7328, 0.4296, -0.1624;
+                -0.7036, 1.6975, 00.0061;
+                00.0030, 0.0136, 00.9834]
+    };
+    pub(crate) static ref CAT02_TRANSFORM_LU: PartialPivLu<f64> =
+        PartialPivLu::decompose(CAT02_TRANSFORM.clone()).expect("Matrix is invertible.");
+    // the CAT16 transform used in CAM16 to go from XYZ to a sharpened cone response space: see Li et
+    // al., "Comprehensive color solutions: CAM16, CAT16, and CAM16-UCS" (2017)
+    pub(crate) static ref CAT16_TRANSFORM: Matrix<f64> = {
+        matrix![00.401288, 0.650173, -0.051461;
+                -0.250268, 1.204414, 00.045854;
+                -0.002079, 0.048952, 00.953127]
+    };
+    pub(crate) static ref CAT16_TRANSFORM_LU: PartialPivLu<f64> =
+        PartialPivLu::decompose(CAT16_TRANSFORM.clone()).expect("Matrix is invertible.");
+    // Display P3 and DCI-P3 share primaries, but have different white points: these are computed
+    // from the primaries and the white points Scarlet uses, so white maps exactly to white
+    pub(crate) static ref DCI_P3_TRANSFORM: Matrix<f64> = {
+        matrix![02.7253940305, -1.0180030062, -0.4401631952;
+                -0.7951680258, 01.6897320548, 00.0226471906;
+                00.0412418914, -0.0876390192, 01.1009293786]
+    };
+    pub(crate) static ref DCI_P3_TRANSFORM_LU: PartialPivLu<f64> =
+        PartialPivLu::decompose(DCI_P3_TRANSFORM.clone()).expect("Matrix is invertible.");
+    pub(crate) static ref DISPLAY_P3_TRANSFORM: Matrix<f64> = {
+        matrix![02.4931907944, -0.9312692753, -0.4026613451;
+                -0.8295029199, 01.7626937047, 00.0236250832;
+                00.0358532826, -0.0761882256, 00.9570834613]
+    };
+    pub(crate) static ref DISPLAY_P3_TRANSFORM_LU: PartialPivLu<f64> =
+        PartialPivLu::decompose(DISPLAY_P3_TRANSFORM.clone()).expect("Matrix is invertible.");
+    // the two matrices used by Oklab, taken from Björn Ottosson's reference implementation
+    // (https://bottosson.github.io/posts/oklab/): the first goes from D65 XYZ to approximate cone
+    // responses, and the second goes from the nonlinear cone responses to L, a, and b
+    pub(crate) static ref OKLAB_LMS_TRANSFORM: Matrix<f64> = {
+        matrix![0.8189330101, 0.3618667424, -0.1288597137;
+                0.0329845436, 0.9293118715, 00.0361456387;
+                0.0482003018, 0.2643662691, 00.6338517070]
+    };
+    pub(crate) static ref OKLAB_LMS_TRANSFORM_LU: PartialPivLu<f64> =
+        PartialPivLu::decompose(OKLAB_LMS_TRANSFORM.clone()).expect("Matrix is invertible.");
+    pub(crate) static ref OKLAB_LAB_TRANSFORM: Matrix<f64> = {
+        matrix![0.2104542553, 00.7936177850, -0.0040720468;
+                1.9779984951, -2.4285922050, 00.4505937099;
+                0.0259040371, 00.7827717662, -0.8086757660]
+    };
+    pub(crate) static ref OKLAB_LAB_TRANSFORM_LU: PartialPivLu<f64> =
+        PartialPivLu::decompose(OKLAB_LAB_TRANSFORM.clone()).expect("Matrix is invertible.");
+    // Rec. 709 shares its primaries with sRGB, but this is computed more precisely from them
+    pub(crate) static ref REC_709_TRANSFORM: Matrix<f64> = {
+        matrix![03.2404703169, -1.5371461760, -0.4985338949;
+                -0.9692656277, 01.8760100659, 00.0415560003;
+                00.0556428427, -0.2040237565, 01.0572140111]
+    };
+    pub(crate) static ref REC_709_TRANSFORM_LU: PartialPivLu<f64> =
+        PartialPivLu::decompose(REC_709_TRANSFORM.clone()).expect("Matrix is invertible.");
+    pub(crate) static ref REC_2020_TRANSFORM: Matrix<f64> = {
+        matrix![01.7165150185, -0.3556425710, -0.2533461837;
+                -0.6666928961, 01.6165019534, 00.0157687479;
+                00.0176434725, -0.0427793786, 00.9422961934]
+    };
+    pub(crate) static ref REC_2020_TRANSFORM_LU: PartialPivLu<f64> =
+        PartialPivLu::decompose(REC_2020_TRANSFORM.clone()).expect("Matrix is invertible.");
     pub(crate) static ref ROMM_RGB_TRANSFORM: Matrix<f64> = {
         matrix![0.7976749, 0.1351917, 0.0313534;
                 0.2880402, 0.7118741, 0.0000857;
@@ -43,6 +108,15 @@ lazy_static! {
     };
     pub(crate) static ref STANDARD_RGB_TRANSFORM_LU: PartialPivLu<f64> =
         { PartialPivLu::decompose(STANDARD_RGB_TRANSFORM.clone()).expect("Matrix is invertible.") };
+    // the Hunt-Pointer-Estévez cone fundamentals, normalized to D65, used in the original von Kries
+    // chromatic adaptation transform
+    pub(crate) static ref VON_KRIES_TRANSFORM: Matrix<f64> = {
+        matrix![00.40024, 0.70760, -0.08081;
+                -0.22630, 1.16532, 00.04570;
+                00.00000, 0.00000, 00.91822]
+    };
+    pub(crate) static ref VON_KRIES_TRANSFORM_LU: PartialPivLu<f64> =
+        PartialPivLu::decompose(VON_KRIES_TRANSFORM.clone()).expect("Matrix is invertible.");
 }
 
 // These next two constants define the X11 color names and hex codes.
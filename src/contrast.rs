@@ -0,0 +1,594 @@
+//! This module contains functions for measuring the contrast between two colors, such as text and
+//! its background, in order to check that it's readable. The WCAG 2 contrast ratio, which
+//! accessibility guidelines and laws around the world use, is based on the ratio between the
+//! [relative luminance](../color/trait.Color.html#method.relative_luminance) of the two colors.
+//!
+//! The WCAG 2 ratio is known to be inaccurate, however, especially for light text on dark
+//! backgrounds, where it overstates the contrast. The Accessible Perceptual Contrast Algorithm
+//! (APCA), the candidate for WCAG 3, instead measures the difference in perceived lightness between
+//! text and its background, accounting for which one is darker. Scarlet implements APCA-W3 version
+//! 0.0.98G-4g, in [`apca_contrast`], along with its font lookup table.
+//!
+//! When a color doesn't have enough contrast, [`ensure_contrast`] can fix it by changing its
+//! lightness as little as possible, keeping its hue and chroma.
+
+use bound::{Bound, GamutMap};
+use color::{Color, RGBColor};
+use colors::CIELABColor;
+use delta_e::DeltaE;
+
+// the constants of APCA-W3 0.0.98G-4g: first the coefficients for estimating screen luminance
+const APCA_TRC: f64 = 2.4;
+const APCA_R: f64 = 0.212_672_9;
+const APCA_G: f64 = 0.715_152_2;
+const APCA_B: f64 = 0.072_175;
+// the exponents for background and text, for dark text on light (normal polarity) and light text on
+// dark (reverse polarity)
+const APCA_NORM_BG: f64 = 0.56;
+const APCA_NORM_TEXT: f64 = 0.57;
+const APCA_REV_TEXT: f64 = 0.62;
+const APCA_REV_BG: f64 = 0.65;
+// the soft clamp for colors near black, which accounts for flare from the display
+const APCA_BLACK_THRESHOLD: f64 = 0.022;
+const APCA_BLACK_CLAMP: f64 = 1.414;
+// scaling, offsets, and clipping of the final result
+const APCA_SCALE: f64 = 1.14;
+const APCA_OFFSET: f64 = 0.027;
+const APCA_MIN_DELTA_Y: f64 = 0.0005;
+const APCA_LOW_CLIP: f64 = 0.1;
+
+/// How close the CIELAB lightness needs to be before the search in [`ensure_contrast`] stops.
+const LIGHTNESS_EPSILON: f64 = 0.0001;
+
+/// The APCA font lookup table, from APCA-W3 0.1.9. Each row starts with a lightness contrast (Lc),
+/// followed by the smallest font size, in CSS pixels, that is readable at that contrast for each
+/// font weight from 100 to 900. A size of 999 means no text at all should use that contrast, and
+/// 777 means that it's only suitable for non-text elements, like icons or dividers. After the first
+/// row, the rows go up in steps of 5 Lc: to use this, take the row for the largest Lc at most the
+/// absolute value of the contrast. [`apca_min_font_size`] does this lookup.
+pub const APCA_FONT_LOOKUP: [[f64; 10]; 25] = [
+    [
+        0.0, 999.0, 999.0, 999.0, 999.0, 999.0, 999.0, 999.0, 999.0, 999.0,
+    ],
+    [
+        10.0, 999.0, 999.0, 999.0, 999.0, 999.0, 999.0, 999.0, 999.0, 999.0,
+    ],
+    [
+        15.0, 777.0, 777.0, 777.0, 777.0, 777.0, 777.0, 777.0, 777.0, 777.0,
+    ],
+    [
+        20.0, 777.0, 777.0, 777.0, 777.0, 777.0, 777.0, 777.0, 777.0, 777.0,
+    ],
+    [
+        25.0, 777.0, 777.0, 777.0, 120.0, 120.0, 108.0, 96.0, 96.0, 96.0,
+    ],
+    [
+        30.0, 777.0, 777.0, 120.0, 108.0, 108.0, 96.0, 72.0, 72.0, 72.0,
+    ],
+    [
+        35.0, 777.0, 120.0, 108.0, 96.0, 72.0, 60.0, 48.0, 48.0, 48.0,
+    ],
+    [40.0, 120.0, 108.0, 96.0, 60.0, 48.0, 42.0, 32.0, 32.0, 32.0],
+    [45.0, 108.0, 96.0, 72.0, 42.0, 32.0, 28.0, 24.0, 24.0, 24.0],
+    [50.0, 96.0, 72.0, 60.0, 32.0, 28.0, 24.0, 21.0, 21.0, 21.0],
+    [55.0, 80.0, 60.0, 48.0, 28.0, 24.0, 21.0, 18.0, 18.0, 18.0],
+    [60.0, 72.0, 48.0, 42.0, 24.0, 21.0, 18.0, 16.0, 16.0, 18.0],
+    [65.0, 68.0, 46.0, 32.0, 21.75, 19.0, 17.0, 15.0, 16.0, 18.0],
+    [70.0, 64.0, 44.0, 28.0, 19.5, 18.0, 16.0, 14.5, 16.0, 18.0],
+    [75.0, 60.0, 42.0, 24.0, 18.0, 16.0, 15.0, 14.0, 16.0, 18.0],
+    [
+        80.0, 56.0, 38.25, 23.0, 17.25, 15.81, 14.81, 14.0, 16.0, 18.0,
+    ],
+    [
+        85.0, 52.0, 34.5, 22.0, 16.5, 15.625, 14.625, 14.0, 16.0, 18.0,
+    ],
+    [90.0, 48.0, 32.0, 21.0, 16.0, 15.5, 14.5, 14.0, 16.0, 18.0],
+    [95.0, 45.0, 28.0, 19.5, 15.5, 15.0, 14.0, 13.5, 16.0, 18.0],
+    [100.0, 42.0, 26.5, 18.5, 15.0, 14.5, 13.5, 13.0, 16.0, 18.0],
+    [105.0, 39.0, 25.0, 18.0, 14.5, 14.0, 13.0, 12.0, 16.0, 18.0],
+    [110.0, 36.0, 24.0, 18.0, 14.0, 13.0, 12.0, 11.0, 16.0, 18.0],
+    [
+        115.0, 34.5, 22.5, 17.25, 12.5, 11.875, 11.25, 10.625, 14.5, 16.5,
+    ],
+    [
+        120.0, 33.0, 21.0, 16.5, 11.0, 10.75, 10.5, 10.25, 13.0, 15.0,
+    ],
+    [125.0, 32.0, 20.0, 16.0, 10.0, 10.0, 10.0, 10.0, 12.0, 14.0],
+];
+
+/// Gets the WCAG 2 contrast ratio between two colors. This ranges from 1, for two colors with the
+/// same luminance, to 21, for black and white. It's symmetric, so the order of the colors doesn't
+/// matter.
+/// # Example
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::contrast::contrast_ratio;
+/// let black = RGBColor::from_hex_code("#000000").unwrap();
+/// let white = RGBColor::from_hex_code("#ffffff").unwrap();
+/// assert!((contrast_ratio(&black, &white) - 21.0).abs() <= 1e-10);
+/// assert!((contrast_ratio(&white, &black) - 21.0).abs() <= 1e-10);
+/// assert!((contrast_ratio(&white, &white) - 1.0).abs() <= 1e-10);
+/// ```
+pub fn contrast_ratio<T: Color, U: Color>(a: &T, b: &U) -> f64 {
+    let lum_a = a.relative_luminance();
+    let lum_b = b.relative_luminance();
+    (lum_a.max(lum_b) + 0.05) / (lum_a.min(lum_b) + 0.05)
+}
+
+/// The size of text, which affects how much contrast WCAG requires: large text is readable with less
+/// contrast.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TextSize {
+    /// Any text that isn't large.
+    Normal,
+    /// Text that is at least 18 point, or at least 14 point and bold. In CSS pixels, this is 24px, or
+    /// 18.66px and bold.
+    Large,
+}
+
+/// A WCAG 2 conformance level for contrast. Level A has no contrast requirements, so it isn't
+/// included.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WCAGLevel {
+    /// Level AA, from success criterion 1.4.3 (Contrast (Minimum)). This is the level most
+    /// guidelines and laws require.
+    AA,
+    /// Level AAA, from success criterion 1.4.6 (Contrast (Enhanced)).
+    AAA,
+}
+
+impl WCAGLevel {
+    /// Gets the minimum contrast ratio that text of the given size needs to meet this level.
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::contrast::{TextSize, WCAGLevel};
+    /// assert_eq!(WCAGLevel::AA.min_contrast(TextSize::Normal), 4.5);
+    /// assert_eq!(WCAGLevel::AA.min_contrast(TextSize::Large), 3.0);
+    /// assert_eq!(WCAGLevel::AAA.min_contrast(TextSize::Normal), 7.0);
+    /// assert_eq!(WCAGLevel::AAA.min_contrast(TextSize::Large), 4.5);
+    /// ```
+    pub fn min_contrast(&self, size: TextSize) -> f64 {
+        match (*self, size) {
+            (WCAGLevel::AA, TextSize::Normal) => 4.5,
+            (WCAGLevel::AA, TextSize::Large) => 3.0,
+            (WCAGLevel::AAA, TextSize::Normal) => 7.0,
+            (WCAGLevel::AAA, TextSize::Large) => 4.5,
+        }
+    }
+    /// Returns `true` if text of the given size and color on the given background has enough
+    /// contrast to meet this level. As WCAG specifies, the contrast ratio isn't rounded, so a ratio
+    /// of 4.499 doesn't meet a requirement of 4.5.
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::prelude::*;
+    /// # use scarlet::contrast::{TextSize, WCAGLevel};
+    /// let white = RGBColor::from_hex_code("#ffffff").unwrap();
+    /// // a famous near miss: this gray has a contrast ratio of 4.48 on white
+    /// let gray = RGBColor::from_hex_code("#777777").unwrap();
+    /// assert!(!WCAGLevel::AA.passes(&gray, &white, TextSize::Normal));
+    /// assert!(WCAGLevel::AA.passes(&gray, &white, TextSize::Large));
+    /// // but one step darker is fine
+    /// let darker_gray = RGBColor::from_hex_code("#767676").unwrap();
+    /// assert!(WCAGLevel::AA.passes(&darker_gray, &white, TextSize::Normal));
+    /// ```
+    pub fn passes<T: Color, U: Color>(&self, text: &T, background: &U, size: TextSize) -> bool {
+        contrast_ratio(text, background) >= self.min_contrast(size)
+    }
+}
+
+/// Estimates the screen luminance of a color as APCA does, including the soft clamp near black.
+fn apca_luminance<T: Color>(color: &T) -> f64 {
+    let rgb: RGBColor = color.convert();
+    let lin = |c: f64| c.clamp(0.0, 1.0).powf(APCA_TRC);
+    let y = APCA_R * lin(rgb.r) + APCA_G * lin(rgb.g) + APCA_B * lin(rgb.b);
+    if y > APCA_BLACK_THRESHOLD {
+        y
+    } else {
+        y + (APCA_BLACK_THRESHOLD - y).powf(APCA_BLACK_CLAMP)
+    }
+}
+
+/// Gets the APCA lightness contrast (Lc) of text of one color on a background of another. Unlike
+/// the WCAG 2 ratio, this isn't symmetric: it's positive for dark text on a light background, and
+/// negative for light text on a dark background. Its absolute value ranges from 0, for no
+/// contrast, to about 106 for black text on white and 108 for white text on black. Low contrasts,
+/// below 10 or so, are clipped to 0. Use [`apca_min_font_size`] to check whether the contrast is
+/// enough for some text.
+/// # Example
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::contrast::{apca_contrast, contrast_ratio};
+/// let gray = RGBColor::from_hex_code("#888888").unwrap();
+/// let white = RGBColor::from_hex_code("#ffffff").unwrap();
+/// let black = RGBColor::from_hex_code("#000000").unwrap();
+/// // WCAG 2 says that gray contrasts better with black than it does with white, but APCA rightly
+/// // disagrees
+/// assert!(contrast_ratio(&gray, &black) > contrast_ratio(&gray, &white));
+/// assert!((apca_contrast(&gray, &white) - 63.056).abs() <= 1e-3);
+/// assert!((apca_contrast(&gray, &black) + 38.623).abs() <= 1e-3);
+/// ```
+pub fn apca_contrast<T: Color, U: Color>(text: &T, background: &U) -> f64 {
+    let y_text = apca_luminance(text);
+    let y_bg = apca_luminance(background);
+    if (y_bg - y_text).abs() < APCA_MIN_DELTA_Y {
+        return 0.0;
+    }
+    let contrast = if y_bg > y_text {
+        // dark text on a light background
+        let sapc = (y_bg.powf(APCA_NORM_BG) - y_text.powf(APCA_NORM_TEXT)) * APCA_SCALE;
+        if sapc < APCA_LOW_CLIP {
+            0.0
+        } else {
+            sapc - APCA_OFFSET
+        }
+    } else {
+        // light text on a dark background
+        let sapc = (y_bg.powf(APCA_REV_BG) - y_text.powf(APCA_REV_TEXT)) * APCA_SCALE;
+        if sapc > -APCA_LOW_CLIP {
+            0.0
+        } else {
+            sapc + APCA_OFFSET
+        }
+    };
+    contrast * 100.0
+}
+
+/// Gets the smallest font size, in CSS pixels, that text of the given weight is readable at with
+/// the given APCA lightness contrast, using [`APCA_FONT_LOOKUP`]. The weight is rounded to the
+/// nearest hundred between 100 and 900, and the contrast is rounded down to the nearest row of the
+/// table, which is slightly conservative. Returns `None` if the contrast is too low for any text.
+/// # Example
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::contrast::{apca_contrast, apca_min_font_size};
+/// let text = RGBColor::from_hex_code("#888888").unwrap();
+/// let background = RGBColor::from_hex_code("#ffffff").unwrap();
+/// let lc = apca_contrast(&text, &background);
+/// // this gray is fine for large, bold headings, but not for body text
+/// assert_eq!(apca_min_font_size(lc, 400), Some(24.0));
+/// assert_eq!(apca_min_font_size(lc, 700), Some(16.0));
+/// assert_eq!(apca_min_font_size(10.0, 700), None);
+/// ```
+pub fn apca_min_font_size(lc: f64, weight: u16) -> Option<f64> {
+    let column = ((f64::from(weight) / 100.0).round() as usize).clamp(1, 9);
+    let size = APCA_FONT_LOOKUP
+        .iter()
+        .rev()
+        .find(|row| row[0] <= lc.abs())
+        .map_or(999.0, |row| row[column]);
+    if size >= 777.0 {
+        None
+    } else {
+        Some(size)
+    }
+}
+
+/// Returns `true` if text of the given color, size in CSS pixels, and weight is readable on the
+/// given background according to APCA, using [`apca_min_font_size`].
+/// # Example
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::contrast::apca_passes;
+/// let text = RGBColor::from_hex_code("#ffffff").unwrap();
+/// let background = RGBColor::from_hex_code("#3366cc").unwrap();
+/// assert!(apca_passes(&text, &background, 18.0, 400));
+/// assert!(!apca_passes(&text, &background, 12.0, 300));
+/// ```
+pub fn apca_passes<T: Color, U: Color>(text: &T, background: &U, size: f64, weight: u16) -> bool {
+    apca_min_font_size(apca_contrast(text, background), weight).map_or(false, |min| size >= min)
+}
+
+/// Which way [`ensure_contrast_with`] should change the lightness of a color.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LightnessPreference {
+    /// Whichever of lightening or darkening changes the color less, according to CIEDE2000.
+    Nearest,
+    /// Lighten the color if that can reach the target contrast, and darken it otherwise.
+    PreferLighter,
+    /// Darken the color if that can reach the target contrast, and lighten it otherwise.
+    PreferDarker,
+}
+
+impl Default for LightnessPreference {
+    /// Whichever direction changes the color less.
+    fn default() -> LightnessPreference {
+        LightnessPreference::Nearest
+    }
+}
+
+/// Adjusts the lightness of a foreground color until it has at least the given WCAG 2 contrast
+/// ratio with the background, changing it as little as possible. See
+/// [`ensure_contrast_with`] for details.
+/// # Example
+/// A brand orange fails AA as text on white, but a darker version of it with the same hue passes.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::contrast::{contrast_ratio, ensure_contrast};
+/// let orange = RGBColor::from_hex_code("#ff8800").unwrap();
+/// let white = RGBColor::from_hex_code("#ffffff").unwrap();
+/// assert!(contrast_ratio(&orange, &white) < 4.5);
+/// let fixed = ensure_contrast(&orange, &white, 4.5).unwrap();
+/// assert!(contrast_ratio(&fixed, &white) >= 4.5);
+/// assert!(fixed.lightness() < orange.lightness());
+/// assert!((fixed.hue() - orange.hue()).abs() < 1.);
+/// // no amount of lightening or darkening gets a gray this much contrast
+/// let gray = RGBColor::from_hex_code("#777777").unwrap();
+/// assert!(ensure_contrast(&orange, &gray, 10.0).is_none());
+/// ```
+pub fn ensure_contrast<T: Color, U: Color>(
+    foreground: &T,
+    background: &U,
+    target: f64,
+) -> Option<T> {
+    ensure_contrast_with(
+        foreground,
+        background,
+        target,
+        LightnessPreference::default(),
+    )
+}
+
+/// Adjusts the lightness of a foreground color until it has at least the given WCAG 2 contrast
+/// ratio with the background, changing it as little as possible. The color is moved along CIELAB
+/// lightness using [`set_lightness`](../color/trait.Color.html#method.set_lightness), which keeps
+/// its hue and chroma, and then brought into the sRGB gamut with
+/// [`GamutMap::PreserveLightness`](../bound/enum.GamutMap.html#variant.PreserveLightness), which
+/// reduces chroma only as much as the gamut requires. The given preference picks between lightening
+/// and darkening when both work. If the color already has enough contrast, it's returned
+/// unchanged, and if neither direction can reach the target, this returns `None`.
+/// # Example
+/// On a mid-tone background, a color can pass by being either lighter or darker.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::contrast::{contrast_ratio, ensure_contrast_with, LightnessPreference};
+/// let blue = RGBColor::from_hex_code("#3366cc").unwrap();
+/// let background = RGBColor::from_hex_code("#808080").unwrap();
+/// let lighter = ensure_contrast_with(&blue, &background, 3.0, LightnessPreference::PreferLighter)
+///     .unwrap();
+/// let darker = ensure_contrast_with(&blue, &background, 3.0, LightnessPreference::PreferDarker)
+///     .unwrap();
+/// assert!(lighter.lightness() > background.lightness());
+/// assert!(darker.lightness() < background.lightness());
+/// assert!(contrast_ratio(&lighter, &background) >= 3.0);
+/// assert!(contrast_ratio(&darker, &background) >= 3.0);
+/// ```
+pub fn ensure_contrast_with<T: Color, U: Color>(
+    foreground: &T,
+    background: &U,
+    target: f64,
+    preference: LightnessPreference,
+) -> Option<T> {
+    let original: T = foreground.convert();
+    if contrast_ratio(&original, background) >= target {
+        return Some(original);
+    }
+    let lab: CIELABColor = foreground.convert();
+    let lighter = search_lightness(lab, background, target, 100.0);
+    let darker = search_lightness(lab, background, target, 0.0);
+    match preference {
+        LightnessPreference::PreferLighter => lighter.or(darker),
+        LightnessPreference::PreferDarker => darker.or(lighter),
+        LightnessPreference::Nearest => match (lighter, darker) {
+            (Some(lighter), Some(darker)) => {
+                let metric = DeltaE::default();
+                if metric.distance(&lab, &lighter) <= metric.distance(&lab, &darker) {
+                    Some(lighter)
+                } else {
+                    Some(darker)
+                }
+            }
+            (lighter, darker) => lighter.or(darker),
+        },
+    }
+}
+
+/// Binary searches for the CIELAB lightness between that of the given color and `end` that is
+/// closest to the original while still reaching the target contrast, returning `None` if even
+/// `end` doesn't reach it. Contrast only goes down and then up again moving away from the original
+/// color, so the first lightness that reaches the target is the only boundary.
+fn search_lightness<T: Color, U: Color>(
+    lab: CIELABColor,
+    background: &U,
+    target: f64,
+    end: f64,
+) -> Option<T> {
+    let with_lightness = |lightness: f64| -> T {
+        let mut color = lab;
+        color.set_lightness(lightness);
+        RGBColor::gamut_map_with(color, GamutMap::PreserveLightness).convert()
+    };
+    let mut best = with_lightness(end);
+    if contrast_ratio(&best, background) < target {
+        return None;
+    }
+    let mut near = lab.l.clamp(0.0, 100.0);
+    let mut far = end;
+    while (far - near).abs() > LIGHTNESS_EPSILON {
+        let mid = (near + far) / 2.0;
+        let color = with_lightness(mid);
+        if contrast_ratio(&color, background) >= target {
+            far = mid;
+            best = color;
+        } else {
+            near = mid;
+        }
+    }
+    Some(best)
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use color::RGBColor;
+    use colors::{CIELABColor, CIELCHColor};
+
+    #[test]
+    fn test_relative_luminance() {
+        // values computed from the WCAG 2 definition
+        let examples = [
+            ("#000000", 0.0),
+            ("#ffffff", 1.0),
+            ("#ff0000", 0.2126),
+            ("#808080", 0.215_860_500_113_899),
+            ("#767676", 0.181_164_244_249_860),
+        ];
+        for &(hex, lum) in examples.iter() {
+            let color = RGBColor::from_hex_code(hex).unwrap();
+            assert!((color.relative_luminance() - lum).abs() <= 1e-10, "{}", hex);
+        }
+        // other color spaces work through sRGB
+        let lab = CIELABColor {
+            l: 100.0,
+            a: 0.0,
+            b: 0.0,
+        };
+        assert!((lab.relative_luminance() - 1.0).abs() <= 1e-3);
+        // out of gamut colors are clipped
+        let too_bright = RGBColor {
+            r: 1.5,
+            g: 1.5,
+            b: 1.5,
+        };
+        assert!((too_bright.relative_luminance() - 1.0).abs() <= 1e-10);
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        // the examples from WCAG 2 and its Understanding documents
+        let examples = [
+            ("#000000", "#ffffff", 21.0),
+            ("#777777", "#ffffff", 4.48),
+            ("#767676", "#ffffff", 4.54),
+            ("#ff0000", "#ffffff", 4.0),
+            ("#0000ff", "#ffffff", 8.59),
+            ("#ffffff", "#ffffff", 1.0),
+        ];
+        for &(fg, bg, ratio) in examples.iter() {
+            let fg = RGBColor::from_hex_code(fg).unwrap();
+            let bg = RGBColor::from_hex_code(bg).unwrap();
+            assert!((contrast_ratio(&fg, &bg) - ratio).abs() <= 0.005);
+        }
+    }
+
+    #[test]
+    fn test_apca_contrast() {
+        // the reference values from the APCA-W3 test suite
+        let examples = [
+            ("#888", "#fff", 63.056_469_930_209_424),
+            ("#fff", "#888", -68.541_464_366_449_62),
+            ("#000", "#aaa", 58.146_262_578_561_334),
+            ("#aaa", "#000", -56.241_133_368_397_42),
+            ("#123", "#def", 91.668_308_114_816_31),
+            ("#def", "#123", -93.067_700_494_842_75),
+            ("#123", "#444", 8.323_261_369_573_93),
+            ("#444", "#123", -7.526_878_460_278_154),
+        ];
+        for &(text, bg, lc) in examples.iter() {
+            let text = RGBColor::from_hex_code(text).unwrap();
+            let bg = RGBColor::from_hex_code(bg).unwrap();
+            assert!((apca_contrast(&text, &bg) - lc).abs() <= 1e-9);
+        }
+        // no contrast at all, and contrast too low to matter
+        let gray = RGBColor::from_hex_code("#777777").unwrap();
+        let gray2 = RGBColor::from_hex_code("#7a7a7a").unwrap();
+        assert_eq!(apca_contrast(&gray, &gray), 0.0);
+        assert_eq!(apca_contrast(&gray, &gray2), 0.0);
+        assert_eq!(apca_contrast(&gray2, &gray), 0.0);
+    }
+
+    #[test]
+    fn test_apca_font_lookup() {
+        // Lc 75 is the minimum for body text: 18px at normal weight or 14px bold
+        assert_eq!(apca_min_font_size(75.0, 400), Some(18.0));
+        assert_eq!(apca_min_font_size(-79.9, 700), Some(14.0));
+        // weights are rounded to the nearest hundred
+        assert_eq!(apca_min_font_size(75.0, 449), Some(18.0));
+        assert_eq!(apca_min_font_size(75.0, 450), Some(16.0));
+        assert_eq!(apca_min_font_size(75.0, 0), Some(60.0));
+        assert_eq!(apca_min_font_size(75.0, 1000), Some(18.0));
+        // above the table, the last row applies
+        assert_eq!(apca_min_font_size(-130.0, 400), Some(10.0));
+        // too little contrast for text
+        assert_eq!(apca_min_font_size(20.0, 900), None);
+        assert_eq!(apca_min_font_size(30.0, 100), None);
+        assert_eq!(apca_min_font_size(f64::NAN, 400), None);
+    }
+
+    #[test]
+    fn test_wcag_levels() {
+        let white = RGBColor::from_hex_code("#ffffff").unwrap();
+        // contrast ratios of about 3.03, 4.54, 5.74, and 7.0
+        let grays = ["#949494", "#767676", "#666666", "#595959"];
+        let expected = [
+            (WCAGLevel::AA, TextSize::Normal, [false, true, true, true]),
+            (WCAGLevel::AA, TextSize::Large, [true, true, true, true]),
+            (
+                WCAGLevel::AAA,
+                TextSize::Normal,
+                [false, false, false, true],
+            ),
+            (WCAGLevel::AAA, TextSize::Large, [false, true, true, true]),
+        ];
+        for &(level, size, passes) in expected.iter() {
+            for (gray, &pass) in grays.iter().zip(passes.iter()) {
+                let gray = RGBColor::from_hex_code(gray).unwrap();
+                assert_eq!(level.passes(&gray, &white, size), pass);
+                assert_eq!(level.passes(&white, &gray, size), pass);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ensure_contrast() {
+        let white = RGBColor::from_hex_code("#ffffff").unwrap();
+        let black = RGBColor::from_hex_code("#000000").unwrap();
+        for &hex in ["#ff8800", "#33cc33", "#00aaff", "#ff00ff", "#ffee00"].iter() {
+            let color = RGBColor::from_hex_code(hex).unwrap();
+            let fixed = ensure_contrast(&color, &white, 4.5).unwrap();
+            let ratio = contrast_ratio(&fixed, &white);
+            // the change should be the smallest one that works
+            assert!((4.5..=4.51).contains(&ratio), "{} {}", hex, ratio);
+            assert!(fixed.lightness() < color.lightness());
+            assert!(RGBColor::in_gamut(&fixed));
+            let color_lch: CIELCHColor = color.convert();
+            let fixed_lch: CIELCHColor = fixed.convert();
+            assert!((fixed_lch.h - color_lch.h).abs() <= 1.0, "{}", hex);
+            // on black, lightening is the only option
+            let fixed =
+                ensure_contrast_with(&color, &black, 19.0, LightnessPreference::PreferDarker)
+                    .unwrap();
+            assert!(contrast_ratio(&fixed, &black) >= 19.0);
+            assert!(fixed.lightness() > color.lightness());
+        }
+    }
+
+    #[test]
+    fn test_ensure_contrast_preferences() {
+        let blue = RGBColor::from_hex_code("#3366cc").unwrap();
+        let gray = RGBColor::from_hex_code("#808080").unwrap();
+        let lighter =
+            ensure_contrast_with(&blue, &gray, 2.5, LightnessPreference::PreferLighter).unwrap();
+        let darker =
+            ensure_contrast_with(&blue, &gray, 2.5, LightnessPreference::PreferDarker).unwrap();
+        let nearest = ensure_contrast(&blue, &gray, 2.5).unwrap();
+        assert!(lighter.lightness() > gray.lightness());
+        assert!(darker.lightness() < gray.lightness());
+        // the blue is darker than the gray to start with
+        assert_eq!(nearest.to_string(), darker.to_string());
+        // colors that already pass are left alone
+        let passing = ensure_contrast(&blue, &gray, 1.2).unwrap();
+        assert_eq!(passing.to_string(), blue.to_string());
+        // and impossible targets fail
+        assert!(ensure_contrast(&blue, &gray, 6.0).is_none());
+        assert!(ensure_contrast(&blue, &gray, 21.5).is_none());
+    }
+}
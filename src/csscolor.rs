@@ -1,12 +1,26 @@
-//! This file uses the CSS numeric parsing in `cssnumeric.rs` to parse CSS functional color notation
-//! according to the W3 specification. The only difference is that arithmetic is not supported to
-//! specify colors. Its end goal is the implementation of FromStr for RGB, HSL, and HSV colors,
-//! although the specific `impl` blocks are in their respective source files. You can see the full
-//! spec here: [https://www.w3.org/TR/css-color-3/](https://www.w3.org/TR/css-color-3/). One quick caveat:
-//! as is relatively standard, percents are only integral: "45.5%" will be treated as invalid.
-
-pub(crate) use cssnumeric::CSSParseError;
+//! This module parses colors written in CSS. The main entry point is
+//! [`parse_css_color`](fn.parse_css_color.html), which handles everything in [CSS Color Module
+//! Level 4](https://www.w3.org/TR/css-color-4/) except arithmetic with `calc()`, and gives back a
+//! [`CSSColor`](enum.CSSColor.html) holding whichever Scarlet color type fits the syntax used.
+//!
+//! The rest of this file uses the CSS numeric parsing in `cssnumeric.rs` to parse the legacy
+//! comma-separated functional notation from [CSS Color Module Level
+//! 3](https://www.w3.org/TR/css-color-3/), which is what the implementations of FromStr for RGB,
+//! HSL, and HSV colors use, although the specific `impl` blocks are in their respective source
+//! files. One quick caveat: as is relatively standard, percents in that notation are only
+//! integral: "45.5%" will be treated as invalid.
+
+use std::str::FromStr;
+
+use alpha::Alpha;
+use color::{Color, RGBColor, XYZColor};
+use colors::{
+    AdobeRGBColor, CIELABColor, CIELCHColor, DisplayP3Color, HSLColor, HSVColor, LinearRGBColor,
+    OklabColor, OklchColor, ROMMRGBColor,
+};
+pub use cssnumeric::CSSParseError;
 use cssnumeric::{parse_css_number, CSSNumeric};
+use illuminants::Illuminant;
 
 /// Given a string, attempts to parse as a CSS numeric. If successful, interprets the number given as
 /// a component of an RGB color, clamping accordingly. Returns the appropriate `u8`: e.g., "102%" maps
@@ -89,6 +103,48 @@ pub(crate) fn parse_rgb_str(num: &str) -> Result<(u8, u8, u8), CSSParseError> {
     Ok((nums[0], nums[1], nums[2]))
 }
 
+/// Given a string, attempts to parse as a CSS numeric and interpret it as an alpha value, clamping
+/// to the range 0-1. Both numbers, like "0.5", and percentages, like "50%", are accepted, as in CSS
+/// Color Level 4.
+pub(crate) fn parse_alpha_num(num: &str) -> Result<f64, CSSParseError> {
+    let alpha = match parse_css_number(num)? {
+        CSSNumeric::Integer(val) => val as f64,
+        CSSNumeric::Float(val) => val,
+        CSSNumeric::Percentage(val) => (val as f64) / 100.,
+    };
+    Ok(alpha.clamp(0., 1.))
+}
+
+/// Parses a string of the form "rgba(r, g, b, a)" or "rgb(r, g, b)", where r, g, and b are numbers
+/// as in `parse_rgb_str` and a is an alpha value, returning a tuple of u8s for the three components
+/// and the alpha as a float. Either function name can be used with either number of arguments, as
+/// CSS allows: if no alpha is given, it is 1. Gives a CSSParseError on invalid input.
+pub(crate) fn parse_rgba_str(num: &str) -> Result<(u8, u8, u8, f64), CSSParseError> {
+    let args: String = if num.starts_with("rgba(") {
+        num.chars().skip(5).collect()
+    } else if num.starts_with("rgb(") {
+        num.chars().skip(4).collect()
+    } else {
+        return Err(CSSParseError::InvalidColorSyntax);
+    };
+    // check for and remove parenthesis
+    if !args.ends_with(')') {
+        return Err(CSSParseError::InvalidColorSyntax);
+    }
+    let splits: Vec<&str> = args[..args.len() - 1].split(',').map(str::trim).collect();
+    match splits.len() {
+        3 => {
+            let (r, g, b) = parse_rgb_str(&format!("rgb({})", splits.join(",")))?;
+            Ok((r, g, b, 1.))
+        }
+        4 => {
+            let (r, g, b) = parse_rgb_str(&format!("rgb({})", splits[..3].join(",")))?;
+            Ok((r, g, b, parse_alpha_num(splits[3])?))
+        }
+        _ => Err(CSSParseError::InvalidColorSyntax),
+    }
+}
+
 /// Parses an HSL or HSV tuple, given after "hsl" or "hsv" in normal CSS, such as "(250, 50%, 50%)"
 /// into a tuple (f64, f64, f64) such that the first float lies within the range 0-360 and the other
 /// two lie within the range 0-1. Gives a CSSParseError if invalid.
@@ -165,10 +221,705 @@ pub(crate) fn parse_hsl_hsv_tuple(tup: &str) -> Result<(f64, f64, f64), CSSParse
     Ok((hue, sat, l_or_v))
 }
 
+/// Parses an HSL or HSV tuple with an optional alpha value, such as "(250, 50%, 50%, 0.5)", into a
+/// tuple (f64, f64, f64, f64) where the first three are as in `parse_hsl_hsv_tuple` and the last is
+/// the alpha value, between 0 and 1. If no alpha is given, it is 1. Gives a CSSParseError if invalid.
+pub(crate) fn parse_hsla_hsva_tuple(tup: &str) -> Result<(f64, f64, f64, f64), CSSParseError> {
+    if !tup.starts_with('(') || !tup.ends_with(')') {
+        return Err(CSSParseError::InvalidColorSyntax);
+    }
+    let splits: Vec<&str> = tup[1..tup.len() - 1].split(',').collect();
+    match splits.len() {
+        3 => {
+            let (h, s, l_or_v) = parse_hsl_hsv_tuple(tup)?;
+            Ok((h, s, l_or_v, 1.))
+        }
+        4 => {
+            let (h, s, l_or_v) = parse_hsl_hsv_tuple(&format!("({})", splits[..3].join(",")))?;
+            Ok((h, s, l_or_v, parse_alpha_num(splits[3].trim())?))
+        }
+        _ => Err(CSSParseError::InvalidColorSyntax),
+    }
+}
+
+/// A color parsed from any CSS color syntax, holding the Scarlet color type that best represents
+/// it along with its alpha. Each CSS function maps onto the color space it describes: `lab()` gives
+/// a [`CIELABColor`], `oklch()` an [`OklchColor`], and so on. The one exception is `hwb()`, which
+/// is a simple transformation of HSV, and so gives an [`HSVColor`].
+///
+/// This implements [`Color`], so it can be converted to any other color space directly without
+/// needing to match on it first. As with [`Alpha`], doing so discards the alpha.
+///
+/// [`CIELABColor`]: ../colors/cielabcolor/struct.CIELABColor.html
+/// [`OklchColor`]: ../colors/oklchcolor/struct.OklchColor.html
+/// [`HSVColor`]: ../colors/hsvcolor/struct.HSVColor.html
+/// [`Color`]: ../color/trait.Color.html
+/// [`Alpha`]: ../alpha/struct.Alpha.html
+#[derive(Debug, Copy, Clone)]
+pub enum CSSColor {
+    /// An sRGB color, from a hex code, a named color, `rgb()`, `rgba()`, or `color(srgb ...)`.
+    RGB(Alpha<RGBColor>),
+    /// An HSL color, from `hsl()` or `hsla()`.
+    HSL(Alpha<HSLColor>),
+    /// An HSV color, from `hwb()`.
+    HSV(Alpha<HSVColor>),
+    /// A CIELAB color, from `lab()`. CSS uses D50 CIELAB, just like Scarlet.
+    CIELAB(Alpha<CIELABColor>),
+    /// A CIELCH color, from `lch()`.
+    CIELCH(Alpha<CIELCHColor>),
+    /// An Oklab color, from `oklab()`.
+    Oklab(Alpha<OklabColor>),
+    /// An Oklch color, from `oklch()`.
+    Oklch(Alpha<OklchColor>),
+    /// A linear-light sRGB color, from `color(srgb-linear ...)`.
+    LinearRGB(Alpha<LinearRGBColor>),
+    /// A Display P3 color, from `color(display-p3 ...)`.
+    DisplayP3(Alpha<DisplayP3Color>),
+    /// An Adobe RGB color, from `color(a98-rgb ...)`.
+    AdobeRGB(Alpha<AdobeRGBColor>),
+    /// A ROMM RGB color, from `color(prophoto-rgb ...)`.
+    ROMMRGB(Alpha<ROMMRGBColor>),
+    /// An XYZ color, from `color(xyz ...)`, `color(xyz-d65 ...)`, or `color(xyz-d50 ...)`.
+    XYZ(Alpha<XYZColor>),
+}
+
+impl CSSColor {
+    /// Gets the alpha value of the color, regardless of its type.
+    pub fn alpha(&self) -> f64 {
+        match *self {
+            CSSColor::RGB(c) => c.alpha,
+            CSSColor::HSL(c) => c.alpha,
+            CSSColor::HSV(c) => c.alpha,
+            CSSColor::CIELAB(c) => c.alpha,
+            CSSColor::CIELCH(c) => c.alpha,
+            CSSColor::Oklab(c) => c.alpha,
+            CSSColor::Oklch(c) => c.alpha,
+            CSSColor::LinearRGB(c) => c.alpha,
+            CSSColor::DisplayP3(c) => c.alpha,
+            CSSColor::AdobeRGB(c) => c.alpha,
+            CSSColor::ROMMRGB(c) => c.alpha,
+            CSSColor::XYZ(c) => c.alpha,
+        }
+    }
+}
+
+impl Color for CSSColor {
+    /// Wraps the given color as a fully opaque XYZ color, the only lossless choice.
+    fn from_xyz(xyz: XYZColor) -> CSSColor {
+        CSSColor::XYZ(Alpha::opaque(xyz))
+    }
+    /// Converts whatever color is held to XYZ, ignoring the alpha.
+    fn to_xyz(&self, illuminant: Illuminant) -> XYZColor {
+        match *self {
+            CSSColor::RGB(c) => c.to_xyz(illuminant),
+            CSSColor::HSL(c) => c.to_xyz(illuminant),
+            CSSColor::HSV(c) => c.to_xyz(illuminant),
+            CSSColor::CIELAB(c) => c.to_xyz(illuminant),
+            CSSColor::CIELCH(c) => c.to_xyz(illuminant),
+            CSSColor::Oklab(c) => c.to_xyz(illuminant),
+            CSSColor::Oklch(c) => c.to_xyz(illuminant),
+            CSSColor::LinearRGB(c) => c.to_xyz(illuminant),
+            CSSColor::DisplayP3(c) => c.to_xyz(illuminant),
+            CSSColor::AdobeRGB(c) => c.to_xyz(illuminant),
+            CSSColor::ROMMRGB(c) => c.to_xyz(illuminant),
+            CSSColor::XYZ(c) => c.to_xyz(illuminant),
+        }
+    }
+}
+
+impl ToCSS for CSSColor {
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        match *self {
+            CSSColor::RGB(c) => c.write_css(alpha, format),
+            CSSColor::HSL(c) => c.write_css(alpha, format),
+            CSSColor::HSV(c) => c.write_css(alpha, format),
+            CSSColor::CIELAB(c) => c.write_css(alpha, format),
+            CSSColor::CIELCH(c) => c.write_css(alpha, format),
+            CSSColor::Oklab(c) => c.write_css(alpha, format),
+            CSSColor::Oklch(c) => c.write_css(alpha, format),
+            CSSColor::LinearRGB(c) => c.write_css(alpha, format),
+            CSSColor::DisplayP3(c) => c.write_css(alpha, format),
+            CSSColor::AdobeRGB(c) => c.write_css(alpha, format),
+            CSSColor::ROMMRGB(c) => c.write_css(alpha, format),
+            CSSColor::XYZ(c) => c.write_css(alpha, format),
+        }
+    }
+}
+
+impl FromStr for CSSColor {
+    type Err = CSSParseError;
+
+    fn from_str(s: &str) -> Result<CSSColor, CSSParseError> {
+        parse_css_color(s)
+    }
+}
+
+/// Parses any color written in CSS, following [CSS Color Module Level
+/// 4](https://www.w3.org/TR/css-color-4/). This covers hex codes with or without alpha, named
+/// colors and `transparent`, the legacy comma-separated `rgb()`, `rgba()`, `hsl()`, and `hsla()`,
+/// and the modern space-separated syntax with an optional `/ alpha` for those and for `hwb()`,
+/// `lab()`, `lch()`, `oklab()`, `oklch()`, and `color()`. Hues can be given in `deg`, `rad`,
+/// `grad`, or `turn`, and any component can be `none` in the modern syntax, which is treated as 0.
+/// The predefined spaces `color()` supports are `srgb`, `srgb-linear`, `display-p3`, `a98-rgb`,
+/// `prophoto-rgb`, `xyz`, `xyz-d50`, and `xyz-d65`. The one other difference from a browser is
+/// that arithmetic with `calc()` isn't supported.
+///
+/// On invalid input, this gives a [`CSSParseError::InvalidToken`] with the byte index of the first
+/// token that couldn't be interpreted.
+/// # Example
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::csscolor::{parse_css_color, CSSColor, CSSParseError};
+/// # fn try_main() -> Result<(), CSSParseError> {
+/// let teal = parse_css_color("oklch(60% 0.1 190deg / 50%)")?;
+/// match teal {
+///     CSSColor::Oklch(c) => {
+///         assert_eq!(c.color.l, 0.6);
+///         assert_eq!(c.alpha, 0.5);
+///     }
+///     _ => unreachable!(),
+/// }
+/// // any result can be converted directly
+/// let rgb: RGBColor = parse_css_color("hsl(0.5turn 100% 50%)")?.convert();
+/// assert_eq!(rgb.to_string(), "#00FFFF");
+/// // the position of the bad token is reported
+/// assert_eq!(parse_css_color("lab(50% 20 30deg)").unwrap_err(), CSSParseError::InvalidToken(11));
+/// # Ok(())
+/// # }
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`CSSParseError::InvalidToken`]: enum.CSSParseError.html#variant.InvalidToken
+pub fn parse_css_color(s: &str) -> Result<CSSColor, CSSParseError> {
+    let tokens = tokenize(s)?;
+    let (pos, first) = match tokens.first() {
+        Some(&(pos, ref first)) => (pos, first),
+        None => return Err(CSSParseError::InvalidToken(s.len())),
+    };
+    match *first {
+        Token::Hash(ref hex) => {
+            expect_end(&tokens[1..])?;
+            match Alpha::<RGBColor>::from_hex_code(hex) {
+                Ok(rgba) => Ok(CSSColor::RGB(rgba)),
+                Err(_e) => Err(CSSParseError::InvalidToken(pos)),
+            }
+        }
+        Token::Ident(ref name) => {
+            expect_end(&tokens[1..])?;
+            if name == "transparent" {
+                Ok(CSSColor::RGB(Alpha::new(RGBColor::from((0, 0, 0)), 0.0)))
+            } else {
+                match RGBColor::from_color_name(name) {
+                    Ok(rgb) => Ok(CSSColor::RGB(Alpha::opaque(rgb))),
+                    Err(_e) => Err(CSSParseError::InvalidToken(pos)),
+                }
+            }
+        }
+        Token::Function(ref name) => {
+            // the arguments run until the closing parenthesis, which has to be the last token
+            let close = match tokens.iter().position(|t| t.1 == Token::CloseParen) {
+                Some(close) => close,
+                None => return Err(CSSParseError::InvalidToken(s.len())),
+            };
+            expect_end(&tokens[close + 1..])?;
+            let args = &tokens[1..close];
+            let end = tokens[close].0;
+            match name.as_str() {
+                "rgb" | "rgba" => parse_rgb_args(args, end),
+                "hsl" | "hsla" => parse_hsl_args(args, end),
+                "hwb" => parse_hwb_args(args, end),
+                "lab" | "lch" | "oklab" | "oklch" => parse_lab_args(name, args, end),
+                "color" => parse_color_args(args, end),
+                _ => Err(CSSParseError::InvalidToken(pos)),
+            }
+        }
+        _ => Err(CSSParseError::InvalidToken(pos)),
+    }
+}
+
+/// Options for writing colors as CSS with [`ToCSS`](trait.ToCSS.html).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CSSFormat {
+    /// The maximum number of digits after the decimal point for each number. Trailing zeros are
+    /// removed, so 0.5 is always written `0.5`. Alpha always gets at least 3 digits.
+    pub precision: usize,
+    /// Whether to use the legacy comma-separated syntax, such as `rgba(255, 0, 0, 0.5)`, instead of
+    /// the modern syntax, `rgb(255 0 0 / 0.5)`, for colors where the legacy syntax exists: only
+    /// `rgb()` and `hsl()` have one. Older browsers only understand the legacy syntax.
+    pub legacy: bool,
+}
+
+impl Default for CSSFormat {
+    /// Uses the modern syntax with up to 3 digits after the decimal point.
+    fn default() -> CSSFormat {
+        CSSFormat {
+            precision: 3,
+            legacy: false,
+        }
+    }
+}
+
+/// Allows a color to be written as CSS text in the syntax of [CSS Color Module Level
+/// 4](https://www.w3.org/TR/css-color-4/), which [`parse_css_color`](fn.parse_css_color.html) can
+/// read back in. Each color uses the CSS function for its own color space when one exists: HSL
+/// colors give `hsl()`, CIELAB colors give `lab()`, and so on, with HSV colors giving `hwb()`.
+/// Color spaces that CSS has no syntax for fall back on `color(xyz-d50 ...)`, which is lossless.
+/// # Example
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::alpha::Alpha;
+/// # use scarlet::colors::{CIELABColor, HSLColor};
+/// # use scarlet::csscolor::CSSFormat;
+/// let hsl = HSLColor{h: 210., s: 0.5, l: 0.4};
+/// assert_eq!(hsl.to_css_string(), "hsl(210deg 50% 40%)");
+/// let lab = CIELABColor{l: 52.3, a: 40., b: -20.};
+/// assert_eq!(Alpha::new(lab, 0.25).to_css_string(), "lab(52.3% 40 -20 / 0.25)");
+/// let legacy = CSSFormat{precision: 0, legacy: true};
+/// let red = RGBColor{r: 0.9, g: 0.1, b: 0.1};
+/// assert_eq!(Alpha::new(red, 0.5).to_css_string_with(legacy), "rgba(230, 26, 26, 0.5)");
+/// ```
+pub trait ToCSS: Color {
+    /// Writes the color as CSS with the given alpha, which is omitted if the color is fully opaque.
+    /// This is mainly useful for implementing this trait: the other two methods are generally more
+    /// convenient. By default, this uses `color(xyz-d50 ...)`.
+    fn write_css(&self, alpha: f64, format: CSSFormat) -> String {
+        let xyz = self.to_xyz(Illuminant::D50);
+        let p = format.precision;
+        let args = [
+            "xyz-d50".to_string(),
+            css_number(xyz.x, p),
+            css_number(xyz.y, p),
+            css_number(xyz.z, p),
+        ];
+        css_function("color", &args, alpha, format)
+    }
+    /// Writes the color as CSS with the given options.
+    fn to_css_string_with(&self, format: CSSFormat) -> String {
+        self.write_css(1.0, format)
+    }
+    /// Writes the color as CSS, using the default options.
+    fn to_css_string(&self) -> String {
+        self.to_css_string_with(CSSFormat::default())
+    }
+}
+
+/// Formats a number for CSS with at most the given number of digits after the decimal point.
+pub(crate) fn css_number(num: f64, precision: usize) -> String {
+    let formatted = format!("{:.*}", precision, num);
+    let trimmed = if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &formatted
+    };
+    // negative numbers that round to zero shouldn't keep their sign
+    if trimmed == "-0" {
+        "0".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Writes a CSS color function with the given name and already-formatted arguments, adding the
+/// alpha if it isn't 1. Uses the legacy syntax if the format asks for it and the function has one.
+pub(crate) fn css_function(name: &str, args: &[String], alpha: f64, format: CSSFormat) -> String {
+    let alpha = if alpha < 1.0 {
+        // alpha keeps a few digits even for integer channels, so 0.5 doesn't round away
+        Some(css_number(alpha.max(0.0), format.precision.max(3)))
+    } else {
+        None
+    };
+    if format.legacy && (name == "rgb" || name == "hsl") {
+        match alpha {
+            Some(alpha) => format!("{}a({}, {})", name, args.join(", "), alpha),
+            None => format!("{}({})", name, args.join(", ")),
+        }
+    } else {
+        match alpha {
+            Some(alpha) => format!("{}({} / {})", name, args.join(" "), alpha),
+            None => format!("{}({})", name, args.join(" ")),
+        }
+    }
+}
+
+/// A single CSS token, as far as color syntax needs them. Keywords, function names, and units are
+/// lowercased, because CSS doesn't care about their case.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A keyword, such as `none` or `srgb`.
+    Ident(String),
+    /// A function name along with its opening parenthesis: `rgb(` is `Function("rgb")`.
+    Function(String),
+    /// A hex color, without the leading `#`.
+    Hash(String),
+    /// A plain number, such as `0.5`.
+    Number(f64),
+    /// A percentage, stored as the number before the `%`.
+    Percentage(f64),
+    /// A number with a unit, such as `90deg`.
+    Dimension(f64, String),
+    Comma,
+    Slash,
+    CloseParen,
+}
+
+/// Splits a string into tokens, each paired with the byte index where it starts.
+fn tokenize(s: &str) -> Result<Vec<(usize, Token)>, CSSParseError> {
+    let bytes = s.as_bytes();
+    // gives the end of the run of identifier characters starting at the given index
+    let ident_end = |start: usize| {
+        let mut end = start;
+        while end < bytes.len()
+            && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'-' || bytes[end] == b'_')
+        {
+            end += 1;
+        }
+        end
+    };
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let token = match bytes[i] {
+            c if c.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b',' => {
+                i += 1;
+                Token::Comma
+            }
+            b'/' => {
+                i += 1;
+                Token::Slash
+            }
+            b')' => {
+                i += 1;
+                Token::CloseParen
+            }
+            b'#' => {
+                i = ident_end(i + 1);
+                Token::Hash(s[start + 1..i].to_string())
+            }
+            _ if starts_number(&bytes[i..]) => {
+                i = number_end(bytes, i);
+                let value: f64 = match s[start..i].parse() {
+                    Ok(value) => value,
+                    Err(_e) => return Err(CSSParseError::InvalidToken(start)),
+                };
+                if i < bytes.len() && bytes[i] == b'%' {
+                    i += 1;
+                    Token::Percentage(value)
+                } else if i < bytes.len() && bytes[i].is_ascii_alphabetic() {
+                    let unit_start = i;
+                    i = ident_end(i);
+                    Token::Dimension(value, s[unit_start..i].to_ascii_lowercase())
+                } else {
+                    Token::Number(value)
+                }
+            }
+            c if c.is_ascii_alphabetic() || c == b'-' => {
+                i = ident_end(i);
+                let name = s[start..i].to_ascii_lowercase();
+                if i < bytes.len() && bytes[i] == b'(' {
+                    i += 1;
+                    Token::Function(name)
+                } else {
+                    Token::Ident(name)
+                }
+            }
+            _ => return Err(CSSParseError::InvalidToken(start)),
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+/// Determines whether the given bytes start with a CSS number: digits, or a period followed by
+/// digits, with an optional sign before either.
+fn starts_number(bytes: &[u8]) -> bool {
+    let unsigned = match bytes.first() {
+        Some(b'+') | Some(b'-') => &bytes[1..],
+        _ => bytes,
+    };
+    match unsigned.first() {
+        Some(c) if c.is_ascii_digit() => true,
+        Some(b'.') => unsigned.get(1).map_or(false, u8::is_ascii_digit),
+        _ => false,
+    }
+}
+
+/// Gives the index just past the end of the CSS number starting at the given index, including a
+/// fractional part and exponent if present.
+fn number_end(bytes: &[u8], start: usize) -> usize {
+    let digits_end = |mut i: usize| {
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        i
+    };
+    let mut i = start;
+    if bytes[i] == b'+' || bytes[i] == b'-' {
+        i += 1;
+    }
+    i = digits_end(i);
+    if i + 1 < bytes.len() && bytes[i] == b'.' && bytes[i + 1].is_ascii_digit() {
+        i = digits_end(i + 1);
+    }
+    // only treat an e as an exponent if digits follow: otherwise it's the start of a unit
+    if i + 1 < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+        let exponent = if bytes[i + 1] == b'+' || bytes[i + 1] == b'-' {
+            i + 2
+        } else {
+            i + 1
+        };
+        if exponent < bytes.len() && bytes[exponent].is_ascii_digit() {
+            i = digits_end(exponent);
+        }
+    }
+    i
+}
+
+/// Gives an error pointing at the first of the given tokens, if there are any.
+fn expect_end(tokens: &[(usize, Token)]) -> Result<(), CSSParseError> {
+    match tokens.first() {
+        Some(&(pos, _)) => Err(CSSParseError::InvalidToken(pos)),
+        None => Ok(()),
+    }
+}
+
+/// The arguments of a color function: the three components, and the alpha if one was given.
+type Arguments<'a> = ([&'a (usize, Token); 3], Option<&'a (usize, Token)>);
+
+/// Splits the arguments to a color function into the components and the alpha. This handles both
+/// the modern syntax, `(a b c / alpha)`, and, if `legacy` is true, the legacy comma-separated
+/// syntax, `(a, b, c, alpha)`, which doesn't allow `none`. `end` is the position of the closing
+/// parenthesis, used for errors where arguments are missing.
+fn split_arguments(
+    args: &[(usize, Token)],
+    legacy: bool,
+    end: usize,
+) -> Result<Arguments<'_>, CSSParseError> {
+    let (components, alpha) = match args.iter().find(|t| t.1 == Token::Comma) {
+        Some(&(pos, _)) if !legacy => return Err(CSSParseError::InvalidToken(pos)),
+        Some(_) => {
+            // the arguments have to alternate between values and commas
+            let mut values = vec![];
+            for (i, arg) in args.iter().enumerate() {
+                if (arg.1 == Token::Comma) != (i % 2 == 1) {
+                    return Err(CSSParseError::InvalidToken(arg.0));
+                }
+                match arg.1 {
+                    Token::Comma => {}
+                    Token::Ident(_) => return Err(CSSParseError::InvalidToken(arg.0)),
+                    _ => values.push(arg),
+                }
+            }
+            if args.len() % 2 == 0 {
+                // a trailing comma
+                return Err(CSSParseError::InvalidToken(end));
+            }
+            if values.len() == 4 {
+                let alpha = values.pop();
+                (values, alpha)
+            } else {
+                (values, None)
+            }
+        }
+        None => match args.iter().position(|t| t.1 == Token::Slash) {
+            Some(slash) => match args.len() - slash {
+                2 => (args[..slash].iter().collect(), Some(&args[slash + 1])),
+                1 => return Err(CSSParseError::InvalidToken(end)),
+                _ => return Err(CSSParseError::InvalidToken(args[slash + 2].0)),
+            },
+            None => (args.iter().collect(), None),
+        },
+    };
+    match components.len() {
+        3 => Ok(([components[0], components[1], components[2]], alpha)),
+        n if n < 3 => Err(CSSParseError::InvalidToken(end)),
+        _ => Err(CSSParseError::InvalidToken(components[3].0)),
+    }
+}
+
+/// Interprets a token as a number or percentage, where 100% corresponds to `hundred_percent`.
+/// `none` counts as 0.
+fn number(token: &(usize, Token), hundred_percent: f64) -> Result<f64, CSSParseError> {
+    match token.1 {
+        Token::Number(val) => Ok(val),
+        Token::Percentage(val) => Ok(val / 100.0 * hundred_percent),
+        Token::Ident(ref name) if name == "none" => Ok(0.0),
+        _ => Err(CSSParseError::InvalidToken(token.0)),
+    }
+}
+
+/// Interprets a token as a hue, in degrees between 0 and 360. Plain numbers are degrees, and
+/// `none` counts as 0.
+fn hue(token: &(usize, Token)) -> Result<f64, CSSParseError> {
+    let degrees = match token.1 {
+        Token::Number(val) => val,
+        Token::Dimension(val, ref unit) => match unit.as_str() {
+            "deg" => val,
+            "rad" => val.to_degrees(),
+            "grad" => val * 0.9,
+            "turn" => val * 360.0,
+            _ => return Err(CSSParseError::InvalidToken(token.0)),
+        },
+        Token::Ident(ref name) if name == "none" => 0.0,
+        _ => return Err(CSSParseError::InvalidToken(token.0)),
+    };
+    Ok(degrees.rem_euclid(360.0))
+}
+
+/// Interprets an optional alpha token, clamping it between 0 and 1. No alpha means fully opaque.
+fn alpha(token: Option<&(usize, Token)>) -> Result<f64, CSSParseError> {
+    match token {
+        Some(token) => Ok(number(token, 1.0)?.clamp(0.0, 1.0)),
+        None => Ok(1.0),
+    }
+}
+
+fn parse_rgb_args(args: &[(usize, Token)], end: usize) -> Result<CSSColor, CSSParseError> {
+    let (c, a) = split_arguments(args, true, end)?;
+    // numbers range from 0 to 255 and are clamped to that range
+    let channel = |token| -> Result<f64, CSSParseError> {
+        Ok((number(token, 255.0)? / 255.0).clamp(0.0, 1.0))
+    };
+    let rgb = RGBColor {
+        r: channel(c[0])?,
+        g: channel(c[1])?,
+        b: channel(c[2])?,
+    };
+    Ok(CSSColor::RGB(Alpha::new(rgb, alpha(a)?)))
+}
+
+fn parse_hsl_args(args: &[(usize, Token)], end: usize) -> Result<CSSColor, CSSParseError> {
+    let (c, a) = split_arguments(args, true, end)?;
+    let hsl = HSLColor {
+        h: hue(c[0])?,
+        s: (number(c[1], 100.0)? / 100.0).clamp(0.0, 1.0),
+        l: (number(c[2], 100.0)? / 100.0).clamp(0.0, 1.0),
+    };
+    Ok(CSSColor::HSL(Alpha::new(hsl, alpha(a)?)))
+}
+
+fn parse_hwb_args(args: &[(usize, Token)], end: usize) -> Result<CSSColor, CSSParseError> {
+    let (c, a) = split_arguments(args, false, end)?;
+    let h = hue(c[0])?;
+    let whiteness = (number(c[1], 100.0)? / 100.0).clamp(0.0, 1.0);
+    let blackness = (number(c[2], 100.0)? / 100.0).clamp(0.0, 1.0);
+    // HWB is HSV with the saturation and value swapped out for how much white and black to mix in
+    let hsv = if whiteness + blackness >= 1.0 {
+        // too much of both: the result is the gray they would make
+        HSVColor {
+            h,
+            s: 0.0,
+            v: whiteness / (whiteness + blackness),
+        }
+    } else {
+        HSVColor {
+            h,
+            s: 1.0 - whiteness / (1.0 - blackness),
+            v: 1.0 - blackness,
+        }
+    };
+    Ok(CSSColor::HSV(Alpha::new(hsv, alpha(a)?)))
+}
+
+fn parse_lab_args(
+    name: &str,
+    args: &[(usize, Token)],
+    end: usize,
+) -> Result<CSSColor, CSSParseError> {
+    let (c, a) = split_arguments(args, false, end)?;
+    let alpha = alpha(a)?;
+    // the percentage references for each component are from the specification
+    Ok(match name {
+        "lab" => CSSColor::CIELAB(Alpha::new(
+            CIELABColor {
+                l: number(c[0], 100.0)?.clamp(0.0, 100.0),
+                a: number(c[1], 125.0)?,
+                b: number(c[2], 125.0)?,
+            },
+            alpha,
+        )),
+        "lch" => CSSColor::CIELCH(Alpha::new(
+            CIELCHColor {
+                l: number(c[0], 100.0)?.clamp(0.0, 100.0),
+                c: number(c[1], 150.0)?.max(0.0),
+                h: hue(c[2])?,
+            },
+            alpha,
+        )),
+        "oklab" => CSSColor::Oklab(Alpha::new(
+            OklabColor {
+                l: number(c[0], 1.0)?.clamp(0.0, 1.0),
+                a: number(c[1], 0.4)?,
+                b: number(c[2], 0.4)?,
+            },
+            alpha,
+        )),
+        _ => CSSColor::Oklch(Alpha::new(
+            OklchColor {
+                l: number(c[0], 1.0)?.clamp(0.0, 1.0),
+                c: number(c[1], 0.4)?.max(0.0),
+                h: hue(c[2])?,
+            },
+            alpha,
+        )),
+    })
+}
+
+fn parse_color_args(args: &[(usize, Token)], end: usize) -> Result<CSSColor, CSSParseError> {
+    let (pos, space) = match args.first() {
+        Some(&(pos, Token::Ident(ref space))) => (pos, space.as_str()),
+        Some(&(pos, _)) => return Err(CSSParseError::InvalidToken(pos)),
+        None => return Err(CSSParseError::InvalidToken(end)),
+    };
+    let (c, a) = split_arguments(&args[1..], false, end)?;
+    let (x, y, z) = (number(c[0], 1.0)?, number(c[1], 1.0)?, number(c[2], 1.0)?);
+    let alpha = alpha(a)?;
+    let xyz = |illuminant| XYZColor {
+        x,
+        y,
+        z,
+        illuminant,
+    };
+    match space {
+        "srgb" => Ok(CSSColor::RGB(Alpha::new(
+            RGBColor { r: x, g: y, b: z },
+            alpha,
+        ))),
+        "srgb-linear" => Ok(CSSColor::LinearRGB(Alpha::new(
+            LinearRGBColor { r: x, g: y, b: z },
+            alpha,
+        ))),
+        "display-p3" => Ok(CSSColor::DisplayP3(Alpha::new(
+            DisplayP3Color { r: x, g: y, b: z },
+            alpha,
+        ))),
+        "a98-rgb" => Ok(CSSColor::AdobeRGB(Alpha::new(
+            AdobeRGBColor { r: x, g: y, b: z },
+            alpha,
+        ))),
+        "prophoto-rgb" => Ok(CSSColor::ROMMRGB(Alpha::new(
+            ROMMRGBColor { r: x, g: y, b: z },
+            alpha,
+        ))),
+        "xyz" | "xyz-d65" => Ok(CSSColor::XYZ(Alpha::new(xyz(Illuminant::D65), alpha))),
+        "xyz-d50" => Ok(CSSColor::XYZ(Alpha::new(xyz(Illuminant::D50), alpha))),
+        _ => Err(CSSParseError::InvalidToken(pos)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
     use super::*;
+    use colors::{CIELCHuvColor, CIELUVColor};
 
     #[test]
     fn test_rgb_num_parsing() {
@@ -242,4 +993,296 @@ mod tests {
             Err(CSSParseError::InvalidColorSyntax)
         );
     }
+
+    #[test]
+    fn test_rgba_str_parsing() {
+        assert_eq!(
+            parse_rgba_str("rgba(125, 20%, 0.5, 0.25)").unwrap(),
+            (125, 51, 127, 0.25)
+        );
+        assert_eq!(
+            parse_rgba_str("rgba(125, 20%, 0.5, 40%)").unwrap(),
+            (125, 51, 127, 0.4)
+        );
+        // either name can be used with or without alpha
+        assert_eq!(parse_rgba_str("rgba(1, 2, 3)").unwrap(), (1, 2, 3, 1.));
+        assert_eq!(parse_rgba_str("rgb(1, 2, 3, 2)").unwrap(), (1, 2, 3, 1.));
+        assert_eq!(
+            Err(CSSParseError::InvalidColorSyntax),
+            parse_rgba_str("rgba(1, 2)")
+        );
+        assert_eq!(
+            Err(CSSParseError::InvalidColorSyntax),
+            parse_rgba_str("rgba(1, 2, 3, 4, 5)")
+        );
+        assert_eq!(
+            Err(CSSParseError::InvalidNumericCharacters),
+            parse_rgba_str("rgba(1, 2, 3, a)")
+        );
+    }
+
+    #[test]
+    fn test_hsla_str_parsing() {
+        let hsla = parse_hsla_hsva_tuple("(123, 40%, 40%, 0.5)").unwrap();
+        assert_eq!(hsla.0.round() as u8, 123u8);
+        assert_eq!((hsla.1 * 100.).round() as u8, 40u8);
+        assert_eq!((hsla.2 * 100.).round() as u8, 40u8);
+        assert_eq!(hsla.3, 0.5);
+        let hsla = parse_hsla_hsva_tuple("(123, 40%, 40%)").unwrap();
+        assert_eq!(hsla.3, 1.);
+        let hsla = parse_hsla_hsva_tuple("(123, 40%, 40%, -20%)").unwrap();
+        assert_eq!(hsla.3, 0.);
+        assert_eq!(
+            parse_hsla_hsva_tuple("(123, 40%)"),
+            Err(CSSParseError::InvalidColorSyntax)
+        );
+    }
+
+    fn rgb_string(s: &str) -> String {
+        parse_css_color(s)
+            .unwrap()
+            .convert::<RGBColor>()
+            .to_string()
+    }
+
+    #[test]
+    fn test_css_color_legacy_syntax() {
+        assert_eq!(rgb_string("#123456"), "#123456");
+        assert_eq!(rgb_string("#12345678"), "#123456");
+        assert_eq!(parse_css_color("#12345678").unwrap().alpha(), 120. / 255.);
+        assert_eq!(rgb_string("RebeccaPurple"), "#663399");
+        assert_eq!(parse_css_color("transparent").unwrap().alpha(), 0.);
+        assert_eq!(rgb_string("rgb(18, 52, 86)"), "#123456");
+        assert_eq!(rgb_string("rgba(18, 52, 86, 0.5)"), "#123456");
+        assert_eq!(
+            parse_css_color("rgba(18, 52, 86, 0.5)").unwrap().alpha(),
+            0.5
+        );
+        assert_eq!(rgb_string("rgb(100%, 0%, 300)"), "#FF00FF");
+        assert_eq!(rgb_string("hsl(120, 100%, 50%)"), "#00FF00");
+        assert_eq!(
+            parse_css_color("hsla(120, 100%, 50%, 20%)")
+                .unwrap()
+                .alpha(),
+            0.2
+        );
+    }
+    #[test]
+    fn test_css_color_modern_syntax() {
+        assert_eq!(rgb_string("rgb(18 52 86)"), "#123456");
+        assert_eq!(rgb_string("RGB(18 52 86 / 50%)"), "#123456");
+        assert_eq!(parse_css_color("rgb(18 52 86 / 50%)").unwrap().alpha(), 0.5);
+        assert_eq!(rgb_string("rgb(none 255 none)"), "#00FF00");
+        assert_eq!(rgb_string("hsl(240deg 100% 50%)"), "#0000FF");
+        assert_eq!(rgb_string("hsl(-120 100 50)"), "#0000FF");
+        // every angle unit should give the same result
+        for angle in ["180", "180deg", "3.14159265358979rad", "200grad", "0.5turn"].iter() {
+            assert_eq!(rgb_string(&format!("hsl({} 100% 50%)", angle)), "#00FFFF");
+        }
+        assert_eq!(rgb_string("hwb(0 0% 0%)"), "#FF0000");
+        assert_eq!(rgb_string("hwb(120 20% 20%)"), "#33CC33");
+        assert_eq!(rgb_string("hwb(120 75% 25%)"), "#BFBFBF");
+        assert_eq!(rgb_string("color(srgb 1 0.6 0)"), "#FF9900");
+        assert_eq!(rgb_string("color(srgb 100% 60% 0% / 0.5)"), "#FF9900");
+    }
+    #[test]
+    fn test_css_color_types() {
+        match parse_css_color("lab(50% 62.5 -40)").unwrap() {
+            CSSColor::CIELAB(c) => {
+                assert_eq!(c.color.l, 50.);
+                assert_eq!(c.color.a, 62.5);
+                assert_eq!(c.color.b, -40.);
+            }
+            c => panic!("wrong type: {:?}", c),
+        }
+        match parse_css_color("lch(50 40% 0.25turn / 0.1)").unwrap() {
+            CSSColor::CIELCH(c) => {
+                assert_eq!(c.color.c, 60.);
+                assert_eq!(c.color.h, 90.);
+                assert_eq!(c.alpha, 0.1);
+            }
+            c => panic!("wrong type: {:?}", c),
+        }
+        match parse_css_color("oklab(0.5 -25% 1e-1)").unwrap() {
+            CSSColor::Oklab(c) => {
+                assert_eq!(c.color.l, 0.5);
+                assert_eq!(c.color.a, -0.1);
+                assert_eq!(c.color.b, 0.1);
+            }
+            c => panic!("wrong type: {:?}", c),
+        }
+        match parse_css_color("oklch(70% 0.1 none)").unwrap() {
+            CSSColor::Oklch(c) => {
+                assert!((c.color.l - 0.7).abs() <= 1e-10);
+                assert_eq!(c.color.h, 0.);
+            }
+            c => panic!("wrong type: {:?}", c),
+        }
+        match parse_css_color("color(xyz-d50 0.2 0.3 0.4)").unwrap() {
+            CSSColor::XYZ(c) => assert_eq!(c.color.illuminant, Illuminant::D50),
+            c => panic!("wrong type: {:?}", c),
+        }
+        match parse_css_color("color(xyz 0.2 0.3 0.4)").unwrap() {
+            CSSColor::XYZ(c) => assert_eq!(c.color.illuminant, Illuminant::D65),
+            c => panic!("wrong type: {:?}", c),
+        }
+        match parse_css_color("color(srgb-linear 0.2 0.3 0.4)").unwrap() {
+            CSSColor::LinearRGB(c) => assert_eq!(c.color.g, 0.3),
+            c => panic!("wrong type: {:?}", c),
+        }
+        match parse_css_color("color(display-p3 0.2 0.3 0.4)").unwrap() {
+            CSSColor::DisplayP3(c) => assert_eq!(c.color.r, 0.2),
+            c => panic!("wrong type: {:?}", c),
+        }
+        match parse_css_color("color(a98-rgb 0.2 0.3 0.4)").unwrap() {
+            CSSColor::AdobeRGB(c) => assert_eq!(c.color.g, 0.3),
+            c => panic!("wrong type: {:?}", c),
+        }
+        match parse_css_color("color(prophoto-rgb 0.2 0.3 0.4)").unwrap() {
+            CSSColor::ROMMRGB(c) => assert_eq!(c.color.b, 0.4),
+            c => panic!("wrong type: {:?}", c),
+        }
+    }
+    #[test]
+    fn test_css_color_errors() {
+        let err = |s: &str| parse_css_color(s).unwrap_err();
+        assert_eq!(err(""), CSSParseError::InvalidToken(0));
+        assert_eq!(err("notacolor"), CSSParseError::InvalidToken(0));
+        assert_eq!(err("#12345"), CSSParseError::InvalidToken(0));
+        assert_eq!(err("red blue"), CSSParseError::InvalidToken(4));
+        assert_eq!(err("foo(1 2 3)"), CSSParseError::InvalidToken(0));
+        // missing closing parenthesis
+        assert_eq!(err("rgb(1 2 3"), CSSParseError::InvalidToken(9));
+        assert_eq!(err("rgb(1 2 3) x"), CSSParseError::InvalidToken(11));
+        // wrong number of arguments
+        assert_eq!(err("rgb(1 2)"), CSSParseError::InvalidToken(7));
+        assert_eq!(err("rgb(1 2 3 4)"), CSSParseError::InvalidToken(10));
+        assert_eq!(err("rgb(1 2 3 /)"), CSSParseError::InvalidToken(11));
+        assert_eq!(err("rgb(1 2 3 / 4 5)"), CSSParseError::InvalidToken(14));
+        // mixing legacy and modern syntax
+        assert_eq!(err("rgb(1, 2 3)"), CSSParseError::InvalidToken(9));
+        assert_eq!(err("rgb(1, 2, 3,)"), CSSParseError::InvalidToken(12));
+        assert_eq!(err("rgb(1, none, 3)"), CSSParseError::InvalidToken(7));
+        assert_eq!(err("lab(1, 2, 3)"), CSSParseError::InvalidToken(5));
+        // wrong kinds of values
+        assert_eq!(err("hsl(120% 50% 50%)"), CSSParseError::InvalidToken(4));
+        assert_eq!(err("hsl(120px 50% 50%)"), CSSParseError::InvalidToken(4));
+        assert_eq!(err("rgb(1deg 2 3)"), CSSParseError::InvalidToken(4));
+        assert_eq!(
+            err("color(display-p4 1 2 3)"),
+            CSSParseError::InvalidToken(6)
+        );
+        assert_eq!(err("color(1 2 3)"), CSSParseError::InvalidToken(6));
+        assert_eq!(err("rgb(1 2 3 $)"), CSSParseError::InvalidToken(10));
+    }
+
+    #[test]
+    fn test_css_number_formatting() {
+        assert_eq!(css_number(0.5, 3), "0.5");
+        assert_eq!(css_number(40.0, 3), "40");
+        assert_eq!(css_number(2.0 / 3.0, 3), "0.667");
+        assert_eq!(css_number(2.0 / 3.0, 0), "1");
+        assert_eq!(css_number(-0.0001, 3), "0");
+        assert_eq!(css_number(1234.5, 0), "1234");
+    }
+    #[test]
+    fn test_css_serialization() {
+        let legacy = CSSFormat {
+            precision: 3,
+            legacy: true,
+        };
+        let rgb = RGBColor::from((18, 52, 86));
+        assert_eq!(rgb.to_css_string(), "rgb(18 52 86)");
+        assert_eq!(rgb.to_css_string_with(legacy), "rgb(18, 52, 86)");
+        assert_eq!(Alpha::new(rgb, 0.5).to_css_string(), "rgb(18 52 86 / 0.5)");
+        let hsl = HSLColor {
+            h: 210.,
+            s: 0.5,
+            l: 0.4,
+        };
+        assert_eq!(hsl.to_css_string(), "hsl(210deg 50% 40%)");
+        assert_eq!(
+            Alpha::new(hsl, 0.5).to_css_string_with(legacy),
+            "hsla(210, 50%, 40%, 0.5)"
+        );
+        // legacy syntax only exists for rgb() and hsl()
+        let hsv = HSVColor {
+            h: 120.,
+            s: 0.5,
+            v: 0.8,
+        };
+        assert_eq!(hsv.to_css_string_with(legacy), "hwb(120deg 40% 20%)");
+        let lch = CIELCHColor {
+            l: 52.3,
+            c: 40.,
+            h: 370.,
+        };
+        assert_eq!(lch.to_css_string(), "lch(52.3% 40 370deg)");
+        let oklch = OklchColor {
+            l: 0.62796,
+            c: 0.25768,
+            h: 29.23,
+        };
+        assert_eq!(oklch.to_css_string(), "oklch(62.796% 0.258 29.23deg)");
+        let xyz = XYZColor {
+            x: 0.2,
+            y: 0.3,
+            z: 0.4,
+            illuminant: Illuminant::D65,
+        };
+        assert_eq!(xyz.to_css_string(), "color(xyz-d65 0.2 0.3 0.4)");
+    }
+    #[test]
+    fn test_css_serialization_round_trip() {
+        let xyz = XYZColor {
+            x: 0.3,
+            y: 0.25,
+            z: 0.2,
+            illuminant: Illuminant::D50,
+        };
+        let format = CSSFormat {
+            precision: 10,
+            legacy: false,
+        };
+        let strings = vec![
+            RGBColor::from_xyz(xyz).to_css_string_with(format),
+            HSLColor::from_xyz(xyz).to_css_string_with(format),
+            HSVColor::from_xyz(xyz).to_css_string_with(format),
+            CIELABColor::from_xyz(xyz).to_css_string_with(format),
+            CIELCHColor::from_xyz(xyz).to_css_string_with(format),
+            OklabColor::from_xyz(xyz).to_css_string_with(format),
+            OklchColor::from_xyz(xyz).to_css_string_with(format),
+            LinearRGBColor::from_xyz(xyz).to_css_string_with(format),
+            DisplayP3Color::from_xyz(xyz).to_css_string_with(format),
+            AdobeRGBColor::from_xyz(xyz).to_css_string_with(format),
+            ROMMRGBColor::from_xyz(xyz).to_css_string_with(format),
+            CIELUVColor::from_xyz(xyz).to_css_string_with(format),
+            xyz.to_css_string_with(format),
+            Alpha::new(CIELCHuvColor::from_xyz(xyz), 0.5).to_css_string_with(format),
+        ];
+        for string in strings {
+            let parsed = parse_css_color(&string).unwrap();
+            assert!(parsed.distance(&xyz) <= 1e-6);
+            // hues may come back normalized, but writing it out again is stable
+            let again = parsed.to_css_string_with(format);
+            assert_eq!(
+                parse_css_color(&again).unwrap().to_css_string_with(format),
+                again
+            );
+        }
+        let legacy = CSSFormat {
+            precision: 10,
+            legacy: true,
+        };
+        for string in [
+            Alpha::new(RGBColor::from_xyz(xyz), 0.5).to_css_string_with(legacy),
+            Alpha::new(HSLColor::from_xyz(xyz), 0.5).to_css_string_with(legacy),
+        ]
+        .iter()
+        {
+            let parsed = parse_css_color(string).unwrap();
+            assert!(parsed.distance(&xyz) <= 1e-6);
+            assert_eq!(parsed.alpha(), 0.5);
+        }
+    }
 }
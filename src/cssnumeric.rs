@@ -30,11 +30,20 @@ pub enum CSSParseError {
     /// This indicates that a general color syntax error occurred, such as mismatching parentheses or
     /// uninterpretable tokens.
     InvalidColorSyntax,
+    /// This indicates that a token couldn't be interpreted where it appeared, such as an unknown
+    /// function name or a percentage where an angle was expected. The value is the byte index in the
+    /// string where the offending token starts, or the length of the string if it ended too early.
+    InvalidToken(usize),
 }
 
 impl fmt::Display for CSSParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "CSS parsing error")
+        match *self {
+            CSSParseError::InvalidToken(pos) => {
+                write!(f, "CSS parsing error: invalid token at position {}", pos)
+            }
+            _ => write!(f, "CSS parsing error"),
+        }
     }
 }
 
@@ -44,6 +53,7 @@ impl Error for CSSParseError {
             CSSParseError::InvalidNumericCharacters => "Unexpected non-numeric characters",
             CSSParseError::InvalidNumericSyntax => "Invalid numeric syntax",
             CSSParseError::InvalidColorSyntax => "Invalid color syntax",
+            CSSParseError::InvalidToken(_) => "Invalid token",
         }
     }
 }
@@ -0,0 +1,552 @@
+//! This module simulates color vision deficiency (CVD), commonly called color blindness: it turns a
+//! color into one that looks, to someone with normal color vision, the way the original looks to
+//! someone with a CVD. This is useful for checking that charts, colormaps, and interfaces are still
+//! readable for the roughly 1 in 12 men and 1 in 200 women who have one.
+//!
+//! Most CVDs come from one of the three kinds of cones in the eye, L (long wavelength), M
+//! (medium), or S (short), being missing or having a shifted sensitivity. The former is called
+//! *dichromacy*, and the latter, much more common, *anomalous trichromacy*: a [`Deficiency`]
+//! describes which cone is affected. [`CVDSimulation`] selects a model:
+//! - Brettel, Viénot, and Mollon (1997), the classic simulation of dichromacy. It projects colors
+//!   onto two half-planes in LMS space that contain the colors dichromats see the same way as
+//!   everyone else.
+//! - Viénot, Brettel, and Mollon (1999), a simplification of the 1997 model that uses a single
+//!   plane. It's accurate for protanopia and deuteranopia, but not for tritanopia.
+//! - Machado, Oliveira, and Fernandes (2009), which models the shifted cone sensitivities of
+//!   anomalous trichromacy, with a severity from 0 (normal vision) to 1 (dichromacy).
+//! - Achromatopsia, complete color blindness, which leaves only luminance.
+//!
+//! All of these work on linear-light sRGB, so colors are converted to
+//! [`LinearRGBColor`](../colors/struct.LinearRGBColor.html), simulated, and clipped back into the
+//! sRGB gamut. The dichromacy matrices are derived using the Smith and Pokorny cone fundamentals
+//! that Viénot et al. use, with the 1997 model anchored on 475 nm and 575 nm for protanopia and
+//! deuteranopia and 485 nm and 660 nm for tritanopia, and the 1999 model anchored on the sRGB blue
+//! and red primaries. To check a colormap, wrap it in a [`CVDColorMap`].
+//!
+//! [`Deficiency`]: enum.Deficiency.html
+//! [`CVDSimulation`]: enum.CVDSimulation.html
+//! [`CVDColorMap`]: struct.CVDColorMap.html
+
+use color::Color;
+use colormap::ColorMap;
+use colors::LinearRGBColor;
+
+/// The parameters of the Brettel et al. model for one kind of dichromacy: the normal of the plane,
+/// in linear sRGB, that separates the two half-planes, and the matrices that project onto the
+/// half-planes on the positive and negative sides of it.
+struct BrettelParams {
+    normal: [f64; 3],
+    positive: [[f64; 3]; 3],
+    negative: [[f64; 3]; 3],
+}
+
+const BRETTEL_PROTAN: BrettelParams = BrettelParams {
+    normal: [0.104349, 0.895651, -1.0],
+    // the 575 nm half-plane
+    positive: [
+        [0.144982, 1.201761, -0.346743],
+        [0.104383, 0.853286, 0.042331],
+        [0.004270, -0.006002, 1.001732],
+    ],
+    // the 475 nm half-plane
+    negative: [
+        [0.141054, 1.168042, -0.309095],
+        [0.104862, 0.857403, 0.037735],
+        [0.004290, -0.005833, 1.001544],
+    ],
+};
+
+const BRETTEL_DEUTAN: BrettelParams = BrettelParams {
+    normal: [-0.312222, -0.687778, 1.0],
+    // the 475 nm half-plane
+    positive: [
+        [0.361928, 0.867684, -0.229613],
+        [0.260923, 0.645183, 0.093894],
+        [-0.019763, 0.026875, 0.992888],
+    ],
+    // the 575 nm half-plane
+    negative: [
+        [0.370007, 0.885480, -0.255486],
+        [0.257619, 0.637906, 0.104474],
+        [-0.019513, 0.027426, 0.992087],
+    ],
+};
+
+const BRETTEL_TRITAN: BrettelParams = BrettelParams {
+    normal: [1.0, -0.714805, -0.285195],
+    // the 660 nm half-plane
+    positive: [
+        [1.013530, 0.142689, -0.156219],
+        [-0.011796, 0.875597, 0.136199],
+        [0.077008, 0.812120, 0.110873],
+    ],
+    // the 485 nm half-plane
+    negative: [
+        [0.933385, 0.199977, -0.133362],
+        [0.058078, 0.825651, 0.116271],
+        [-0.379144, 1.138179, 0.240965],
+    ],
+};
+
+/// The Viénot et al. matrix for protanopia.
+const VIENOT_PROTAN: [[f64; 3]; 3] = [
+    [0.108800, 0.891200, 0.0],
+    [0.108800, 0.891200, 0.0],
+    [0.004451, -0.004451, 1.0],
+];
+
+/// The Viénot et al. matrix for deuteranopia.
+const VIENOT_DEUTAN: [[f64; 3]; 3] = [
+    [0.290238, 0.709762, 0.0],
+    [0.290238, 0.709762, 0.0],
+    [-0.021984, 0.021984, 1.0],
+];
+
+/// The Viénot et al. matrix for tritanopia.
+const VIENOT_TRITAN: [[f64; 3]; 3] = [
+    [1.0, 0.152360, -0.152360],
+    [0.0, 0.867165, 0.132835],
+    [0.0, 0.867165, 0.132835],
+];
+
+/// The Machado et al. matrices for protanomaly, for severities from 0 to 1 in steps of 0.1.
+const MACHADO_PROTAN: [[[f64; 3]; 3]; 11] = [
+    [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    [
+        [0.856167, 0.182038, -0.038205],
+        [0.029342, 0.955115, 0.015544],
+        [-0.002880, -0.001563, 1.004443],
+    ],
+    [
+        [0.734766, 0.334872, -0.069637],
+        [0.051840, 0.919198, 0.028963],
+        [-0.004928, -0.004209, 1.009137],
+    ],
+    [
+        [0.630323, 0.465641, -0.095964],
+        [0.069181, 0.890046, 0.040773],
+        [-0.006308, -0.007724, 1.014032],
+    ],
+    [
+        [0.539009, 0.579343, -0.118352],
+        [0.082546, 0.866121, 0.051332],
+        [-0.007136, -0.011959, 1.019095],
+    ],
+    [
+        [0.458064, 0.679578, -0.137642],
+        [0.092785, 0.846313, 0.060902],
+        [-0.007494, -0.016807, 1.024301],
+    ],
+    [
+        [0.385450, 0.769005, -0.154455],
+        [0.100526, 0.829802, 0.069673],
+        [-0.007442, -0.022190, 1.029632],
+    ],
+    [
+        [0.319627, 0.849633, -0.169261],
+        [0.106241, 0.815969, 0.077790],
+        [-0.007025, -0.028051, 1.035076],
+    ],
+    [
+        [0.259411, 0.923008, -0.182420],
+        [0.110296, 0.804340, 0.085364],
+        [-0.006276, -0.034346, 1.040622],
+    ],
+    [
+        [0.203876, 0.990338, -0.194214],
+        [0.112975, 0.794542, 0.092483],
+        [-0.005222, -0.041043, 1.046265],
+    ],
+    [
+        [0.152286, 1.052583, -0.204868],
+        [0.114503, 0.786281, 0.099216],
+        [-0.003882, -0.048116, 1.051998],
+    ],
+];
+/// The Machado et al. matrices for deuteranomaly, for severities from 0 to 1 in steps of 0.1.
+const MACHADO_DEUTAN: [[[f64; 3]; 3]; 11] = [
+    [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    [
+        [0.866435, 0.177704, -0.044139],
+        [0.049567, 0.939063, 0.011370],
+        [-0.003453, 0.007233, 0.996220],
+    ],
+    [
+        [0.760729, 0.319078, -0.079807],
+        [0.090568, 0.889315, 0.020117],
+        [-0.006027, 0.013325, 0.992702],
+    ],
+    [
+        [0.675425, 0.433850, -0.109275],
+        [0.125303, 0.847755, 0.026942],
+        [-0.007950, 0.018572, 0.989378],
+    ],
+    [
+        [0.605511, 0.528560, -0.134071],
+        [0.155318, 0.812366, 0.032316],
+        [-0.009376, 0.023176, 0.986200],
+    ],
+    [
+        [0.547494, 0.607765, -0.155259],
+        [0.181692, 0.781742, 0.036566],
+        [-0.010410, 0.027275, 0.983136],
+    ],
+    [
+        [0.498864, 0.674741, -0.173604],
+        [0.205199, 0.754872, 0.039929],
+        [-0.011131, 0.030969, 0.980162],
+    ],
+    [
+        [0.457771, 0.731899, -0.189670],
+        [0.226409, 0.731012, 0.042579],
+        [-0.011595, 0.034333, 0.977261],
+    ],
+    [
+        [0.422823, 0.781057, -0.203881],
+        [0.245752, 0.709602, 0.044646],
+        [-0.011843, 0.037423, 0.974421],
+    ],
+    [
+        [0.392952, 0.823610, -0.216562],
+        [0.263559, 0.690210, 0.046232],
+        [-0.011910, 0.040281, 0.971630],
+    ],
+    [
+        [0.367322, 0.860646, -0.227968],
+        [0.280085, 0.672501, 0.047413],
+        [-0.011820, 0.042940, 0.968881],
+    ],
+];
+/// The Machado et al. matrices for tritanomaly, for severities from 0 to 1 in steps of 0.1.
+const MACHADO_TRITAN: [[[f64; 3]; 3]; 11] = [
+    [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    [
+        [0.926670, 0.092514, -0.019184],
+        [0.021191, 0.964503, 0.014306],
+        [0.008437, 0.054813, 0.936750],
+    ],
+    [
+        [0.895720, 0.133330, -0.029050],
+        [0.029997, 0.945400, 0.024603],
+        [0.013027, 0.104707, 0.882266],
+    ],
+    [
+        [0.905871, 0.127791, -0.033662],
+        [0.026856, 0.941251, 0.031893],
+        [0.013410, 0.148296, 0.838294],
+    ],
+    [
+        [0.948035, 0.089490, -0.037526],
+        [0.014364, 0.946792, 0.038844],
+        [0.010853, 0.193991, 0.795156],
+    ],
+    [
+        [1.017277, 0.027029, -0.044306],
+        [-0.006113, 0.958479, 0.047634],
+        [0.006379, 0.248708, 0.744913],
+    ],
+    [
+        [1.104996, -0.046633, -0.058363],
+        [-0.032137, 0.971635, 0.060503],
+        [0.001336, 0.317922, 0.680742],
+    ],
+    [
+        [1.193214, -0.109812, -0.083402],
+        [-0.058496, 0.979410, 0.079086],
+        [-0.002346, 0.403492, 0.598854],
+    ],
+    [
+        [1.257728, -0.139648, -0.118081],
+        [-0.078003, 0.975409, 0.102594],
+        [-0.003316, 0.501214, 0.502102],
+    ],
+    [
+        [1.278864, -0.125333, -0.153531],
+        [-0.084748, 0.957674, 0.127074],
+        [-0.000989, 0.601151, 0.399838],
+    ],
+    [
+        [1.255528, -0.076749, -0.178779],
+        [-0.078411, 0.930809, 0.147602],
+        [0.004733, 0.691367, 0.303900],
+    ],
+];
+
+/// The luminance of each linear sRGB primary, used for achromatopsia.
+const SRGB_LUMINANCE: [f64; 3] = [0.2126, 0.7152, 0.0722];
+
+/// The kind of cone that a color vision deficiency affects.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Deficiency {
+    /// The L cones, which are most sensitive to long wavelengths: protanopia and protanomaly. Reds
+    /// look dark and are confused with greens.
+    Protan,
+    /// The M cones, which are most sensitive to medium wavelengths: deuteranopia and deuteranomaly.
+    /// This is by far the most common kind, and also confuses reds and greens.
+    Deutan,
+    /// The S cones, which are most sensitive to short wavelengths: tritanopia and tritanomaly. This
+    /// is rare, and confuses blues with greens and yellows with pinks.
+    Tritan,
+}
+
+/// A model of color vision deficiency, which can simulate how colors look to someone with it. See
+/// the [module documentation](index.html) for a description of each.
+/// # Example
+/// Red and green, which are about as different as colors get, are hard for dichromats to tell
+/// apart.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::cvd::{CVDSimulation, Deficiency};
+/// let red = RGBColor::from_hex_code("#d03030").unwrap();
+/// let green = RGBColor::from_hex_code("#6a8a20").unwrap();
+/// assert!(red.distance(&green) > 50.);
+/// let deutan = CVDSimulation::Brettel1997(Deficiency::Deutan);
+/// let red_cvd = deutan.simulate(&red);
+/// let green_cvd = deutan.simulate(&green);
+/// assert!(red_cvd.distance(&green_cvd) < 10.);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CVDSimulation {
+    /// The Brettel, Viénot, and Mollon (1997) model of dichromacy.
+    Brettel1997(Deficiency),
+    /// The Viénot, Brettel, and Mollon (1999) model of dichromacy. Fast and accurate for protanopia
+    /// and deuteranopia, but use `Brettel1997` for tritanopia.
+    Vienot1999(Deficiency),
+    /// The Machado, Oliveira, and Fernandes (2009) model of anomalous trichromacy, with the given
+    /// severity from 0 to 1: 0 is normal color vision, and 1 is dichromacy. Values outside of that
+    /// range are clamped. The matrices published for every tenth of severity are interpolated
+    /// between.
+    Machado2009(Deficiency, f64),
+    /// Complete achromatopsia, or rod monochromacy: only the luminance of a color is visible.
+    Achromatopsia,
+}
+
+impl CVDSimulation {
+    /// Simulates how the given color looks to someone with this color vision deficiency, returning
+    /// a color that looks the same to someone with normal color vision. The result is clipped to
+    /// the sRGB gamut.
+    /// # Example
+    /// Simulating protanopia darkens reds considerably, but leaves neutral colors alone.
+    ///
+    /// ```
+    /// # use scarlet::prelude::*;
+    /// # use scarlet::cvd::{CVDSimulation, Deficiency};
+    /// let protan = CVDSimulation::Vienot1999(Deficiency::Protan);
+    /// let red = RGBColor::from_hex_code("#ff0000").unwrap();
+    /// let gray = RGBColor::from_hex_code("#808080").unwrap();
+    /// assert!(protan.simulate(&red).lightness() < red.lightness() - 10.);
+    /// assert_eq!(protan.simulate(&gray).to_string(), "#808080");
+    /// ```
+    pub fn simulate<T: Color>(&self, color: &T) -> T {
+        let linear: LinearRGBColor = color.convert();
+        let rgb = [linear.r, linear.g, linear.b];
+        let simulated = match *self {
+            CVDSimulation::Brettel1997(deficiency) => {
+                let params = match deficiency {
+                    Deficiency::Protan => &BRETTEL_PROTAN,
+                    Deficiency::Deutan => &BRETTEL_DEUTAN,
+                    Deficiency::Tritan => &BRETTEL_TRITAN,
+                };
+                let side: f64 = params
+                    .normal
+                    .iter()
+                    .zip(rgb.iter())
+                    .map(|(n, c)| n * c)
+                    .sum();
+                if side >= 0.0 {
+                    apply(&params.positive, rgb)
+                } else {
+                    apply(&params.negative, rgb)
+                }
+            }
+            CVDSimulation::Vienot1999(deficiency) => {
+                let matrix = match deficiency {
+                    Deficiency::Protan => &VIENOT_PROTAN,
+                    Deficiency::Deutan => &VIENOT_DEUTAN,
+                    Deficiency::Tritan => &VIENOT_TRITAN,
+                };
+                apply(matrix, rgb)
+            }
+            CVDSimulation::Machado2009(deficiency, severity) => {
+                apply(&machado_matrix(deficiency, severity), rgb)
+            }
+            CVDSimulation::Achromatopsia => {
+                let y = apply(&[SRGB_LUMINANCE; 3], rgb);
+                [y[0], y[0], y[0]]
+            }
+        };
+        LinearRGBColor {
+            r: simulated[0].clamp(0.0, 1.0),
+            g: simulated[1].clamp(0.0, 1.0),
+            b: simulated[2].clamp(0.0, 1.0),
+        }
+        .convert()
+    }
+}
+
+/// Multiplies a matrix by a column vector.
+fn apply(matrix: &[[f64; 3]; 3], v: [f64; 3]) -> [f64; 3] {
+    let mut out = [0.0; 3];
+    for (out, row) in out.iter_mut().zip(matrix.iter()) {
+        *out = row[0] * v[0] + row[1] * v[1] + row[2] * v[2];
+    }
+    out
+}
+
+/// Linearly interpolates between the two tabulated Machado et al. matrices that bound the given
+/// severity.
+fn machado_matrix(deficiency: Deficiency, severity: f64) -> [[f64; 3]; 3] {
+    let table = match deficiency {
+        Deficiency::Protan => &MACHADO_PROTAN,
+        Deficiency::Deutan => &MACHADO_DEUTAN,
+        Deficiency::Tritan => &MACHADO_TRITAN,
+    };
+    let scaled = severity.clamp(0.0, 1.0) * 10.0;
+    let lower = (scaled.floor() as usize).min(9);
+    let frac = scaled - lower as f64;
+    let mut matrix = [[0.0; 3]; 3];
+    for (i, row) in matrix.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = table[lower][i][j] * (1.0 - frac) + table[lower + 1][i][j] * frac;
+        }
+    }
+    matrix
+}
+
+/// A colormap that simulates how another colormap looks to someone with a color vision deficiency,
+/// by applying a [`CVDSimulation`](enum.CVDSimulation.html) to each of its outputs.
+/// # Example
+/// A red-green gradient is a poor choice for deuteranopes, who can barely tell its ends apart,
+/// while viridis stays readable.
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::colormap::{ColorMap, GradientColorMap, ListedColorMap};
+/// # use scarlet::cvd::{CVDColorMap, CVDSimulation, Deficiency};
+/// let deutan = CVDSimulation::Brettel1997(Deficiency::Deutan);
+/// let red = RGBColor::from_hex_code("#c83c3c").unwrap();
+/// let green = RGBColor::from_hex_code("#6e8c28").unwrap();
+/// let red_green = CVDColorMap::new(GradientColorMap::new_linear(red, green), deutan);
+/// let viridis = CVDColorMap::new(ListedColorMap::viridis(), deutan);
+/// let ends: Vec<RGBColor> = red_green.transform(vec![0., 1.]);
+/// assert!(ends[0].distance(&ends[1]) < 10.);
+/// let ends: Vec<RGBColor> = viridis.transform(vec![0., 1.]);
+/// assert!(ends[0].distance(&ends[1]) > 50.);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CVDColorMap<M> {
+    /// The colormap being simulated.
+    pub colormap: M,
+    /// The color vision deficiency to simulate.
+    pub simulation: CVDSimulation,
+}
+
+impl<M> CVDColorMap<M> {
+    /// Wraps a colormap so that its outputs are seen through the given simulation.
+    pub fn new(colormap: M, simulation: CVDSimulation) -> CVDColorMap<M> {
+        CVDColorMap {
+            colormap,
+            simulation,
+        }
+    }
+}
+
+impl<T: Color, M: ColorMap<T>> ColorMap<T> for CVDColorMap<M> {
+    fn transform_single(&self, x: f64) -> T {
+        self.simulation.simulate(&self.colormap.transform_single(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use color::RGBColor;
+    use colormap::ListedColorMap;
+
+    const SIMULATIONS: [CVDSimulation; 11] = [
+        CVDSimulation::Brettel1997(Deficiency::Protan),
+        CVDSimulation::Brettel1997(Deficiency::Deutan),
+        CVDSimulation::Brettel1997(Deficiency::Tritan),
+        CVDSimulation::Vienot1999(Deficiency::Protan),
+        CVDSimulation::Vienot1999(Deficiency::Deutan),
+        CVDSimulation::Vienot1999(Deficiency::Tritan),
+        CVDSimulation::Machado2009(Deficiency::Protan, 0.35),
+        CVDSimulation::Machado2009(Deficiency::Deutan, 1.0),
+        CVDSimulation::Machado2009(Deficiency::Tritan, 0.8),
+        CVDSimulation::Machado2009(Deficiency::Deutan, 2.0),
+        CVDSimulation::Achromatopsia,
+    ];
+
+    #[test]
+    fn test_neutrals() {
+        // every model leaves white, black, and grays alone
+        for sim in SIMULATIONS.iter() {
+            for &hex in ["#000000", "#FFFFFF", "#808080", "#3A3A3A"].iter() {
+                let color = RGBColor::from_hex_code(hex).unwrap();
+                assert_eq!(sim.simulate(&color).to_string(), hex, "{:?}", sim);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dichromacy() {
+        // dichromats only have two dimensions of color vision, so simulating twice changes nothing
+        let colors = ["#4080C0", "#B06040", "#60A060", "#A080B0"];
+        for sim in SIMULATIONS[..6].iter() {
+            for &hex in colors.iter() {
+                let once = sim.simulate(&RGBColor::from_hex_code(hex).unwrap());
+                let twice = sim.simulate(&once);
+                assert!(once.distance(&twice) <= 1e-3, "{:?} {}", sim, hex);
+            }
+        }
+        // the two dichromacy models roughly agree for protanopia and deuteranopia: the single plane
+        // of the 1999 model differs the most from the 1997 model for blues
+        for &deficiency in [Deficiency::Protan, Deficiency::Deutan].iter() {
+            for &hex in colors.iter() {
+                let color = RGBColor::from_hex_code(hex).unwrap();
+                let brettel = CVDSimulation::Brettel1997(deficiency).simulate(&color);
+                let vienot = CVDSimulation::Vienot1999(deficiency).simulate(&color);
+                assert!(brettel.distance(&vienot) <= 12.0, "{}", hex);
+            }
+        }
+    }
+
+    #[test]
+    fn test_machado_severity() {
+        let color = RGBColor::from_hex_code("#D04080").unwrap();
+        // no severity is normal vision, and the tabulated severities are used exactly
+        let normal = CVDSimulation::Machado2009(Deficiency::Protan, 0.0).simulate(&color);
+        assert!(normal.distance(&color) <= 1e-6);
+        let half = machado_matrix(Deficiency::Tritan, 0.5);
+        assert_eq!(half, MACHADO_TRITAN[5]);
+        assert_eq!(machado_matrix(Deficiency::Deutan, 1.0), MACHADO_DEUTAN[10]);
+        // the difference from normal vision grows with severity
+        let mut last = 0.0;
+        for i in 1..=20 {
+            let sim = CVDSimulation::Machado2009(Deficiency::Deutan, f64::from(i) / 20.0);
+            let dist = sim.simulate(&color).distance(&color);
+            assert!(dist > last);
+            last = dist;
+        }
+        // and complete deuteranomaly is deuteranopia
+        let brettel = CVDSimulation::Brettel1997(Deficiency::Deutan).simulate(&color);
+        let machado = CVDSimulation::Machado2009(Deficiency::Deutan, 1.0).simulate(&color);
+        assert!(brettel.distance(&machado) <= 5.0);
+    }
+
+    #[test]
+    fn test_colormap() {
+        // viridis is designed to be monotonic in lightness, which survives any CVD
+        let viridis = ListedColorMap::viridis();
+        let inputs: Vec<f64> = (0..=50).map(|i| f64::from(i) / 50.0).collect();
+        for sim in SIMULATIONS.iter() {
+            let cvd = CVDColorMap::new(viridis.clone(), *sim);
+            let colors: Vec<RGBColor> = cvd.transform(inputs.clone());
+            for pair in colors.windows(2) {
+                assert!(pair[1].lightness() > pair[0].lightness(), "{:?}", sim);
+            }
+        }
+    }
+}
@@ -0,0 +1,425 @@
+//! This module defines [`DeltaE`](delta_e::DeltaE), an enum of the many different color difference
+//! formulas that have been standardized over the years. [`Color::distance`] uses CIEDE2000, the
+//! current standard and the most accurate, but many industries standardized on older formulas (or
+//! different weights) and still use them: textile work generally uses CMC l:c, for example, and a
+//! lot of print quality control is done with plain CIE76. To compute a difference with any of these,
+//! use [`Color::distance_with`].
+//!
+//! Every formula besides CIE76 and CAM16-UCS is asymmetric: the first color, the one whose method
+//! is called, is treated as the reference (or "standard") color, and the weights are computed from
+//! it. This matches how these formulas are used in practice, but it does mean that swapping the
+//! colors can slightly change the result.
+//!
+//! [`Color::distance`]: ../color/trait.Color.html#method.distance
+//! [`Color::distance_with`]: ../color/trait.Color.html#method.distance_with
+
+use color::Color;
+use colors::cam16ucscolor::CAM16UCSColor;
+use colors::cielabcolor::CIELABColor;
+
+/// A color difference formula, along with any parametric weights it uses. The weights that each
+/// formula was designed with are available as associated constants, and [`Default`] gives
+/// CIEDE2000 with all weights equal to 1, which is what [`Color::distance`] uses.
+///
+/// # Example
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::delta_e::DeltaE;
+/// let standard = RGBColor::from_hex_code("#4B7ACD").unwrap();
+/// let sample = RGBColor::from_hex_code("#4A7DC8").unwrap();
+/// // the same pair of colors, measured in a few different ways
+/// let de76 = standard.distance_with(&sample, DeltaE::CIE76);
+/// let de94 = standard.distance_with(&sample, DeltaE::CIE94_TEXTILES);
+/// let cmc = standard.distance_with(&sample, DeltaE::CMC_ACCEPTABILITY);
+/// let de00 = standard.distance_with(&sample, DeltaE::default());
+/// assert_eq!(de00, standard.distance(&sample));
+/// // the older formulas overestimate differences in saturated colors like this one
+/// assert!(de76 > de94);
+/// println!("{} {} {} {}", de76, de94, cmc, de00);
+/// ```
+///
+/// [`Default`]: #impl-Default
+/// [`Color::distance`]: ../color/trait.Color.html#method.distance
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DeltaE {
+    /// The original 1976 formula: the Euclidean distance between two colors in CIELAB. Simple and
+    /// still widely used, but it noticeably overestimates differences in saturated colors.
+    CIE76,
+    /// The 1994 revision, which weights differences in chroma and hue less for more saturated
+    /// colors. `k_l` weights lightness, and `k_1` and `k_2` control how much the chroma and hue
+    /// weights grow with the chroma of the reference color. See
+    /// [`CIE94_GRAPHIC_ARTS`](#associatedconstant.CIE94_GRAPHIC_ARTS) and
+    /// [`CIE94_TEXTILES`](#associatedconstant.CIE94_TEXTILES) for the standard values.
+    CIE94 {
+        /// The lightness weight. Higher values make differences in lightness matter less.
+        k_l: f64,
+        /// How much the chroma weight grows with chroma.
+        k_1: f64,
+        /// How much the hue weight grows with chroma.
+        k_2: f64,
+    },
+    /// The formula developed by the Colour Measurement Committee of the Society of Dyers and
+    /// Colourists, used a lot in the textile industry. It's generally written CMC l:c, with `l`
+    /// weighting lightness and `c` weighting chroma: 2:1 is used to judge acceptability and 1:1 to
+    /// judge perceptibility.
+    CMC {
+        /// The lightness weight. Higher values make differences in lightness matter less.
+        l: f64,
+        /// The chroma weight. Higher values make differences in chroma matter less.
+        c: f64,
+    },
+    /// The current CIE standard, CIEDE2000, with its three parametric weights for lightness,
+    /// chroma, and hue. These are all 1 in pretty much any application, which is what
+    /// [`Default`](#impl-Default) uses, but some industries use different values: textiles often
+    /// use a lightness weight of 2, for example.
+    CIEDE2000 {
+        /// The lightness weight. Higher values make differences in lightness matter less.
+        k_l: f64,
+        /// The chroma weight. Higher values make differences in chroma matter less.
+        k_c: f64,
+        /// The hue weight. Higher values make differences in hue matter less.
+        k_h: f64,
+    },
+    /// The Euclidean distance in [CAM16-UCS](../colors/cam16ucscolor/index.html) under the default
+    /// viewing conditions, the same as [`Color::cam16_distance`]. This is on a different scale than
+    /// the others.
+    ///
+    /// [`Color::cam16_distance`]: ../color/trait.Color.html#method.cam16_distance
+    CAM16UCS,
+}
+
+impl DeltaE {
+    /// CIE94 with the weights meant for the graphic arts: `k_l = 1`, `k_1 = 0.045`, and `k_2 =
+    /// 0.015`.
+    pub const CIE94_GRAPHIC_ARTS: DeltaE = DeltaE::CIE94 {
+        k_l: 1.0,
+        k_1: 0.045,
+        k_2: 0.015,
+    };
+    /// CIE94 with the weights meant for textiles: `k_l = 2`, `k_1 = 0.048`, and `k_2 = 0.014`.
+    pub const CIE94_TEXTILES: DeltaE = DeltaE::CIE94 {
+        k_l: 2.0,
+        k_1: 0.048,
+        k_2: 0.014,
+    };
+    /// CMC 2:1, used to judge whether a difference is acceptable.
+    pub const CMC_ACCEPTABILITY: DeltaE = DeltaE::CMC { l: 2.0, c: 1.0 };
+    /// CMC 1:1, used to judge whether a difference is perceptible.
+    pub const CMC_PERCEPTIBILITY: DeltaE = DeltaE::CMC { l: 1.0, c: 1.0 };
+
+    /// Computes the difference between a reference color and a sample using this formula. This is
+    /// what [`Color::distance_with`](../color/trait.Color.html#method.distance_with) uses, and is
+    /// generally more convenient to call through that.
+    pub fn distance<T: Color, U: Color>(&self, reference: &T, sample: &U) -> f64 {
+        match *self {
+            DeltaE::CIE76 => {
+                let (lab1, lab2) = lab_pair(reference, sample);
+                ((lab1.l - lab2.l).powi(2) + (lab1.a - lab2.a).powi(2) + (lab1.b - lab2.b).powi(2))
+                    .sqrt()
+            }
+            DeltaE::CIE94 { k_l, k_1, k_2 } => {
+                let (lab1, lab2) = lab_pair(reference, sample);
+                cie94(lab1, lab2, k_l, k_1, k_2)
+            }
+            DeltaE::CMC { l, c } => {
+                let (lab1, lab2) = lab_pair(reference, sample);
+                cmc(lab1, lab2, l, c)
+            }
+            DeltaE::CIEDE2000 { k_l, k_c, k_h } => {
+                let (lab1, lab2) = lab_pair(reference, sample);
+                ciede2000(lab1, lab2, k_l, k_c, k_h)
+            }
+            DeltaE::CAM16UCS => {
+                let ucs1: CAM16UCSColor = reference.convert();
+                let ucs2: CAM16UCSColor = sample.convert();
+                ((ucs1.j - ucs2.j).powi(2) + (ucs1.a - ucs2.a).powi(2) + (ucs1.b - ucs2.b).powi(2))
+                    .sqrt()
+            }
+        }
+    }
+}
+
+impl Default for DeltaE {
+    /// CIEDE2000 with all of its weights set to 1.
+    fn default() -> DeltaE {
+        DeltaE::CIEDE2000 {
+            k_l: 1.0,
+            k_c: 1.0,
+            k_h: 1.0,
+        }
+    }
+}
+
+// every formula except CAM16-UCS works in CIELAB
+fn lab_pair<T: Color, U: Color>(reference: &T, sample: &U) -> (CIELABColor, CIELABColor) {
+    (reference.convert(), sample.convert())
+}
+
+// Both CIE94 and CMC split the difference in a and b into the difference in chroma and a leftover
+// hue difference. This returns the chroma of the reference color, the chroma difference, and the
+// square of the hue difference, which is all either formula needs.
+fn chroma_hue_differences(lab1: CIELABColor, lab2: CIELABColor) -> (f64, f64, f64) {
+    let c_1 = lab1.a.hypot(lab1.b);
+    let c_2 = lab2.a.hypot(lab2.b);
+    let delta_c = c_1 - c_2;
+    // rounding error can make this very slightly negative when the hues are the same
+    let delta_h_sq =
+        ((lab1.a - lab2.a).powi(2) + (lab1.b - lab2.b).powi(2) - delta_c.powi(2)).max(0.0);
+    (c_1, delta_c, delta_h_sq)
+}
+
+fn cie94(lab1: CIELABColor, lab2: CIELABColor, k_l: f64, k_1: f64, k_2: f64) -> f64 {
+    let delta_l = lab1.l - lab2.l;
+    let (c_1, delta_c, delta_h_sq) = chroma_hue_differences(lab1, lab2);
+    let s_c = 1.0 + k_1 * c_1;
+    let s_h = 1.0 + k_2 * c_1;
+    // s_l, k_c, and k_h are all 1 in every version of this formula, so they're omitted
+    ((delta_l / k_l).powi(2) + (delta_c / s_c).powi(2) + delta_h_sq / s_h.powi(2)).sqrt()
+}
+
+fn cmc(lab1: CIELABColor, lab2: CIELABColor, l: f64, c: f64) -> f64 {
+    let delta_l = lab1.l - lab2.l;
+    let (c_1, delta_c, delta_h_sq) = chroma_hue_differences(lab1, lab2);
+    let unbounded_h = lab1.b.atan2(lab1.a).to_degrees();
+    let h_1 = if unbounded_h < 0.0 {
+        unbounded_h + 360.0
+    } else {
+        unbounded_h
+    };
+
+    let f = (c_1.powi(4) / (c_1.powi(4) + 1900.0)).sqrt();
+    let t = if (164.0..=345.0).contains(&h_1) {
+        0.56 + (0.2 * (h_1 + 168.0).to_radians().cos()).abs()
+    } else {
+        0.36 + (0.4 * (h_1 + 35.0).to_radians().cos()).abs()
+    };
+    // the lightness weight levels off for very dark colors
+    let s_l = if lab1.l < 16.0 {
+        0.511
+    } else {
+        0.040975 * lab1.l / (1.0 + 0.01765 * lab1.l)
+    };
+    let s_c = 0.0638 * c_1 / (1.0 + 0.0131 * c_1) + 0.638;
+    let s_h = s_c * (f * t + 1.0 - f);
+    ((delta_l / (l * s_l)).powi(2) + (delta_c / (c * s_c)).powi(2) + delta_h_sq / s_h.powi(2))
+        .sqrt()
+}
+
+fn ciede2000(lab1: CIELABColor, lab2: CIELABColor, k_l: f64, k_c: f64, k_h: f64) -> f64 {
+    // implementation reference found here:
+    // https://pdfs.semanticscholar.org/969b/c38ea067dd22a47a44bcb59c23807037c8d8.pdf
+
+    // I'm going to match the notation in that text pretty much exactly: it's the only way to
+    // keep this both concise and readable
+
+    // step 1: calculation of C and h
+    // the method hypot returns sqrt(a^2 + b^2)
+    let c_star_1: f64 = lab1.a.hypot(lab1.b);
+    let c_star_2: f64 = lab2.a.hypot(lab2.b);
+
+    let c_bar_ab: f64 = (c_star_1 + c_star_2) / 2.0;
+    let g = 0.5 * (1.0 - ((c_bar_ab.powi(7)) / (c_bar_ab.powi(7) + 25.0f64.powi(7))).sqrt());
+
+    let a_prime_1 = (1.0 + g) * lab1.a;
+    let a_prime_2 = (1.0 + g) * lab2.a;
+
+    let c_prime_1 = a_prime_1.hypot(lab1.b);
+    let c_prime_2 = a_prime_2.hypot(lab2.b);
+
+    // this closure simply does the atan2 like CIELCH, but safely accounts for a == b == 0
+    // we're gonna do this twice, so I just use a closure
+    let h_func = |a: f64, b: f64| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            let val = b.atan2(a).to_degrees();
+            if val < 0.0 {
+                val + 360.0
+            } else {
+                val
+            }
+        }
+    };
+
+    let h_prime_1 = h_func(a_prime_1, lab1.b);
+    let h_prime_2 = h_func(a_prime_2, lab2.b);
+
+    // step 2: computing delta L, delta C, and delta H
+    // take a deep breath, you got this!
+
+    let delta_l = lab2.l - lab1.l;
+    let delta_c = c_prime_2 - c_prime_1;
+    // essentially, compute the difference in hue but keep it in the right range
+    let delta_angle_h = if c_prime_1 * c_prime_2 == 0.0 {
+        0.0
+    } else if (h_prime_2 - h_prime_1).abs() <= 180.0 {
+        h_prime_2 - h_prime_1
+    } else if h_prime_2 - h_prime_1 > 180.0 {
+        h_prime_2 - h_prime_1 - 360.0
+    } else {
+        h_prime_2 - h_prime_1 + 360.0
+    };
+    // now get the Cartesian equivalent of the angle difference in hue
+    // this also corrects for chromaticity mattering less at low luminances
+    let delta_h = 2.0 * (c_prime_1 * c_prime_2).sqrt() * (delta_angle_h / 2.0).to_radians().sin();
+
+    // step 3: the color difference
+    // if you're reading this, it's not too late to back out
+    let l_bar_prime = (lab1.l + lab2.l) / 2.0;
+    let c_bar_prime = (c_prime_1 + c_prime_2) / 2.0;
+    let h_bar_prime = if c_prime_1 * c_prime_2 == 0.0 {
+        h_prime_1 + h_prime_2
+    } else if (h_prime_2 - h_prime_1).abs() <= 180.0 {
+        (h_prime_1 + h_prime_2) / 2.0
+    } else if h_prime_1 + h_prime_2 < 360.0 {
+        (h_prime_1 + h_prime_2 + 360.0) / 2.0
+    } else {
+        (h_prime_1 + h_prime_2 - 360.0) / 2.0
+    };
+
+    // we're gonna use this a lot
+    let deg_cos = |x: f64| x.to_radians().cos();
+
+    let t = 1.0 - 0.17 * deg_cos(h_bar_prime - 30.0)
+        + 0.24 * deg_cos(2.0 * h_bar_prime)
+        + 0.32 * deg_cos(3.0 * h_bar_prime + 6.0)
+        - 0.20 * deg_cos(4.0 * h_bar_prime - 63.0);
+
+    let delta_theta = 30.0 * (-((h_bar_prime - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (c_bar_prime.powi(7) / (c_bar_prime.powi(7) + 25.0f64.powi(7))).sqrt();
+    let s_l = 1.0
+        + ((0.015 * (l_bar_prime - 50.0).powi(2)) / (20.0 + (l_bar_prime - 50.0).powi(2)).sqrt());
+    let s_c = 1.0 + 0.045 * c_bar_prime;
+    let s_h = 1.0 + 0.015 * c_bar_prime * t;
+    let r_t = -r_c * (2.0 * delta_theta).to_radians().sin();
+    // finally, the end result, with the parametric weights for lightness, chroma, and hue
+    ((delta_l / (k_l * s_l)).powi(2)
+        + (delta_c / (k_c * s_c)).powi(2)
+        + (delta_h / (k_h * s_h)).powi(2)
+        + r_t * (delta_c / (k_c * s_c)) * (delta_h / (k_h * s_h)))
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // a few pairs from the CIEDE2000 test data, which exercise a range of lightnesses and hues
+    fn test_pairs() -> Vec<(CIELABColor, CIELABColor)> {
+        vec![
+            (
+                CIELABColor {
+                    l: 50.0,
+                    a: 2.6772,
+                    b: -79.7751,
+                },
+                CIELABColor {
+                    l: 50.0,
+                    a: 0.0,
+                    b: -82.7485,
+                },
+            ),
+            (
+                CIELABColor {
+                    l: 60.2574,
+                    a: -34.0099,
+                    b: 36.2677,
+                },
+                CIELABColor {
+                    l: 60.4626,
+                    a: -34.1751,
+                    b: 39.4387,
+                },
+            ),
+            (
+                CIELABColor {
+                    l: 50.0,
+                    a: 2.5,
+                    b: 0.0,
+                },
+                CIELABColor {
+                    l: 73.0,
+                    a: 25.0,
+                    b: -18.0,
+                },
+            ),
+        ]
+    }
+
+    fn check(method: DeltaE, expected: [f64; 3]) {
+        for ((lab1, lab2), d_e) in test_pairs().into_iter().zip(expected.iter()) {
+            assert!((method.distance(&lab1, &lab2) - d_e).abs() <= 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_cie76() {
+        check(
+            DeltaE::CIE76,
+            [4.001063283678486, 3.1819238017275016, 36.86800781165155],
+        );
+    }
+    #[test]
+    fn test_cie94() {
+        check(
+            DeltaE::CIE94_GRAPHIC_ARTS,
+            [1.3950388678587375, 1.3909947094745128, 34.68916319804271],
+        );
+        check(
+            DeltaE::CIE94_TEXTILES,
+            [1.4230462054212831, 1.3897333208830112, 28.25026349619294],
+        );
+    }
+    #[test]
+    fn test_cmc() {
+        check(
+            DeltaE::CMC_ACCEPTABILITY,
+            [1.738736105726153, 1.4204860453649197, 37.923276169430395],
+        );
+        check(
+            DeltaE::CMC_PERCEPTIBILITY,
+            [1.738736105726153, 1.428229509299823, 42.10875484558739],
+        );
+    }
+    #[test]
+    fn test_ciede2000_weights() {
+        for (lab1, lab2) in test_pairs() {
+            assert_eq!(
+                DeltaE::default().distance(&lab1, &lab2),
+                lab1.distance(&lab2)
+            );
+            // with no lightness difference, the lightness weight shouldn't matter
+            let textiles = DeltaE::CIEDE2000 {
+                k_l: 2.0,
+                k_c: 1.0,
+                k_h: 1.0,
+            };
+            if lab1.l == lab2.l {
+                assert!((textiles.distance(&lab1, &lab2) - lab1.distance(&lab2)).abs() <= 1e-10);
+            } else {
+                assert!(textiles.distance(&lab1, &lab2) < lab1.distance(&lab2));
+            }
+        }
+    }
+    #[test]
+    fn test_identical_colors() {
+        let lab = CIELABColor {
+            l: 45.0,
+            a: 12.0,
+            b: -30.0,
+        };
+        for method in [
+            DeltaE::CIE76,
+            DeltaE::CIE94_GRAPHIC_ARTS,
+            DeltaE::CMC_ACCEPTABILITY,
+            DeltaE::default(),
+            DeltaE::CAM16UCS,
+        ]
+        .iter()
+        {
+            assert!(method.distance(&lab, &lab) <= 1e-10);
+        }
+    }
+}
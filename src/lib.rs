@@ -14,7 +14,6 @@
 // compare -0.96924 with -0.96_924
 #![allow(clippy::unreadable_literal)]
 
-extern crate csv;
 extern crate geo;
 #[macro_use]
 extern crate rulinalg;
@@ -25,19 +24,31 @@ extern crate serde_derive;
 #[macro_use]
 extern crate lazy_static;
 
+pub mod adaptation;
+pub mod alpha;
 pub mod bound;
+pub mod cam16;
+mod cct;
+pub mod cmf;
 pub mod color;
 pub mod colormap;
 pub mod colorpoint;
 pub mod colors;
+pub mod compositing;
 mod consts;
+pub mod contrast;
 pub mod coord;
-mod csscolor;
+pub mod csscolor;
 mod cssnumeric;
+pub mod cvd;
+pub mod delta_e;
 pub mod illuminants;
+mod illuminant_spds;
 pub mod material_colors;
 mod matplotlib_cmaps;
 pub mod prelude;
+pub mod spectrum;
+pub mod transfer;
 mod visual_gamut;
 // pub mod doc;
 
@@ -8,4 +8,5 @@
 pub use bound::Bound;
 pub use color::{Color, RGBColor, RGBParseError};
 pub use colorpoint::ColorPoint;
+pub use csscolor::ToCSS;
 pub use illuminants::Illuminant;
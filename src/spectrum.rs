@@ -0,0 +1,477 @@
+//! This module provides a type for spectral power distributions and reflectances: the amount of
+//! light at each wavelength, which is what a spectrophotometer measures. Every color that the eye
+//! sees comes from some spectrum, and many different spectra look like the same color, so this is
+//! the most fundamental representation of color there is. Scarlet can turn a spectrum into a color
+//! by integrating it against a standard observer's color matching functions, either as a light seen
+//! directly or as a surface lit by some other light.
+
+use std::error::Error;
+use std::fmt;
+
+use cct::daylight_xy;
+use cmf::Observer;
+use color::XYZColor;
+use illuminants::Illuminant;
+
+/// The CIE daylight basis functions S0, S1, and S2 from CIE 15, as `[wavelength, S0, S1, S2]`. Any
+/// phase of daylight is a mix of these, with the weights determined by its chromaticity.
+const DAYLIGHT_BASIS: [[f64; 4]; 54] = [
+    [300.0, 0.04, 0.02, 0.00],
+    [310.0, 6.0, 4.5, 2.0],
+    [320.0, 29.6, 22.4, 4.0],
+    [330.0, 55.3, 42.0, 8.5],
+    [340.0, 57.3, 40.6, 7.8],
+    [350.0, 61.8, 41.6, 6.7],
+    [360.0, 61.5, 38.0, 5.3],
+    [370.0, 68.8, 42.4, 6.1],
+    [380.0, 63.4, 38.5, 3.0],
+    [390.0, 65.8, 35.0, 1.2],
+    [400.0, 94.8, 43.4, -1.1],
+    [410.0, 104.8, 46.3, -0.5],
+    [420.0, 105.9, 43.9, -0.7],
+    [430.0, 96.8, 37.1, -1.2],
+    [440.0, 113.9, 36.7, -2.6],
+    [450.0, 125.6, 35.9, -2.9],
+    [460.0, 125.5, 32.6, -2.8],
+    [470.0, 121.3, 27.9, -2.6],
+    [480.0, 121.3, 24.3, -2.6],
+    [490.0, 113.5, 20.1, -1.8],
+    [500.0, 113.1, 16.2, -1.5],
+    [510.0, 110.8, 13.2, -1.3],
+    [520.0, 106.5, 8.6, -1.2],
+    [530.0, 108.8, 6.1, -1.0],
+    [540.0, 105.3, 4.2, -0.5],
+    [550.0, 104.4, 1.9, -0.3],
+    [560.0, 100.0, 0.0, 0.0],
+    [570.0, 96.0, -1.6, 0.2],
+    [580.0, 95.1, -3.5, 0.5],
+    [590.0, 89.1, -3.5, 2.1],
+    [600.0, 90.5, -5.8, 3.2],
+    [610.0, 90.3, -7.2, 4.1],
+    [620.0, 88.4, -8.6, 4.7],
+    [630.0, 84.0, -9.5, 5.1],
+    [640.0, 85.1, -10.9, 6.7],
+    [650.0, 81.9, -10.7, 7.3],
+    [660.0, 82.6, -12.0, 8.6],
+    [670.0, 84.9, -14.0, 9.8],
+    [680.0, 81.3, -13.6, 10.2],
+    [690.0, 71.9, -12.0, 8.3],
+    [700.0, 74.3, -13.3, 9.6],
+    [710.0, 76.4, -12.9, 8.5],
+    [720.0, 63.3, -10.6, 7.0],
+    [730.0, 71.7, -11.6, 7.6],
+    [740.0, 77.0, -12.2, 8.0],
+    [750.0, 65.2, -10.2, 6.7],
+    [760.0, 47.7, -7.8, 5.2],
+    [770.0, 68.6, -11.2, 7.4],
+    [780.0, 65.0, -10.4, 6.8],
+    [790.0, 66.0, -10.6, 7.0],
+    [800.0, 61.0, -9.7, 6.4],
+    [810.0, 53.3, -8.3, 5.5],
+    [820.0, 58.9, -9.3, 6.1],
+    [830.0, 61.9, -9.8, 6.5],
+];
+
+/// An error in creating a spectrum from data.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpectrumError {
+    /// The spectrum has no samples.
+    Empty,
+    /// The number of wavelengths and the number of values differ.
+    MismatchedLengths,
+    /// The wavelengths are not strictly increasing, or some aren't finite.
+    UnsortedWavelengths,
+}
+
+impl fmt::Display for SpectrumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SpectrumError::Empty => write!(f, "Spectrum has no samples"),
+            SpectrumError::MismatchedLengths => {
+                write!(
+                    f,
+                    "Spectrum has different numbers of wavelengths and values"
+                )
+            }
+            SpectrumError::UnsortedWavelengths => {
+                write!(f, "Spectrum wavelengths are not strictly increasing")
+            }
+        }
+    }
+}
+
+impl Error for SpectrumError {}
+
+/// A spectrum, sampled at a list of wavelengths in nanometers. This can represent either the power
+/// of a light at each wavelength (a *spectral power distribution*) or the fraction of light a
+/// surface reflects at each wavelength (a *reflectance*, from 0 to 1), depending on how it's used.
+/// Between samples, values are linearly interpolated. Outside of the sampled range, the nearest
+/// sample is used, as CIE 15 recommends for measurements that don't cover the whole visible range.
+/// # Example
+/// A paint sample measured with a spectrophotometer from 400 nm to 700 nm, every 20 nm:
+///
+/// ```
+/// # use scarlet::prelude::*;
+/// # use scarlet::spectrum::Spectrum;
+/// let wavelengths: Vec<f64> = (0..16).map(|i| 400.0 + 20.0 * i as f64).collect();
+/// let reflectance = vec![0.05, 0.06, 0.07, 0.07, 0.08, 0.09, 0.12, 0.2,
+///                        0.38, 0.55, 0.63, 0.66, 0.68, 0.69, 0.7, 0.7];
+/// let paint = Spectrum::new(wavelengths, reflectance).unwrap();
+/// let d65 = Illuminant::D65.spd().unwrap();
+/// let color: RGBColor = paint.reflectance_to_xyz(&d65).convert();
+/// // it's an orange
+/// assert!(color.r > color.g && color.g > color.b);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Spectrum {
+    wavelengths: Vec<f64>,
+    values: Vec<f64>,
+}
+
+impl Spectrum {
+    /// Creates a new spectrum from the given wavelengths, in nanometers, and the values at each of
+    /// them.
+    /// # Errors
+    /// Returns `SpectrumError::Empty` if there are no samples, `SpectrumError::MismatchedLengths` if
+    /// the two vectors have different lengths, and `SpectrumError::UnsortedWavelengths` if the
+    /// wavelengths aren't finite and strictly increasing.
+    pub fn new(wavelengths: Vec<f64>, values: Vec<f64>) -> Result<Spectrum, SpectrumError> {
+        if wavelengths.len() != values.len() {
+            Err(SpectrumError::MismatchedLengths)
+        } else if wavelengths.is_empty() {
+            Err(SpectrumError::Empty)
+        } else if wavelengths.iter().any(|w| !w.is_finite())
+            || wavelengths.windows(2).any(|w| w[0] >= w[1])
+        {
+            Err(SpectrumError::UnsortedWavelengths)
+        } else {
+            Ok(Spectrum {
+                wavelengths,
+                values,
+            })
+        }
+    }
+    /// Creates a new spectrum by sampling the given function every `step` nanometers from `start` to
+    /// `end`, inclusive. Panics if `step` isn't positive or `end` is less than `start`.
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::spectrum::Spectrum;
+    /// let flat = Spectrum::from_fn(380.0, 780.0, 10.0, |_| 1.0);
+    /// assert_eq!(flat.wavelengths().len(), 41);
+    /// assert_eq!(flat.value_at(555.0), 1.0);
+    /// ```
+    pub fn from_fn<F: Fn(f64) -> f64>(start: f64, end: f64, step: f64, f: F) -> Spectrum {
+        assert!(step > 0.0 && end >= start, "Invalid spectrum range");
+        // rounding so that floating-point error doesn't drop the last sample
+        let n = ((end - start) / step + 1e-9).floor() as usize + 1;
+        let wavelengths: Vec<f64> = (0..n).map(|i| start + step * i as f64).collect();
+        let values = wavelengths.iter().map(|&w| f(w)).collect();
+        Spectrum {
+            wavelengths,
+            values,
+        }
+    }
+    /// Gets the spectral power distribution of a black body radiator at the given temperature in
+    /// Kelvin, using Planck's law, from 300 nm to 830 nm every 5 nm. This is normalized so that the
+    /// value at 560 nm is 100, as CIE 15 does for illuminant A.
+    pub fn blackbody(kelvin: f64) -> Spectrum {
+        // the second radiation constant, in nm K
+        let c2 = 1.4388e7;
+        let planck = |nm: f64| nm.powi(-5) / ((c2 / (nm * kelvin)).exp() - 1.0);
+        let norm = 100.0 / planck(560.0);
+        Spectrum::from_fn(300.0, 830.0, 5.0, |nm| planck(nm) * norm)
+    }
+    /// Gets the spectral power distribution of CIE daylight with the given correlated color
+    /// temperature in Kelvin, from 300 nm to 830 nm every 10 nm, using the CIE daylight basis
+    /// functions. This is only defined from 4000 K to 25000 K, and temperatures outside that range
+    /// are clamped. Like the CIE tables, this is normalized so that the value at 560 nm is 100.
+    pub fn daylight(kelvin: f64) -> Spectrum {
+        let (x, y) = daylight_xy(kelvin.clamp(4000.0, 25000.0));
+        // CIE 15 rounds the weights to three decimal places so that everyone gets the same tables
+        let m = 0.0241 + 0.2562 * x - 0.7341 * y;
+        let m1 = ((-1.3515 - 1.7703 * x + 5.9114 * y) / m * 1000.0).round() / 1000.0;
+        let m2 = ((0.0300 - 31.4424 * x + 30.0717 * y) / m * 1000.0).round() / 1000.0;
+        Spectrum {
+            wavelengths: DAYLIGHT_BASIS.iter().map(|row| row[0]).collect(),
+            values: DAYLIGHT_BASIS
+                .iter()
+                .map(|row| row[1] + m1 * row[2] + m2 * row[3])
+                .collect(),
+        }
+    }
+    /// Gets the wavelengths at which this spectrum is sampled, in nanometers.
+    pub fn wavelengths(&self) -> &[f64] {
+        &self.wavelengths
+    }
+    /// Gets the values of this spectrum at each of its wavelengths.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+    /// Gets the value of this spectrum at the given wavelength, linearly interpolating between
+    /// samples and using the nearest sample outside of the measured range. A NaN wavelength gives
+    /// NaN.
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::spectrum::Spectrum;
+    /// let spectrum = Spectrum::new(vec![400.0, 500.0, 600.0], vec![0.2, 0.4, 0.8]).unwrap();
+    /// assert!((spectrum.value_at(450.0) - 0.3).abs() <= 1e-10);
+    /// assert!((spectrum.value_at(575.0) - 0.7).abs() <= 1e-10);
+    /// assert_eq!(spectrum.value_at(380.0), 0.2);
+    /// assert_eq!(spectrum.value_at(700.0), 0.8);
+    /// ```
+    pub fn value_at(&self, wavelength: f64) -> f64 {
+        let last = self.wavelengths.len() - 1;
+        if wavelength.is_nan() {
+            f64::NAN
+        } else if wavelength <= self.wavelengths[0] {
+            self.values[0]
+        } else if wavelength >= self.wavelengths[last] {
+            self.values[last]
+        } else {
+            // the first sample past the given wavelength: this can't be the first one
+            let i = self
+                .wavelengths
+                .iter()
+                .position(|&w| w > wavelength)
+                .unwrap();
+            let (w0, w1) = (self.wavelengths[i - 1], self.wavelengths[i]);
+            let t = (wavelength - w0) / (w1 - w0);
+            self.values[i - 1] * (1.0 - t) + self.values[i] * t
+        }
+    }
+    /// Resamples this spectrum every `step` nanometers from `start` to `end`, inclusive, using
+    /// [`value_at`](#method.value_at). Panics if `step` isn't positive or `end` is less than
+    /// `start`.
+    pub fn resample(&self, start: f64, end: f64, step: f64) -> Spectrum {
+        Spectrum::from_fn(start, end, step, |w| self.value_at(w))
+    }
+    /// Gets the color of this spectrum as a light seen directly, like an LED or a monitor. This
+    /// integrates it against the CIE 1931 2° standard observer's color matching functions, and
+    /// then scales it so that Y is 1, because the units of a spectrum are arbitrary. The
+    /// coordinates are of the light as is, without adapting to it, so they're marked as being under
+    /// D65: converting them to RGB shows the light's own color, as with
+    /// [`RGBColor::from_temperature`](../color/struct.RGBColor.html#method.from_temperature). Black
+    /// gives NaN coordinates.
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::spectrum::Spectrum;
+    /// # use scarlet::color::XYZColor;
+    /// # use scarlet::illuminants::Illuminant;
+    /// // the D65 light is white on a display made for D65
+    /// let d65 = Illuminant::D65.spd().unwrap().to_xyz();
+    /// assert!((d65.x - 0.95047).abs() <= 1e-3);
+    /// assert!((d65.z - 1.08883).abs() <= 1e-3);
+    /// ```
+    pub fn to_xyz(&self) -> XYZColor {
+        self.to_xyz_with(Observer::CIE1931)
+    }
+    /// Like [`to_xyz`](#method.to_xyz), but using the given standard observer.
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::spectrum::Spectrum;
+    /// # use scarlet::cmf::Observer;
+    /// # use scarlet::illuminants::Illuminant;
+    /// let d50 = Illuminant::D50.spd().unwrap().to_xyz_with(Observer::CIE1964);
+    /// let wp = Illuminant::D50.white_point_for(Observer::CIE1964).unwrap();
+    /// assert!((d50.x - wp[0]).abs() <= 1e-4);
+    /// assert!((d50.z - wp[2]).abs() <= 1e-4);
+    /// ```
+    pub fn to_xyz_with(&self, observer: Observer) -> XYZColor {
+        let xyz = self.integrate(observer, |_| 1.0);
+        XYZColor {
+            x: xyz[0] / xyz[1],
+            y: 1.0,
+            z: xyz[2] / xyz[1],
+            illuminant: Illuminant::D65,
+        }
+    }
+    /// Gets the illuminant with the same white point as this spectrum, treating it as a light,
+    /// using the CIE 1931 2° standard observer.
+    pub fn to_illuminant(&self) -> Illuminant {
+        self.to_illuminant_with(Observer::CIE1931)
+    }
+    /// Like [`to_illuminant`](#method.to_illuminant), but using the given standard observer.
+    pub fn to_illuminant_with(&self, observer: Observer) -> Illuminant {
+        Illuminant::Custom(self.integrate(observer, |_| 1.0))
+    }
+    /// Gets the color of a surface with this spectrum as its reflectance, lit by the given light.
+    /// This integrates the product of the two against the CIE 1931 2° standard observer's color
+    /// matching functions, and scales the result so that a perfect reflector, reflecting all of the
+    /// light at every wavelength, has a Y of 1. The result is under the light's own white point, so
+    /// a perfect reflector is white.
+    /// # Example
+    ///
+    /// ```
+    /// # use scarlet::prelude::*;
+    /// # use scarlet::spectrum::Spectrum;
+    /// let gray = Spectrum::from_fn(380.0, 780.0, 10.0, |_| 0.2);
+    /// let a = Illuminant::A.spd().unwrap();
+    /// let color = gray.reflectance_to_xyz(&a);
+    /// // a flat reflectance is gray under any light, and reflects 20% of it
+    /// assert!((color.y - 0.2).abs() <= 1e-10);
+    /// let rgb: RGBColor = color.convert();
+    /// assert_eq!(rgb.int_rgb_tup(), (124, 124, 124));
+    /// ```
+    pub fn reflectance_to_xyz(&self, light: &Spectrum) -> XYZColor {
+        self.reflectance_to_xyz_with(light, Observer::CIE1931)
+    }
+    /// Like [`reflectance_to_xyz`](#method.reflectance_to_xyz), but using the given standard
+    /// observer. The result is under the light's white point for that observer.
+    pub fn reflectance_to_xyz_with(&self, light: &Spectrum, observer: Observer) -> XYZColor {
+        let white = light.integrate(observer, |_| 1.0);
+        let xyz = light.integrate(observer, |w| self.value_at(w));
+        XYZColor {
+            x: xyz[0] / white[1],
+            y: xyz[1] / white[1],
+            z: xyz[2] / white[1],
+            illuminant: Illuminant::Custom(white),
+        }
+    }
+    /// Sums this spectrum, multiplied by the given weights, against the given observer's color
+    /// matching functions at each of their wavelengths.
+    fn integrate<F: Fn(f64) -> f64>(&self, observer: Observer, weight: F) -> [f64; 3] {
+        let mut xyz = [0.0; 3];
+        for row in observer.color_matching_functions().iter() {
+            let power = self.value_at(row[0]) * weight(row[0]);
+            xyz[0] += power * row[1];
+            xyz[1] += power * row[2];
+            xyz[2] += power * row[3];
+        }
+        xyz
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    use color::{Color, RGBColor};
+
+    #[test]
+    fn test_new_errors() {
+        assert_eq!(
+            Spectrum::new(vec![400.0, 500.0], vec![1.0]),
+            Err(SpectrumError::MismatchedLengths)
+        );
+        assert_eq!(Spectrum::new(vec![], vec![]), Err(SpectrumError::Empty));
+        assert_eq!(
+            Spectrum::new(vec![500.0, 400.0], vec![1.0, 1.0]),
+            Err(SpectrumError::UnsortedWavelengths)
+        );
+        assert_eq!(
+            Spectrum::new(vec![400.0, 400.0], vec![1.0, 1.0]),
+            Err(SpectrumError::UnsortedWavelengths)
+        );
+        assert!(Spectrum::new(vec![555.0], vec![1.0]).is_ok());
+    }
+
+    #[test]
+    fn test_resample() {
+        let spectrum = Spectrum::new(vec![400.0, 500.0, 700.0], vec![0.0, 1.0, 0.0]).unwrap();
+        let resampled = spectrum.resample(380.0, 720.0, 20.0);
+        assert_eq!(resampled.wavelengths().len(), 18);
+        assert_eq!(resampled.wavelengths()[17], 720.0);
+        let expected = [
+            0.0, 0.0, 0.2, 0.4, 0.6, 0.8, 1.0, 0.9, 0.8, 0.7, 0.6, 0.5, 0.4, 0.3, 0.2, 0.1, 0.0,
+            0.0,
+        ];
+        for (value, expected) in resampled.values().iter().zip(expected.iter()) {
+            assert!((value - expected).abs() <= 1e-10);
+        }
+        // uneven steps still hit the end exactly
+        assert_eq!(
+            spectrum.resample(400.0, 700.0, 0.1).wavelengths().len(),
+            3001
+        );
+    }
+
+    #[test]
+    fn test_value_at_nan() {
+        let spectrum = Spectrum::new(vec![400.0, 500.0, 700.0], vec![0.0, 1.0, 0.0]).unwrap();
+        assert!(spectrum.value_at(f64::NAN).is_nan());
+        assert_eq!(spectrum.value_at(f64::INFINITY), 0.0);
+    }
+
+    #[test]
+    fn test_illuminant_spds() {
+        let illuminants = [
+            (Illuminant::A, 1e-4),
+            (Illuminant::D50, 1e-4),
+            (Illuminant::D55, 1e-4),
+            // the official D65 table differs slightly from what the basis functions give
+            (Illuminant::D65, 1e-3),
+            (Illuminant::D75, 1e-4),
+            // the color matching functions are only equal-energy normalized at 1 nm steps
+            (Illuminant::E, 5e-4),
+            (Illuminant::B, 2e-4),
+            (Illuminant::C, 2e-4),
+            // the fluorescent lamps have sharp emission lines, which makes their white points more
+            // sensitive to how the tables were sampled and rounded
+            (Illuminant::F1, 2e-3),
+            (Illuminant::F2, 2e-4),
+            (Illuminant::F3, 2e-3),
+            (Illuminant::F4, 2e-3),
+            (Illuminant::F5, 2e-3),
+            (Illuminant::F6, 2e-3),
+            (Illuminant::F7, 2e-4),
+            (Illuminant::F8, 2e-3),
+            (Illuminant::F9, 2e-3),
+            // F10's table matches its 10° white point, but is about 0.001 off in y from the
+            // tabulated 2° one
+            (Illuminant::F10, 7e-3),
+            (Illuminant::F11, 5e-4),
+            (Illuminant::F12, 2e-3),
+        ];
+        for &(illuminant, precision) in illuminants.iter() {
+            let spd = illuminant.spd().unwrap();
+            assert!((spd.value_at(560.0) - 100.0).abs() <= 0.1);
+            let xyz = spd.to_xyz();
+            let wp = illuminant.white_point();
+            assert!((xyz.x - wp[0]).abs() <= precision, "{:?}", illuminant);
+            assert!((xyz.z - wp[2]).abs() <= precision, "{:?}", illuminant);
+        }
+        // the 10° observer should give the 10° white points
+        for &(illuminant, precision) in illuminants.iter() {
+            let xyz = illuminant.spd().unwrap().to_xyz_with(Observer::CIE1964);
+            let wp = illuminant.white_point_for(Observer::CIE1964).unwrap();
+            let precision = precision.max(1e-3);
+            assert!((xyz.x - wp[0]).abs() <= precision, "{:?}", illuminant);
+            assert!((xyz.z - wp[2]).abs() <= precision, "{:?}", illuminant);
+        }
+        assert_eq!(Illuminant::Custom([1.0, 1.0, 1.0]).spd(), None);
+    }
+
+    #[test]
+    fn test_reflectance() {
+        let white = Spectrum::from_fn(360.0, 830.0, 5.0, |_| 1.0);
+        let d50 = Illuminant::D50.spd().unwrap();
+        let xyz = white.reflectance_to_xyz(&d50);
+        assert_eq!(xyz.illuminant, d50.to_illuminant());
+        let wp = xyz.illuminant.white_point();
+        assert!((xyz.x - wp[0]).abs() <= 1e-10);
+        assert!((xyz.y - 1.0).abs() <= 1e-10);
+        assert!((xyz.z - wp[2]).abs() <= 1e-10);
+        // the two observers disagree on colors, but both see white as white
+        let green =
+            Spectrum::new(vec![490.0, 500.0, 560.0, 570.0], vec![0.0, 0.8, 0.8, 0.0]).unwrap();
+        let white_10 = white.reflectance_to_xyz_with(&d50, Observer::CIE1964);
+        assert_eq!(
+            white_10.illuminant,
+            d50.to_illuminant_with(Observer::CIE1964)
+        );
+        assert!((white_10.y - 1.0).abs() <= 1e-10);
+        let green_2 = green.reflectance_to_xyz(&d50);
+        let green_10 = green.reflectance_to_xyz_with(&d50, Observer::CIE1964);
+        assert!((green_2.x - green_10.x).abs() >= 0.01);
+        // a surface that only reflects long wavelengths is red under any light
+        let red = Spectrum::new(vec![590.0, 610.0], vec![0.0, 0.9]).unwrap();
+        for light in [d50, Illuminant::A.spd().unwrap()].iter() {
+            let rgb: RGBColor = red.reflectance_to_xyz(light).convert();
+            assert!(rgb.r > 0.5 && rgb.g < 0.3 && rgb.b < 0.3);
+        }
+    }
+}
@@ -0,0 +1,399 @@
+//! This module describes transfer functions, which convert between the linear light that color
+//! science works with and the nonlinear values that images and video store. Storing values
+//! nonlinearly spends more of the available precision on dark colors, where vision is most sensitive,
+//! and so almost every RGB space has a transfer function of some kind.
+//!
+//! The [`TransferFunction`](trait.TransferFunction.html) trait describes them, and this module
+//! implements the common ones: the sRGB curve, pure power functions, ROMM RGB's curve with its flare
+//! correction, the BT.709 and BT.2020 camera curves, the BT.1886 display curve, and the two HDR
+//! curves, PQ and HLG. Any of these can describe a custom RGB space through
+//! [`RgbSpace`](../colors/customrgbcolor/trait.RgbSpace.html).
+//!
+//! Linear light is relative: 1 is the reference white, the brightest white of standard dynamic
+//! range content. HDR transfer functions can go well above 1 for highlights.
+
+/// A transfer function, which converts between encoded component values and linear light. The two
+/// methods should be inverses of each other. Colors outside of a gamut have components below 0 or
+/// above 1, so implementations should handle those as well: the ones in Scarlet mirror the curve for
+/// negative values.
+///
+/// # Example
+/// PQ can store light far brighter than SDR white.
+///
+/// ```
+/// # use scarlet::transfer::{TransferFunction, PQ, SRGB};
+/// let pq = PQ::default();
+/// // 1000 nit highlights, with SDR white at 203 nits
+/// let highlight = pq.encode(1000.0 / 203.0);
+/// assert!((highlight - 0.7518).abs() < 1e-4);
+/// assert!((pq.decode(highlight) - 1000.0 / 203.0).abs() < 1e-9);
+/// // but that's far past the top of sRGB, which is SDR white
+/// assert!((SRGB.encode(1.0) - 1.0).abs() < 1e-12);
+/// ```
+pub trait TransferFunction {
+    /// Converts an encoded value into linear light, relative to reference white. This is the
+    /// electro-optical transfer function, or EOTF, for display curves, and the inverse of the
+    /// opto-electronic transfer function, or OETF, for camera curves.
+    fn decode(&self, value: f64) -> f64;
+    /// Converts linear light, relative to reference white, into an encoded value. This is the
+    /// inverse of [`decode`](#tymethod.decode).
+    fn encode(&self, light: f64) -> f64;
+}
+
+/// The sRGB transfer function, which is a power function with exponent 2.4 with a short linear
+/// segment near black, approximating a gamma of 2.2 overall. This is what
+/// [`RGBColor`](../color/struct.RGBColor.html) and Display P3 use. The linear segment is extended
+/// for negative values.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SRGB;
+
+impl TransferFunction for SRGB {
+    fn decode(&self, value: f64) -> f64 {
+        if value <= 0.04045 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    }
+    fn encode(&self, light: f64) -> f64 {
+        if light <= 0.0031308 {
+            12.92 * light
+        } else {
+            1.055 * light.powf(1.0 / 2.4) - 0.055
+        }
+    }
+}
+
+/// A pure power function with the given exponent, which is applied when decoding: linear light is
+/// the encoded value raised to that power. Adobe RGB uses 563/256, about 2.2, and DCI-P3 uses 2.6.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gamma(pub f64);
+
+impl TransferFunction for Gamma {
+    fn decode(&self, value: f64) -> f64 {
+        value.signum() * value.abs().powf(self.0)
+    }
+    fn encode(&self, light: f64) -> f64 {
+        light.signum() * light.abs().powf(1.0 / self.0)
+    }
+}
+
+/// The transfer function of ROMM RGB, also known as ProPhoto RGB: a power function with exponent
+/// 1.8 and a short linear segment near black, followed by the flare correction from the
+/// specification, which keeps black slightly above 0 when encoding. The linear segments are
+/// extended for negative values.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ROMM;
+
+impl TransferFunction for ROMM {
+    fn decode(&self, value: f64) -> f64 {
+        // undo the flare correction first: its cutoff is where the flare correction sends 2^-5, the
+        // cutoff of the power function
+        let flared = if value >= 0.005419340625 {
+            ((value - 0.003473) / 0.996527).powf(1.0 / 1.8)
+        } else {
+            (value - 0.003473) / 0.0622829
+        };
+        if flared >= 0.03125 {
+            flared.powf(1.8)
+        } else {
+            flared / 16.0
+        }
+    }
+    fn encode(&self, light: f64) -> f64 {
+        // the specification truncates the cutoff, but 2^-9 is exact and continuous
+        let gamma = if light < (2.0f64).powf(-9.0) {
+            light * 16.0
+        } else {
+            light.powf(1.0 / 1.8)
+        };
+        if gamma < 0.03125 {
+            0.003473 + 0.0622829 * gamma
+        } else {
+            0.003473 + 0.996527 * gamma.powf(1.8)
+        }
+    }
+}
+
+/// The BT.709 camera curve, or OETF, which turns scene light into a code value. Decoding with this
+/// recovers scene light, which is right for processing footage, but not for reproducing what a
+/// display shows: that's [`BT1886`](struct.BT1886.html). The two aren't inverses of each other on
+/// purpose, as the mismatch boosts contrast for dim viewing environments.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BT709;
+
+impl TransferFunction for BT709 {
+    fn decode(&self, value: f64) -> f64 {
+        if value.abs() < 0.081 {
+            value / 4.5
+        } else {
+            value.signum() * ((value.abs() + 0.099) / 1.099).powf(1.0 / 0.45)
+        }
+    }
+    fn encode(&self, light: f64) -> f64 {
+        if light.abs() < 0.018 {
+            4.5 * light
+        } else {
+            light.signum() * (1.099 * light.abs().powf(0.45) - 0.099)
+        }
+    }
+}
+
+// the constants of the BT.2020 OETF, given more precisely than in BT.709 so that 12-bit video is
+// accurate: these are the values that make both pieces meet smoothly
+const BT2020_ALPHA: f64 = 1.09929682680944;
+const BT2020_BETA: f64 = 0.018053968510807;
+
+/// The BT.2020 camera curve, which is the same as [`BT709`](struct.BT709.html) with more precise
+/// constants.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BT2020;
+
+impl TransferFunction for BT2020 {
+    fn decode(&self, value: f64) -> f64 {
+        if value.abs() < 4.5 * BT2020_BETA {
+            value / 4.5
+        } else {
+            value.signum() * ((value.abs() + BT2020_ALPHA - 1.0) / BT2020_ALPHA).powf(1.0 / 0.45)
+        }
+    }
+    fn encode(&self, light: f64) -> f64 {
+        if light.abs() < BT2020_BETA {
+            4.5 * light
+        } else {
+            light.signum() * (BT2020_ALPHA * light.abs().powf(0.45) - (BT2020_ALPHA - 1.0))
+        }
+    }
+}
+
+/// The exponent of the BT.1886 EOTF.
+const BT1886_GAMMA: f64 = 2.4;
+
+/// The BT.1886 display curve, the reference EOTF for both Rec. 709 and Rec. 2020, which gives the
+/// light a display emits for a code value. `black` is the luminance of the display's black relative
+/// to its white, which is 0 for an ideal display: with that, this is a plain power function with
+/// exponent 2.4. Any other black level lifts the whole curve, so code value 0 gives that black.
+/// # Example
+///
+/// ```
+/// # use scarlet::transfer::{TransferFunction, BT1886};
+/// assert_eq!(BT1886::default().decode(1.0), 1.0);
+/// // a display with a bit of backlight bleed shows code value 0 as its black
+/// let lcd = BT1886{black: 0.001};
+/// assert!((lcd.decode(0.0) - 0.001).abs() < 1e-12);
+/// assert!((lcd.decode(1.0) - 1.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BT1886 {
+    /// The relative luminance of the display's black, between 0 and 1.
+    pub black: f64,
+}
+
+impl BT1886 {
+    /// Computes the gain `a` and black lift `b` from the standard, for a display with white 1.
+    fn constants(&self) -> (f64, f64) {
+        let black_root = self.black.powf(1.0 / BT1886_GAMMA);
+        let a = (1.0 - black_root).powf(BT1886_GAMMA);
+        let b = black_root / (1.0 - black_root);
+        (a, b)
+    }
+}
+
+impl Default for BT1886 {
+    /// An ideal display, with a black level of 0.
+    fn default() -> BT1886 {
+        BT1886 { black: 0.0 }
+    }
+}
+
+impl TransferFunction for BT1886 {
+    fn decode(&self, value: f64) -> f64 {
+        let (a, b) = self.constants();
+        let lifted = value + b;
+        a * lifted.signum() * lifted.abs().powf(BT1886_GAMMA)
+    }
+    fn encode(&self, light: f64) -> f64 {
+        let (a, b) = self.constants();
+        let scaled = light / a;
+        scaled.signum() * scaled.abs().powf(1.0 / BT1886_GAMMA) - b
+    }
+}
+
+// the constants of the PQ curve, from SMPTE ST 2084
+const PQ_M1: f64 = 2610.0 / 16384.0;
+const PQ_M2: f64 = 2523.0 / 4096.0 * 128.0;
+const PQ_C1: f64 = 3424.0 / 4096.0;
+const PQ_C2: f64 = 2413.0 / 4096.0 * 32.0;
+const PQ_C3: f64 = 2392.0 / 4096.0 * 32.0;
+/// The luminance that a PQ value of 1 gives, in cd/m².
+const PQ_PEAK: f64 = 10000.0;
+
+/// The perceptual quantizer curve from SMPTE ST 2084, used by HDR10 and Dolby Vision. Unlike other
+/// curves, PQ encodes absolute luminance, from 0 to 10,000 cd/m², so converting it to relative light
+/// needs the luminance of reference white, in cd/m². The default is 203 cd/m², the value that
+/// ITU-R BT.2408 recommends for HDR production.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PQ {
+    /// The luminance of reference white, in cd/m².
+    pub reference_white: f64,
+}
+
+impl Default for PQ {
+    /// Reference white at 203 cd/m².
+    fn default() -> PQ {
+        PQ {
+            reference_white: 203.0,
+        }
+    }
+}
+
+impl TransferFunction for PQ {
+    fn decode(&self, value: f64) -> f64 {
+        let root = value.abs().powf(1.0 / PQ_M2);
+        let normalized = ((root - PQ_C1).max(0.0) / (PQ_C2 - PQ_C3 * root)).powf(1.0 / PQ_M1);
+        value.signum() * normalized * PQ_PEAK / self.reference_white
+    }
+    fn encode(&self, light: f64) -> f64 {
+        let normalized = (light.abs() * self.reference_white / PQ_PEAK).powf(PQ_M1);
+        light.signum() * ((PQ_C1 + PQ_C2 * normalized) / (1.0 + PQ_C3 * normalized)).powf(PQ_M2)
+    }
+}
+
+// the constants of the HLG OETF, from ITU-R BT.2100
+const HLG_A: f64 = 0.17883277;
+const HLG_B: f64 = 0.28466892;
+const HLG_C: f64 = 0.55991073;
+
+/// The hybrid log-gamma curve from ITU-R BT.2100, used for HDR broadcast. HLG is relative to the
+/// display: decoding goes through the inverse of the HLG OETF to get scene light, and then the HLG
+/// OOTF, which raises scene light to a system gamma that depends on the display's peak luminance
+/// and scales it to that peak. The result is relative to reference white, so both luminances are in
+/// cd/m². The default is a 1000 cd/m² display with reference white at 203 cd/m², where a code value
+/// of 0.75 is reference white.
+///
+/// BT.2100 applies the OOTF to the luminance of a color, not to each component: doing it per
+/// component, as a transfer function has to, gives the same result for neutral colors but slightly
+/// more saturation for bright, saturated ones.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HLG {
+    /// The peak luminance of the display, in cd/m².
+    pub peak_luminance: f64,
+    /// The luminance of reference white, in cd/m².
+    pub reference_white: f64,
+}
+
+impl HLG {
+    /// The system gamma for the display's peak luminance, from BT.2100.
+    fn system_gamma(&self) -> f64 {
+        1.2 + 0.42 * (self.peak_luminance / 1000.0).log10()
+    }
+}
+
+impl Default for HLG {
+    /// A 1000 cd/m² display with reference white at 203 cd/m².
+    fn default() -> HLG {
+        HLG {
+            peak_luminance: 1000.0,
+            reference_white: 203.0,
+        }
+    }
+}
+
+impl TransferFunction for HLG {
+    fn decode(&self, value: f64) -> f64 {
+        let v = value.abs();
+        let scene = if v <= 0.5 {
+            v * v / 3.0
+        } else {
+            (((v - HLG_C) / HLG_A).exp() + HLG_B) / 12.0
+        };
+        value.signum() * scene.powf(self.system_gamma()) * self.peak_luminance
+            / self.reference_white
+    }
+    fn encode(&self, light: f64) -> f64 {
+        let scene = (light.abs() * self.reference_white / self.peak_luminance)
+            .powf(1.0 / self.system_gamma());
+        let v = if scene <= 1.0 / 12.0 {
+            (3.0 * scene).sqrt()
+        } else {
+            HLG_A * (12.0 * scene - HLG_B).ln() + HLG_C
+        };
+        light.signum() * v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    fn check_inverse<T: TransferFunction>(transfer: T) {
+        for &x in &[
+            -0.5, -0.01, 0.0, 0.001, 0.01, 0.018, 0.05, 0.2, 0.5, 0.75, 0.9, 1.0,
+        ] {
+            assert!((transfer.decode(transfer.encode(x)) - x).abs() <= 1e-10);
+            // PQ encodes 0 as slightly more than 0, so this can't be as strict
+            assert!((transfer.encode(transfer.decode(x)) - x).abs() <= 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_inverses() {
+        check_inverse(SRGB);
+        check_inverse(Gamma(2.2));
+        check_inverse(ROMM);
+        check_inverse(BT709);
+        check_inverse(BT2020);
+        check_inverse(BT1886::default());
+        check_inverse(BT1886 { black: 0.01 });
+        check_inverse(PQ::default());
+        check_inverse(PQ {
+            reference_white: 100.0,
+        });
+        check_inverse(HLG::default());
+        check_inverse(HLG {
+            peak_luminance: 2000.0,
+            reference_white: 300.0,
+        });
+    }
+
+    #[test]
+    fn test_white_and_black() {
+        assert!((SRGB.decode(1.0) - 1.0).abs() <= 1e-12);
+        assert_eq!(Gamma(2.6).decode(1.0), 1.0);
+        assert!((ROMM.encode(1.0) - 1.0).abs() <= 1e-12);
+        assert!((BT709.encode(1.0) - 1.0).abs() <= 1e-12);
+        assert!((BT2020.encode(1.0) - 1.0).abs() <= 1e-12);
+        assert!((BT1886 { black: 0.01 }.decode(1.0) - 1.0).abs() <= 1e-12);
+        assert_eq!(PQ::default().decode(0.0), 0.0);
+        assert_eq!(HLG::default().decode(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_pq() {
+        // 100 cd/m² is about 0.508 and the top of the curve is 10,000 cd/m²
+        let pq = PQ {
+            reference_white: 1.0,
+        };
+        assert!((pq.encode(100.0) - 0.5081).abs() <= 1e-4);
+        assert!((pq.decode(1.0) - 10000.0).abs() <= 1e-6);
+        // 203 cd/m² is reference white, which is about 0.58
+        assert!((PQ::default().encode(1.0) - 0.5807).abs() <= 1e-4);
+    }
+
+    #[test]
+    fn test_hlg() {
+        // BT.2408: reference white is at 75% on a 1000 cd/m² display
+        let hlg = HLG::default();
+        assert!((hlg.decode(0.75) - 1.0).abs() <= 2e-3);
+        assert!((hlg.decode(1.0) - 1000.0 / 203.0).abs() <= 1e-6);
+        // the two pieces of the curve meet at 0.5
+        assert!((hlg.encode(hlg.decode(0.5 - 1e-12)) - 0.5).abs() <= 1e-9);
+        // a brighter display has a higher system gamma
+        let bright = HLG {
+            peak_luminance: 2000.0,
+            reference_white: 203.0,
+        };
+        assert!(bright.system_gamma() > hlg.system_gamma());
+    }
+}
@@ -1,40 +1,56 @@
 //! This file implements a rather complex and involved function: one that finds the closest color
-//! visible by the human eye to a given color.
-use color::XYZColor;
+//! visible by the human eye to a given color. Real colors are bounded by the *spectral locus*, the
+//! chromaticities of pure single-wavelength light, closed off by the line of purples between the
+//! two ends. This is computed once from the CIE 1931 color matching functions and then cached.
+use super::geo::prelude::*;
+use super::geo::{Closest, LineString, Point, Polygon};
+use cmf::CIE_1931_2_DEGREE;
+use color::{Color, XYZColor};
+use colors::uvprimecolor::UVPrimeColor;
 use illuminants::Illuminant;
 
-use super::csv;
-
-use std::error::Error;
-use std::path::Path;
+lazy_static! {
+    // the spectral locus in CIE 1976 UCS, where distances between chromaticities are more even than
+    // in CIE 1931 xy
+    static ref SPECTRAL_LOCUS: Polygon<f64> = {
+        let mut uv_data: Vec<(f64, f64)> = CIE_1931_2_DEGREE
+            .iter()
+            .map(|row| {
+                let uv = UVPrimeColor::from_xyz(XYZColor {
+                    x: row[1],
+                    y: row[2],
+                    z: row[3],
+                    illuminant: Illuminant::D50,
+                });
+                (uv.u, uv.v)
+            })
+            .collect();
+        // close off the polygon with the line of purples: geo doesn't do this for us
+        let first = uv_data[0];
+        uv_data.push(first);
+        let line: LineString<f64> = uv_data.into();
+        Polygon::new(line, vec![])
+    };
+}
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Record {
-    wavelength: u16,
-    xbar: f64,
-    ybar: f64,
-    zbar: f64,
+/// Returns `Some(true)` if the given chromaticity lies outside of the spectral locus, or `None` if
+/// the chromaticity isn't a finite number.
+pub(crate) fn is_outside_locus(u: f64, v: f64) -> Option<bool> {
+    if !u.is_finite() || !v.is_finite() {
+        return None;
+    }
+    Some(!SPECTRAL_LOCUS.contains(&Point::new(u, v)))
 }
 
-// first, read in spectral color data
-pub fn read_cie_spectral_data() -> (Vec<u16>, Vec<XYZColor>) {
-    let mut wavelengths = vec![];
-    let mut xyz_data = vec![];
-    let path = Path::new("cie-1931-standard-matching.csv");
-    let mut reader = match csv::Reader::from_path(path) {
-        Err(e) => panic!("CIE spectral data could not be read: {}", e.description()),
-        Ok(rdr) => rdr,
-    };
-    for result in reader.deserialize() {
-        // we should panic on bad data: this file is supplied by us!
-        let record: Record = result.unwrap();
-        wavelengths.push(record.wavelength);
-        xyz_data.push(XYZColor {
-            x: record.xbar,
-            y: record.ybar,
-            z: record.zbar,
-            illuminant: Illuminant::D50,
-        });
+/// Returns the closest chromaticity on the boundary of the spectral locus to the given one, or
+/// `None` if the chromaticity isn't a finite number.
+pub(crate) fn closest_on_locus(u: f64, v: f64) -> Option<(f64, f64)> {
+    if !u.is_finite() || !v.is_finite() {
+        return None;
+    }
+    // this is an annoying algorithm, so I'm using a crate instead
+    match SPECTRAL_LOCUS.exterior.closest_point(&Point::new(u, v)) {
+        Closest::Intersection(p) | Closest::SinglePoint(p) => Some((p.x(), p.y())),
+        Closest::Indeterminate => None,
     }
-    (wavelengths, xyz_data)
 }